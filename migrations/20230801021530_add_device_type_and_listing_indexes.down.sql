@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP INDEX devices_received_date_idx;
+DROP INDEX devices_device_type_id_idx;
+DROP INDEX devices_owner_id_idx;
+
+ALTER TABLE devices DROP COLUMN device_type_id;
@@ -0,0 +1,6 @@
+-- Add up migration script here
+ALTER TABLE devices ADD COLUMN device_type_id uuid;
+
+CREATE INDEX devices_owner_id_idx ON devices (owner_id);
+CREATE INDEX devices_device_type_id_idx ON devices (device_type_id);
+CREATE INDEX devices_received_date_idx ON devices (received_date);
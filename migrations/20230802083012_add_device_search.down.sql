@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP INDEX devices_barcode_trgm_idx;
+DROP INDEX devices_sn_trgm_idx;
+DROP INDEX devices_search_vector_idx;
+
+ALTER TABLE devices DROP COLUMN search_vector;
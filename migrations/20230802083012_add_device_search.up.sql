@@ -0,0 +1,14 @@
+-- Add up migration script here
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+ALTER TABLE devices ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+  setweight(to_tsvector('simple', coalesce(name, '')), 'A') ||
+  setweight(to_tsvector('simple', coalesce(sn, '')), 'A') ||
+  setweight(to_tsvector('simple', coalesce(barcode, '')), 'A') ||
+  setweight(to_tsvector('simple', coalesce(board, '')), 'B') ||
+  setweight(to_tsvector('simple', coalesce(note, '')), 'C')
+) STORED;
+
+CREATE INDEX devices_search_vector_idx ON devices USING gin (search_vector);
+CREATE INDEX devices_sn_trgm_idx ON devices USING gin (sn gin_trgm_ops);
+CREATE INDEX devices_barcode_trgm_idx ON devices USING gin (barcode gin_trgm_ops);
@@ -0,0 +1,18 @@
+-- Add up migration script here
+CREATE TABLE device_loans (
+  id uuid not null,
+  device_id uuid not null REFERENCES devices (id) ON DELETE CASCADE,
+  borrower_id uuid not null REFERENCES users (id),
+  lent_by uuid not null REFERENCES users (id),
+  checked_out_at timestamptz not null,
+  expected_return_at timestamptz not null,
+  returned_at timestamptz,
+  returned_by uuid REFERENCES users (id),
+  note text,
+  PRIMARY KEY(id)
+);
+
+-- A device can only be lent to one user at a time
+CREATE UNIQUE INDEX device_loans_open_loan_idx ON device_loans (device_id) WHERE returned_at IS NULL;
+CREATE INDEX device_loans_device_id_idx ON device_loans (device_id, checked_out_at);
+CREATE INDEX device_loans_borrower_id_idx ON device_loans (borrower_id);
@@ -0,0 +1,19 @@
+-- Add up migration script here
+CREATE EXTENSION IF NOT EXISTS btree_gist;
+
+CREATE TABLE device_reservations (
+  id uuid not null,
+  device_id uuid not null REFERENCES devices (id) ON DELETE CASCADE,
+  reserved_by uuid not null REFERENCES users (id),
+  starts_at timestamptz not null,
+  ends_at timestamptz not null,
+  purpose text,
+  created_at timestamptz not null,
+  PRIMARY KEY(id),
+  CONSTRAINT device_reservations_valid_window CHECK (starts_at < ends_at),
+  -- A device can't be booked twice for overlapping windows
+  CONSTRAINT device_reservations_no_overlap EXCLUDE USING gist (
+    device_id WITH =,
+    tstzrange(starts_at, ends_at) WITH &&
+  )
+);
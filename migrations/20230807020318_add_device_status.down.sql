@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP TABLE device_status_changes;
+
+DROP INDEX devices_status_idx;
+ALTER TABLE devices DROP COLUMN status_changed_at;
+ALTER TABLE devices DROP COLUMN status;
@@ -0,0 +1,17 @@
+-- Add up migration script here
+ALTER TABLE devices ADD COLUMN status varchar(32) not null DEFAULT 'received';
+ALTER TABLE devices ADD COLUMN status_changed_at timestamptz;
+
+CREATE INDEX devices_status_idx ON devices (status);
+
+CREATE TABLE device_status_changes (
+  id uuid not null,
+  device_id uuid not null REFERENCES devices (id) ON DELETE CASCADE,
+  from_status varchar(32) not null,
+  to_status varchar(32) not null,
+  changed_by uuid not null REFERENCES users (id),
+  changed_at timestamptz not null,
+  PRIMARY KEY(id)
+);
+
+CREATE INDEX device_status_changes_device_id_idx ON device_status_changes (device_id, changed_at);
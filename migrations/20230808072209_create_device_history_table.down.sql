@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE device_history;
+DROP FUNCTION reject_device_history_change;
@@ -0,0 +1,24 @@
+-- Add up migration script here
+-- The history outlives the device, so there is no foreign key on `device_id`
+CREATE TABLE device_history (
+  id uuid not null,
+  device_id uuid not null,
+  action varchar(32) not null,
+  changed_by uuid not null REFERENCES users (id),
+  changed_at timestamptz not null,
+  changes jsonb not null,
+  PRIMARY KEY(id)
+);
+
+CREATE INDEX device_history_device_id_idx ON device_history (device_id, changed_at);
+
+-- The history is append-only
+CREATE FUNCTION reject_device_history_change() RETURNS trigger AS $$
+BEGIN
+  RAISE EXCEPTION 'device_history is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER device_history_append_only
+  BEFORE UPDATE OR DELETE ON device_history
+  FOR EACH ROW EXECUTE FUNCTION reject_device_history_change();
@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP INDEX devices_barcode_unique_idx;
+DROP INDEX devices_sn_unique_idx;
+
+DROP TABLE device_identifier_migration_report;
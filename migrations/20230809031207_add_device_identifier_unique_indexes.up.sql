@@ -0,0 +1,51 @@
+-- Add up migration script here
+-- Blank identifiers are treated as missing so they don't collide
+UPDATE devices SET sn = NULL WHERE btrim(sn) = '';
+UPDATE devices SET barcode = NULL WHERE btrim(barcode) = '';
+
+-- The identifiers shared by many devices stay with the first received one,
+-- they are cleared from the others and kept here to be fixed by hand
+CREATE TABLE device_identifier_migration_report(
+    device_id uuid NOT NULL,
+    identifier varchar(16) NOT NULL,
+    value varchar(128) NOT NULL,
+    kept_by uuid NOT NULL,
+    PRIMARY KEY (device_id, identifier)
+);
+
+INSERT INTO device_identifier_migration_report (device_id, identifier, value, kept_by)
+SELECT id, 'sn', sn, kept_by
+FROM (
+    SELECT id, sn,
+        first_value(id) OVER (PARTITION BY sn ORDER BY received_date NULLS LAST, id) AS kept_by
+    FROM devices
+    WHERE sn IS NOT NULL
+) shared
+WHERE id <> kept_by;
+
+INSERT INTO device_identifier_migration_report (device_id, identifier, value, kept_by)
+SELECT id, 'barcode', barcode, kept_by
+FROM (
+    SELECT id, barcode,
+        first_value(id) OVER (PARTITION BY barcode ORDER BY received_date NULLS LAST, id) AS kept_by
+    FROM devices
+    WHERE barcode IS NOT NULL
+) shared
+WHERE id <> kept_by;
+
+UPDATE devices SET sn = NULL
+WHERE id IN (SELECT device_id FROM device_identifier_migration_report WHERE identifier = 'sn');
+UPDATE devices SET barcode = NULL
+WHERE id IN (SELECT device_id FROM device_identifier_migration_report WHERE identifier = 'barcode');
+
+DO $$
+DECLARE
+    duplicated bigint := (SELECT count(*) FROM device_identifier_migration_report);
+BEGIN
+    IF duplicated > 0 THEN
+        RAISE WARNING '% duplicated device identifiers were cleared, see device_identifier_migration_report', duplicated;
+    END IF;
+END $$;
+
+CREATE UNIQUE INDEX devices_sn_unique_idx ON devices (sn);
+CREATE UNIQUE INDEX devices_barcode_unique_idx ON devices (barcode);
@@ -0,0 +1,19 @@
+-- Add up migration script here
+CREATE TABLE label_templates(
+    id uuid NOT NULL PRIMARY KEY,
+    name varchar(128) NOT NULL UNIQUE,
+    zpl text NOT NULL,
+    is_default boolean NOT NULL DEFAULT false,
+    created_at timestamptz NOT NULL DEFAULT now()
+);
+
+-- At most one template is the default one
+CREATE UNIQUE INDEX label_templates_default_idx ON label_templates (is_default) WHERE is_default;
+
+-- A 2 x 1 inch label at 203 dpi with a QR code, the name and the serial number
+INSERT INTO label_templates (id, name, zpl, is_default) VALUES (
+    'a3f1c6de-5b7e-4c1a-9d0e-2f6b8c4d7e10',
+    'default',
+    E'^XA\n^CI28\n^PW406\n^LL203\n^FO16,16^BQN,2,4^FH^FDMA,{{code}}^FS\n^FO176,24^A0N,28,28^FB214,2,0,L^FH^FD{{name}}^FS\n^FO176,104^A0N,22,22^FB214,1,0,L^FH^FD{{sn}}^FS\n^XZ\n',
+    true
+);
@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE devices DROP CONSTRAINT devices_hw_phase_fkey;
+
+DROP TABLE hw_phases;
@@ -0,0 +1,36 @@
+-- Add up migration script here
+CREATE TABLE hw_phases(
+    id uuid NOT NULL PRIMARY KEY,
+    name varchar(128) NOT NULL UNIQUE,
+    position integer NOT NULL CHECK (position > 0),
+    created_at timestamptz NOT NULL DEFAULT now(),
+    -- Reordering shifts many positions in one statement
+    CONSTRAINT hw_phases_position_key UNIQUE (position) DEFERRABLE INITIALLY DEFERRED
+);
+
+INSERT INTO hw_phases (id, name, position) VALUES
+    (gen_random_uuid(), 'Proto', 1),
+    (gen_random_uuid(), 'EVT', 2),
+    (gen_random_uuid(), 'DVT', 3),
+    (gen_random_uuid(), 'PVT', 4),
+    (gen_random_uuid(), 'MP', 5);
+
+-- Normalise the free-text phases, e.g. `dvt ` becomes `DVT`
+UPDATE devices SET hw_phase = NULL WHERE btrim(hw_phase) = '';
+UPDATE devices
+SET hw_phase = hw_phases.name
+FROM hw_phases
+WHERE lower(btrim(devices.hw_phase)) = lower(hw_phases.name);
+
+-- Keep the phases which can't be matched at the end of the order instead of
+-- losing them, they can be renamed or merged through the API
+INSERT INTO hw_phases (id, name, position)
+SELECT gen_random_uuid(), unknown.hw_phase, 5 + row_number() OVER (ORDER BY unknown.hw_phase)
+FROM (
+    SELECT DISTINCT hw_phase FROM devices
+    WHERE hw_phase IS NOT NULL AND hw_phase NOT IN (SELECT name FROM hw_phases)
+) unknown;
+
+ALTER TABLE devices
+    ADD CONSTRAINT devices_hw_phase_fkey FOREIGN KEY (hw_phase)
+    REFERENCES hw_phases (name) ON UPDATE CASCADE;
@@ -0,0 +1,5 @@
+-- Add down migration script here
+ALTER TABLE devices DROP CONSTRAINT devices_board_fkey;
+
+DROP TABLE board_migration_report;
+DROP TABLE boards;
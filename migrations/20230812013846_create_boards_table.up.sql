@@ -0,0 +1,90 @@
+-- Add up migration script here
+CREATE TABLE boards(
+    id uuid NOT NULL PRIMARY KEY,
+    name varchar(96) NOT NULL,
+    revision varchar(24) NOT NULL DEFAULT '',
+    vendor varchar(256),
+    description text,
+    -- What devices refer to, e.g. `main-board rev B`
+    full_name varchar(128) GENERATED ALWAYS AS (
+        CASE WHEN revision = '' THEN name ELSE name || ' rev ' || revision END
+    ) STORED,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    CONSTRAINT boards_full_name_key UNIQUE (full_name)
+);
+
+-- The board strings which couldn't be turned into a catalog entry, they are
+-- cleared from the devices and kept here to be fixed by hand
+CREATE TABLE board_migration_report(
+    device_id uuid NOT NULL PRIMARY KEY,
+    board varchar(128) NOT NULL,
+    reason text NOT NULL
+);
+
+UPDATE devices SET board = regexp_replace(btrim(board), '\s+', ' ', 'g');
+UPDATE devices SET board = NULL WHERE board = '';
+
+INSERT INTO board_migration_report (device_id, board, reason)
+SELECT id, board, 'not a board name' FROM devices
+WHERE board !~ '[[:alnum:]]' OR lower(board) IN ('n/a', 'na', 'none', 'null', 'tbd', 'unknown');
+
+-- Split the revision from the name, `main-board rev.b`, `main-board-revB` and
+-- `main-board r2` are all revisions of `main-board`
+CREATE TEMPORARY TABLE parsed_boards AS
+SELECT devices.id AS device_id,
+       coalesce(parsed.m[1], devices.board) AS name,
+       upper(coalesce(parsed.m[2], '')) AS revision
+FROM devices,
+     LATERAL (
+         SELECT regexp_match(
+             devices.board,
+             '^(.*?[[:alnum:]])[\s_-]+(?:rev(?:ision)?\.?|r)\s*([[:alnum:]][[:alnum:].]*)$',
+             'i'
+         ) AS m
+     ) parsed
+WHERE devices.board IS NOT NULL
+  AND devices.id NOT IN (SELECT device_id FROM board_migration_report);
+
+INSERT INTO board_migration_report (device_id, board, reason)
+SELECT devices.id, devices.board, 'the name or the revision is too long'
+FROM devices JOIN parsed_boards ON parsed_boards.device_id = devices.id
+WHERE length(parsed_boards.name) > 96 OR length(parsed_boards.revision) > 24;
+
+DELETE FROM parsed_boards
+WHERE device_id IN (SELECT device_id FROM board_migration_report);
+
+UPDATE devices SET board = NULL
+WHERE id IN (SELECT device_id FROM board_migration_report);
+
+-- Names differing in case are the same board, the most used spelling wins
+-- for every revision
+INSERT INTO boards (id, name, revision)
+SELECT gen_random_uuid(), spellings.name, revisions.revision
+FROM (SELECT DISTINCT lower(name) AS key, revision FROM parsed_boards) revisions
+JOIN (
+    SELECT lower(name) AS key, mode() WITHIN GROUP (ORDER BY name) AS name
+    FROM parsed_boards
+    GROUP BY lower(name)
+) spellings USING (key);
+
+UPDATE devices
+SET board = boards.full_name
+FROM parsed_boards, boards
+WHERE parsed_boards.device_id = devices.id
+  AND lower(boards.name) = lower(parsed_boards.name)
+  AND boards.revision = parsed_boards.revision;
+
+DROP TABLE parsed_boards;
+
+DO $$
+DECLARE
+    unmatched bigint := (SELECT count(*) FROM board_migration_report);
+BEGIN
+    IF unmatched > 0 THEN
+        RAISE WARNING '% device boards could not be matched, see board_migration_report', unmatched;
+    END IF;
+END $$;
+
+ALTER TABLE devices
+    ADD CONSTRAINT devices_board_fkey FOREIGN KEY (board)
+    REFERENCES boards (full_name) ON UPDATE CASCADE;
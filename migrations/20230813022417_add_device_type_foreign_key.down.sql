@@ -0,0 +1,6 @@
+-- Add down migration script here
+ALTER TABLE devices DROP CONSTRAINT devices_device_type_id_fkey;
+
+ALTER TABLE device_types
+    DROP CONSTRAINT device_types_name_key,
+    DROP COLUMN created_at;
@@ -0,0 +1,24 @@
+-- Add up migration script here
+
+-- Merge the types sharing a name into the oldest one by id
+UPDATE devices
+SET device_type_id = kept.id
+FROM device_types duplicate
+JOIN (SELECT min(id::text)::uuid AS id, name FROM device_types GROUP BY name) kept
+    ON kept.name = duplicate.name
+WHERE devices.device_type_id = duplicate.id AND duplicate.id <> kept.id;
+
+DELETE FROM device_types
+WHERE id NOT IN (SELECT min(id::text)::uuid FROM device_types GROUP BY name);
+
+ALTER TABLE device_types
+    ADD COLUMN created_at timestamptz NOT NULL DEFAULT now(),
+    ADD CONSTRAINT device_types_name_key UNIQUE (name);
+
+-- Nothing could create a type before, so unknown ids are dangling
+UPDATE devices SET device_type_id = NULL
+WHERE device_type_id NOT IN (SELECT id FROM device_types);
+
+ALTER TABLE devices
+    ADD CONSTRAINT devices_device_type_id_fkey FOREIGN KEY (device_type_id)
+    REFERENCES device_types (id);
@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP INDEX devices_attributes_idx;
+
+ALTER TABLE devices DROP COLUMN attributes;
+ALTER TABLE device_types DROP COLUMN attribute_schema;
@@ -0,0 +1,10 @@
+-- Add up migration script here
+-- The empty schema accepts any attributes
+ALTER TABLE device_types ADD COLUMN attribute_schema jsonb NOT NULL DEFAULT '{}';
+
+ALTER TABLE devices
+    ADD COLUMN attributes jsonb NOT NULL DEFAULT '{}'
+    CONSTRAINT devices_attributes_check CHECK (jsonb_typeof(attributes) = 'object');
+
+-- Serves the `@>` filter of the list
+CREATE INDEX devices_attributes_idx ON devices USING gin (attributes jsonb_path_ops);
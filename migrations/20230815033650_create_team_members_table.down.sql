@@ -0,0 +1,7 @@
+-- Add down migration script here
+DROP TABLE team_members;
+
+ALTER TABLE teams
+    DROP CONSTRAINT teams_name_key,
+    DROP COLUMN created_at,
+    DROP COLUMN permissions;
@@ -0,0 +1,22 @@
+-- Add up migration script here
+
+-- Team names become unique, the duplicates keep their id as a suffix
+UPDATE teams SET name = name || ' (' || id || ')'
+WHERE id NOT IN (SELECT min(id::text)::uuid FROM teams GROUP BY name);
+
+ALTER TABLE teams
+    -- The permissions granted to every member of the team
+    ADD COLUMN permissions varchar(128)[] NOT NULL DEFAULT '{}',
+    ADD COLUMN created_at timestamptz NOT NULL DEFAULT now(),
+    ADD CONSTRAINT teams_name_key UNIQUE (name);
+
+CREATE TABLE team_members(
+    team_id uuid NOT NULL REFERENCES teams (id) ON DELETE CASCADE,
+    user_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    role varchar(16) NOT NULL CHECK (role IN ('member', 'manager')),
+    created_at timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (team_id, user_id)
+);
+
+-- Serves the memberships read at login
+CREATE INDEX team_members_user_id_idx ON team_members (user_id);
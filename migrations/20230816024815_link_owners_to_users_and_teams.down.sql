@@ -0,0 +1,9 @@
+-- Add down migration script here
+ALTER TABLE devices DROP CONSTRAINT devices_owner_id_fkey;
+
+DROP TABLE owner_migration_report;
+
+ALTER TABLE owners
+    DROP CONSTRAINT owners_user_or_team_check,
+    DROP COLUMN team_id,
+    DROP COLUMN user_id;
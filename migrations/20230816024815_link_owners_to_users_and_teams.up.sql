@@ -0,0 +1,58 @@
+-- Add up migration script here
+ALTER TABLE owners
+    ADD COLUMN user_id uuid REFERENCES users (id) ON DELETE CASCADE,
+    ADD COLUMN team_id uuid REFERENCES teams (id) ON DELETE CASCADE,
+    ADD CONSTRAINT owners_user_id_key UNIQUE (user_id),
+    ADD CONSTRAINT owners_team_id_key UNIQUE (team_id),
+    -- The owners created before the link belong to neither until updated
+    ADD CONSTRAINT owners_user_or_team_check CHECK (num_nonnulls(user_id, team_id) <= 1);
+
+-- Owners were sometimes created with the id of their user or team
+UPDATE owners SET user_id = id WHERE id IN (SELECT id FROM users);
+UPDATE owners SET team_id = id WHERE user_id IS NULL AND id IN (SELECT id FROM teams);
+
+-- The devices whose owner doesn't exist, and how each one was fixed
+CREATE TABLE owner_migration_report(
+    device_id uuid NOT NULL PRIMARY KEY,
+    owner_id uuid NOT NULL,
+    resolution text NOT NULL
+);
+
+INSERT INTO owner_migration_report (device_id, owner_id, resolution)
+SELECT devices.id, devices.owner_id,
+    CASE
+        WHEN users.id IS NOT NULL THEN 'created an owner for the user'
+        WHEN teams.id IS NOT NULL THEN 'created an owner for the team'
+        ELSE 'created a placeholder owner'
+    END
+FROM devices
+LEFT JOIN users ON users.id = devices.owner_id
+LEFT JOIN teams ON teams.id = devices.owner_id
+WHERE devices.owner_id NOT IN (SELECT id FROM owners);
+
+DO $$
+DECLARE
+    orphaned bigint := (SELECT count(*) FROM owner_migration_report);
+BEGIN
+    IF orphaned > 0 THEN
+        RAISE WARNING '% devices had no owner, see owner_migration_report', orphaned;
+    END IF;
+END $$;
+
+-- Create the missing owners with the ids of the devices so they still group
+-- together, an owner id of a user or a team links to it
+INSERT INTO owners (id, name, user_id, team_id)
+SELECT orphans.owner_id,
+    coalesce(users.username, teams.name, 'unknown owner ' || orphans.owner_id),
+    users.id,
+    CASE WHEN users.id IS NULL THEN teams.id END
+FROM (
+    SELECT DISTINCT owner_id FROM devices
+    WHERE owner_id NOT IN (SELECT id FROM owners)
+) orphans
+LEFT JOIN users ON users.id = orphans.owner_id
+LEFT JOIN teams ON teams.id = orphans.owner_id;
+
+ALTER TABLE devices
+    ADD CONSTRAINT devices_owner_id_fkey FOREIGN KEY (owner_id)
+    REFERENCES owners (id);
@@ -0,0 +1,16 @@
+-- Add up migration script here
+CREATE TABLE device_attachments (
+  id uuid not null,
+  device_id uuid not null REFERENCES devices (id) ON DELETE CASCADE,
+  file_name varchar(255) not null,
+  content_type varchar(128) not null,
+  size_bytes bigint not null CHECK (size_bytes >= 0),
+  -- The key of the file in the blob storage
+  storage_key varchar(512) not null,
+  uploaded_by uuid REFERENCES users (id) ON DELETE SET NULL,
+  created_at timestamptz not null DEFAULT now(),
+  PRIMARY KEY(id),
+  CONSTRAINT device_attachments_storage_key_key UNIQUE (storage_key)
+);
+
+CREATE INDEX device_attachments_device_id_idx ON device_attachments (device_id, created_at);
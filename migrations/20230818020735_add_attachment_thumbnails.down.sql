@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE device_attachments DROP COLUMN has_thumbnails;
@@ -0,0 +1,3 @@
+-- Add up migration script here
+-- The thumbnails are stored next to the original, their keys derive from `storage_key`
+ALTER TABLE device_attachments ADD COLUMN has_thumbnails boolean not null DEFAULT false;
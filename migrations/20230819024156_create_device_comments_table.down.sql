@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE device_comment_mentions;
+DROP TABLE device_comments;
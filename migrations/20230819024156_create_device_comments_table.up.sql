@@ -0,0 +1,24 @@
+-- Add up migration script here
+CREATE TABLE device_comments (
+  id uuid not null,
+  device_id uuid not null REFERENCES devices (id) ON DELETE CASCADE,
+  -- The comments are kept when their author is deleted
+  author_id uuid REFERENCES users (id) ON DELETE SET NULL,
+  body text not null,
+  created_at timestamptz not null DEFAULT now(),
+  updated_at timestamptz,
+  PRIMARY KEY(id)
+);
+
+CREATE INDEX device_comments_device_id_idx ON device_comments (device_id, created_at);
+
+-- The users mentioned as `@username` in a comment, a mention is kept with its
+-- first `created_at` while the comment is edited so it is notified once
+CREATE TABLE device_comment_mentions (
+  comment_id uuid not null REFERENCES device_comments (id) ON DELETE CASCADE,
+  user_id uuid not null REFERENCES users (id) ON DELETE CASCADE,
+  created_at timestamptz not null DEFAULT now(),
+  PRIMARY KEY(comment_id, user_id)
+);
+
+CREATE INDEX device_comment_mentions_user_id_idx ON device_comment_mentions (user_id, created_at);
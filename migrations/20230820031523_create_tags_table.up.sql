@@ -0,0 +1,18 @@
+-- Add up migration script here
+CREATE TABLE tags (
+  id uuid not null,
+  name varchar(64) not null,
+  created_at timestamptz not null DEFAULT now(),
+  PRIMARY KEY(id),
+  CONSTRAINT tags_name_key UNIQUE (name)
+);
+
+CREATE TABLE device_tags (
+  device_id uuid not null REFERENCES devices (id) ON DELETE CASCADE,
+  tag_id uuid not null REFERENCES tags (id) ON DELETE CASCADE,
+  created_at timestamptz not null DEFAULT now(),
+  PRIMARY KEY(device_id, tag_id)
+);
+
+-- The primary key serves the tags of a device, this index the devices of a tag
+CREATE INDEX device_tags_tag_id_idx ON device_tags (tag_id, device_id);
@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE devices DROP COLUMN location_id;
+
+DROP TABLE locations;
@@ -0,0 +1,20 @@
+-- Add up migration script here
+CREATE TABLE locations (
+  id uuid not null,
+  -- A location with sub-locations can't be deleted
+  parent_id uuid REFERENCES locations (id),
+  kind varchar(16) not null,
+  name varchar(96) not null,
+  created_at timestamptz not null DEFAULT now(),
+  PRIMARY KEY(id),
+  CONSTRAINT locations_kind_check CHECK (kind IN ('site', 'room', 'cabinet', 'shelf')),
+  CONSTRAINT locations_parent_id_check CHECK (parent_id <> id)
+);
+
+-- Siblings have distinct names, the sites as well
+CREATE UNIQUE INDEX locations_sibling_name_idx
+  ON locations (coalesce(parent_id, '00000000-0000-0000-0000-000000000000'), name);
+
+ALTER TABLE devices ADD COLUMN location_id uuid REFERENCES locations (id);
+
+CREATE INDEX devices_location_id_idx ON devices (location_id);
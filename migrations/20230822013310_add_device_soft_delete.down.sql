@@ -0,0 +1,6 @@
+-- Add down migration script here
+DELETE FROM devices WHERE deleted_at IS NOT NULL;
+
+ALTER TABLE devices
+  DROP COLUMN deleted_by,
+  DROP COLUMN deleted_at;
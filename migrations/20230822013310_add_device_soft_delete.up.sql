@@ -0,0 +1,8 @@
+-- Add up migration script here
+-- A deleted device stays in the trash until it is purged
+ALTER TABLE devices
+  ADD COLUMN deleted_at timestamptz,
+  ADD COLUMN deleted_by uuid REFERENCES users (id),
+  ADD CONSTRAINT devices_deleted_by_check CHECK ((deleted_at IS NULL) = (deleted_by IS NULL));
+
+CREATE INDEX devices_deleted_at_idx ON devices (deleted_at) WHERE deleted_at IS NOT NULL;
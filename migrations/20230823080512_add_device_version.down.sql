@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP TRIGGER devices_bump_version ON devices;
+DROP FUNCTION bump_device_version;
+
+ALTER TABLE devices DROP COLUMN version;
@@ -0,0 +1,16 @@
+-- Add up migration script here
+-- The version is sent as the ETag of a device, every change of a row bumps it
+ALTER TABLE devices ADD COLUMN version integer not null default 1;
+
+CREATE FUNCTION bump_device_version() RETURNS trigger AS $$
+BEGIN
+  IF NEW IS DISTINCT FROM OLD THEN
+    NEW.version := OLD.version + 1;
+  END IF;
+  RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER devices_bump_version
+  BEFORE UPDATE ON devices
+  FOR EACH ROW EXECUTE FUNCTION bump_device_version();
@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP INDEX devices_barcode_unique_idx;
+DROP INDEX devices_sn_unique_idx;
+
+CREATE UNIQUE INDEX devices_sn_unique_idx ON devices (sn);
+CREATE UNIQUE INDEX devices_barcode_unique_idx ON devices (barcode);
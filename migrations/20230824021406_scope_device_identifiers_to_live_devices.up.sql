@@ -0,0 +1,7 @@
+-- Add up migration script here
+-- A deleted device gives up its serial number and barcode until it is restored
+DROP INDEX devices_sn_unique_idx;
+DROP INDEX devices_barcode_unique_idx;
+
+CREATE UNIQUE INDEX devices_sn_unique_idx ON devices (sn) WHERE deleted_at IS NULL;
+CREATE UNIQUE INDEX devices_barcode_unique_idx ON devices (barcode) WHERE deleted_at IS NULL;
@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP INDEX device_history_device_id_idx;
+CREATE INDEX device_history_device_id_idx ON device_history (device_id, changed_at);
+
+ALTER TABLE device_history DROP COLUMN seq;
+ALTER TABLE device_history ALTER COLUMN changed_at DROP DEFAULT;
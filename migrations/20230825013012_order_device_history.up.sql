@@ -0,0 +1,8 @@
+-- Add up migration script here
+-- The database fills the timestamp with its sub-seconds, the sequence orders
+-- the entries written at the same time
+ALTER TABLE device_history ALTER COLUMN changed_at SET DEFAULT clock_timestamp();
+ALTER TABLE device_history ADD COLUMN seq bigserial not null;
+
+DROP INDEX device_history_device_id_idx;
+CREATE INDEX device_history_device_id_idx ON device_history (device_id, changed_at, seq);
@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP INDEX device_status_changes_device_id_idx;
+CREATE INDEX device_status_changes_device_id_idx ON device_status_changes (device_id, changed_at);
+
+ALTER TABLE device_status_changes DROP COLUMN seq;
+ALTER TABLE device_status_changes ALTER COLUMN changed_at DROP DEFAULT;
@@ -0,0 +1,8 @@
+-- Add up migration script here
+-- The database fills the timestamp with its sub-seconds, the sequence orders
+-- the changes written at the same time
+ALTER TABLE device_status_changes ALTER COLUMN changed_at SET DEFAULT clock_timestamp();
+ALTER TABLE device_status_changes ADD COLUMN seq bigserial not null;
+
+DROP INDEX device_status_changes_device_id_idx;
+CREATE INDEX device_status_changes_device_id_idx ON device_status_changes (device_id, changed_at, seq);
@@ -0,0 +1,2 @@
+-- Add down migration script here
+DROP INDEX hw_phases_name_lower_key;
@@ -0,0 +1,28 @@
+-- Add up migration script here
+-- Merge the phases which only differ in case or surrounding whitespace into
+-- the first of them in the order, their devices move along
+CREATE TEMPORARY TABLE hw_phase_merges ON COMMIT DROP AS
+SELECT name, first_value(name) OVER (PARTITION BY lower(btrim(name)) ORDER BY position) AS kept
+FROM hw_phases;
+
+UPDATE devices
+SET hw_phase = hw_phase_merges.kept
+FROM hw_phase_merges
+WHERE devices.hw_phase = hw_phase_merges.name AND hw_phase_merges.name <> hw_phase_merges.kept;
+
+DELETE FROM hw_phases
+USING hw_phase_merges
+WHERE hw_phases.name = hw_phase_merges.name AND hw_phase_merges.name <> hw_phase_merges.kept;
+
+-- Close the gaps the merged phases left in the order
+UPDATE hw_phases
+SET position = ordered.position
+FROM (SELECT id, row_number() OVER (ORDER BY position) AS position FROM hw_phases) ordered
+WHERE hw_phases.id = ordered.id AND hw_phases.position <> ordered.position;
+
+-- The devices follow the trimmed names
+UPDATE hw_phases SET name = btrim(name) WHERE name <> btrim(name);
+
+SET CONSTRAINTS hw_phases_position_key IMMEDIATE;
+
+CREATE UNIQUE INDEX hw_phases_name_lower_key ON hw_phases (lower(name));
@@ -0,0 +1,2 @@
+-- Add down migration script here
+DELETE FROM teams WHERE id = 'c0a7d3e2-5b1f-4d8e-9a46-2f1e8b7c9d10';
@@ -0,0 +1,24 @@
+-- Add up migration script here
+-- Permissions only come from teams, the admin user manages the first team so
+-- a new deployment can grant the others through the API
+INSERT INTO teams (id, name, description, permissions) VALUES (
+    'c0a7d3e2-5b1f-4d8e-9a46-2f1e8b7c9d10',
+    'Administrators',
+    'Manages the catalogs, the teams and their permissions',
+    ARRAY[
+        'checkin:device', 'checkout:device', 'comment:device', 'create:device',
+        'delete:devices', 'manage:boards', 'manage:device-types', 'manage:hw-phases',
+        'manage:label-templates', 'manage:locations', 'manage:owners',
+        'manage:reservations', 'manage:tags', 'manage:teams', 'moderate:comments',
+        'override:hw-phase', 'print:labels', 'purge:devices', 'read:devices',
+        'reserve:device', 'update:device'
+    ]
+)
+ON CONFLICT DO NOTHING;
+
+INSERT INTO team_members (team_id, user_id, role)
+SELECT teams.id, users.id, 'manager'
+FROM teams, users
+WHERE teams.id = 'c0a7d3e2-5b1f-4d8e-9a46-2f1e8b7c9d10'
+  AND users.id = '391afe4c-6c47-4719-bc1f-3aca3600b8db'
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,57 @@
+use jsonschema::JSONSchema;
+
+/// Compile the attribute schema of a device type
+pub fn compile(schema: &serde_json::Value) -> Result<JSONSchema, String> {
+    if !schema.is_object() {
+        return Err("attributeSchema must be a JSON object".to_owned());
+    }
+
+    JSONSchema::compile(schema)
+        .map_err(|e| format!("attributeSchema is not a valid JSON Schema: {e}"))
+}
+
+/// Check the custom attributes of a device against the schema of its type,
+/// every violation is reported with the path of the attribute
+pub fn validate(schema: &JSONSchema, attributes: &serde_json::Value) -> Result<(), String> {
+    schema.validate(attributes).map_err(|errors| {
+        let errors: Vec<_> = errors
+            .map(|e| match e.instance_path.to_string() {
+                path if path.is_empty() => e.to_string(),
+                path => format!("{path}: {e}"),
+            })
+            .collect();
+
+        format!("invalid attributes: {}", errors.join("; "))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compile, validate};
+
+    #[test]
+    fn validate_reports_every_invalid_attribute() {
+        let schema = compile(&serde_json::json!({
+            "type": "object",
+            "properties": {
+                "imei": { "type": "string", "pattern": "^[0-9]{15}$" },
+                "ramGb": { "type": "integer", "minimum": 1 },
+            },
+            "required": ["imei"],
+        }))
+        .unwrap();
+
+        assert!(validate(&schema, &serde_json::json!({ "imei": "490154203237518" })).is_ok());
+
+        let error =
+            validate(&schema, &serde_json::json!({ "imei": "4901", "ramGb": 0 })).unwrap_err();
+        assert!(error.contains("/imei: "), "{error}");
+        assert!(error.contains("/ramGb: "), "{error}");
+    }
+
+    #[test]
+    fn compile_rejects_invalid_schemas() {
+        assert!(compile(&serde_json::json!(true)).is_err());
+        assert!(compile(&serde_json::json!({ "type": "no-such-type" })).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use secrecy::Secret;
 use sqlx::{
     postgres::{PgConnectOptions, PgSslMode},
     ConnectOptions,
@@ -5,12 +6,15 @@ use sqlx::{
 
 /// A data structure that contains other settings
 /// including `ApplicationSettings`, `DatabaseSettings`,
-/// and `JwtSettings`
+/// `JwtSettings`, `PrinterSettings`, `AttachmentSettings` and `TrashSettings`
 #[derive(Debug, serde::Deserialize)]
 pub struct Settings {
     pub application: ApplicationSettings,
     pub database: DatabaseSettings,
     pub jwt_secret: JwtSettings,
+    pub printer: PrinterSettings,
+    pub attachments: AttachmentSettings,
+    pub trash: TrashSettings,
 }
 
 /// A data structure that contains host and port
@@ -63,6 +67,61 @@ pub struct JwtSettings {
     pub secret_key: String,
 }
 
+/// A data structure contains where the thermal label printer listens for
+/// raw ZPL, usually port 9100
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct PrinterSettings {
+    pub host: String,
+    pub port: u16,
+    pub timeout_milliseconds: u64,
+}
+
+/// A data structure contains the limits of the files attached to devices and
+/// where they are stored
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct AttachmentSettings {
+    pub max_size_bytes: usize,
+    /// The accepted media types without parameters, such as `image/png`
+    pub allowed_content_types: Vec<String>,
+    pub storage: StorageSettings,
+}
+
+impl AttachmentSettings {
+    /// Whether a media type is accepted, its parameters such as `charset`
+    /// are ignored
+    pub fn allows(&self, content_type: &str) -> bool {
+        let essence = content_type.split(';').next().unwrap_or_default().trim();
+
+        self.allowed_content_types
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(essence))
+    }
+}
+
+/// The backend storing the attachment files, selected by `backend`
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(tag = "backend", rename_all = "lowercase")]
+pub enum StorageSettings {
+    /// A directory of the local filesystem
+    Local { directory: std::path::PathBuf },
+    /// A bucket of an S3-compatible service such as MinIO, addressed by path
+    /// so any endpoint works without wildcard DNS
+    S3 {
+        endpoint: String,
+        bucket: String,
+        region: String,
+        access_key_id: String,
+        secret_access_key: Secret<String>,
+    },
+}
+
+/// A data structure contains how long the deleted devices are kept before
+/// they can be purged
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct TrashSettings {
+    pub retention_days: u32,
+}
+
 /// An enum that indicate which environment we want to run
 pub enum Environment {
     Local,
@@ -0,0 +1,152 @@
+use anyhow::Context;
+
+use crate::models::device::Device;
+
+/// The columns of an exported device, the names match the columns read by
+/// [`crate::csv_import::parse_devices`] so an export can be imported again.
+/// Every field of a device is exported, `attributes` as a JSON object.
+const COLUMNS: [&str; 14] = [
+    "id",
+    "name",
+    "owner",
+    "board",
+    "sn",
+    "barcode",
+    "received_date",
+    "hw_phase",
+    "note",
+    "device_type_id",
+    "attributes",
+    "status",
+    "status_changed_at",
+    "location_id",
+];
+
+/// The header line of an exported csv file
+pub fn header() -> anyhow::Result<Vec<u8>> {
+    write_record(COLUMNS)
+}
+
+/// A line of an exported csv file, null fields are left empty
+pub fn device_record(device: &Device) -> anyhow::Result<Vec<u8>> {
+    let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+
+    write_record([
+        device.id.to_string(),
+        device.name.clone(),
+        device.owner_id.to_string(),
+        optional(&device.board),
+        optional(&device.sn),
+        optional(&device.barcode),
+        device
+            .received_date
+            .map(|date| date.to_rfc3339())
+            .unwrap_or_default(),
+        optional(&device.hw_phase),
+        optional(&device.note),
+        device
+            .device_type_id
+            .map(|id| id.to_string())
+            .unwrap_or_default(),
+        device.attributes.to_string(),
+        device.status.to_string(),
+        device
+            .status_changed_at
+            .map(|date| date.to_rfc3339())
+            .unwrap_or_default(),
+        device
+            .location_id
+            .map(|id| id.to_string())
+            .unwrap_or_default(),
+    ])
+}
+
+fn write_record<I, T>(record: I) -> anyhow::Result<Vec<u8>>
+where
+    I: IntoIterator<Item = T>,
+    T: AsRef<[u8]>,
+{
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer
+        .write_record(record)
+        .context("Failed to write a csv record")?;
+
+    writer.into_inner().context("Failed to flush a csv record")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::{device_record, header, COLUMNS};
+    use crate::csv_import::parse_devices;
+    use crate::models::device::Device;
+    use crate::models::device_status::DeviceStatus;
+
+    fn device() -> Device {
+        Device {
+            id: uuid::Uuid::new_v4(),
+            name: "phone, rev \"B\"".to_owned(),
+            owner_id: uuid::Uuid::new_v4(),
+            board: Some("main-board".to_owned()),
+            sn: Some("SN-1".to_owned()),
+            barcode: None,
+            received_date: Some(Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap()),
+            hw_phase: Some("EVT".to_owned()),
+            note: Some("first line\nsecond line".to_owned()),
+            device_type_id: None,
+            attributes: serde_json::json!({}),
+            status: DeviceStatus::InInventory,
+            status_changed_at: None,
+            location_id: None,
+            version: 1,
+        }
+    }
+
+    #[test]
+    fn exported_devices_contain_every_field() {
+        let device = serde_json::to_value(device()).unwrap();
+        // The owner column is named like the column of the import
+        let mut fields: Vec<String> = device
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(|key| match key.as_str() {
+                "ownerId" => "owner".to_owned(),
+                key => key.chars().fold(String::new(), |mut column, c| {
+                    if c.is_ascii_uppercase() {
+                        column.push('_');
+                    }
+                    column.push(c.to_ascii_lowercase());
+                    column
+                }),
+            })
+            .collect();
+        fields.sort();
+        let mut columns = COLUMNS.to_vec();
+        columns.sort();
+
+        assert_eq!(fields, columns);
+        assert_eq!(
+            String::from_utf8(header().unwrap()).unwrap(),
+            format!("{}\n", COLUMNS.join(","))
+        );
+    }
+
+    #[test]
+    fn exported_devices_can_be_imported_again() {
+        let device = device();
+
+        let mut content = header().unwrap();
+        content.extend(device_record(&device).unwrap());
+        let parsed = parse_devices(&content).unwrap();
+
+        assert!(parsed.errors.is_empty());
+        let imported = &parsed.devices[0].request;
+        assert_eq!(imported.name, device.name);
+        assert_eq!(imported.owner_id, device.owner_id);
+        assert_eq!(imported.barcode, None);
+        assert_eq!(imported.received_date, device.received_date);
+        assert_eq!(imported.note, device.note);
+    }
+}
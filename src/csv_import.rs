@@ -0,0 +1,245 @@
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::models::device::{CreateDeviceRequest, MAX_FIELD_LENGTH, MAX_NAME_LENGTH};
+use crate::models::device_import::ImportRowError;
+
+const REQUIRED_COLUMNS: [&str; 2] = ["name", "owner"];
+
+/// A valid row of a csv file
+#[derive(Debug)]
+pub struct ParsedDevice {
+    /// The line number the row starts at, the header is line 1
+    pub row: u64,
+    pub request: CreateDeviceRequest,
+}
+
+/// The result of parsing a csv file, every row is either a device or errors
+#[derive(Debug, Default)]
+pub struct ParsedDevices {
+    pub total_rows: usize,
+    pub devices: Vec<ParsedDevice>,
+    pub errors: Vec<ImportRowError>,
+}
+
+/// Parse a csv file of devices.
+///
+/// The header maps the columns to the device fields and is case insensitive:
+/// `name`, `board`, `sn`, `barcode`, `received_date`, `hw_phase`, `note`
+/// and `owner` (the owner id). Unknown columns are ignored. Every invalid
+/// cell is reported instead of stopping at the first error.
+pub fn parse_devices(content: &[u8]) -> Result<ParsedDevices, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(content);
+
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| format!("failed to read the header: {e}"))?
+        .iter()
+        .map(|header| header.to_lowercase())
+        .collect();
+
+    let missing: Vec<_> = REQUIRED_COLUMNS
+        .iter()
+        .filter(|column| !headers.iter().any(|header| header == *column))
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!(
+            "missing required columns: {}",
+            missing
+                .iter()
+                .map(|column| column.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    let mut parsed = ParsedDevices::default();
+    for record in reader.records() {
+        parsed.total_rows += 1;
+
+        // A quoted cell can span several lines, so the row is the line the
+        // record starts at rather than a count of the records
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                parsed.errors.push(ImportRowError {
+                    row: e.position().map_or(0, |position| position.line()),
+                    column: None,
+                    message: e.to_string(),
+                });
+                continue;
+            }
+        };
+        let row = record.position().map_or(0, |position| position.line());
+
+        let cell = |column: &str| {
+            headers
+                .iter()
+                .position(|header| header == column)
+                .and_then(|i| record.get(i))
+                .filter(|value| !value.is_empty())
+                .map(|value| value.to_owned())
+        };
+
+        let mut row_errors = vec![];
+        let mut error = |column: &str, message: String| {
+            row_errors.push(ImportRowError {
+                row,
+                column: Some(column.to_owned()),
+                message,
+            })
+        };
+
+        let name = cell("name");
+        match &name {
+            None => error("name", "is required".to_owned()),
+            Some(name) if name.chars().count() > MAX_NAME_LENGTH => error(
+                "name",
+                format!("is longer than {MAX_NAME_LENGTH} characters"),
+            ),
+            _ => {}
+        }
+
+        let owner_id = match cell("owner").map(|owner| uuid::Uuid::parse_str(&owner)) {
+            None => {
+                error("owner", "is required".to_owned());
+                None
+            }
+            Some(Err(_)) => {
+                error("owner", "is not a valid id".to_owned());
+                None
+            }
+            Some(Ok(owner_id)) => Some(owner_id),
+        };
+
+        let received_date = match cell("received_date").map(|date| parse_date(&date)) {
+            None => None,
+            Some(Err(message)) => {
+                error("received_date", message);
+                None
+            }
+            Some(Ok(date)) => Some(date),
+        };
+
+        for column in ["board", "sn", "barcode", "hw_phase"] {
+            if cell(column).is_some_and(|value| value.chars().count() > MAX_FIELD_LENGTH) {
+                error(
+                    column,
+                    format!("is longer than {MAX_FIELD_LENGTH} characters"),
+                );
+            }
+        }
+
+        match (name, owner_id, row_errors.is_empty()) {
+            (Some(name), Some(owner_id), true) => parsed.devices.push(ParsedDevice {
+                row,
+                request: CreateDeviceRequest {
+                    name,
+                    owner_id,
+                    board: cell("board"),
+                    sn: cell("sn"),
+                    barcode: cell("barcode"),
+                    received_date,
+                    hw_phase: cell("hw_phase"),
+                    note: cell("note"),
+                    device_type_id: None,
+                    attributes: Default::default(),
+                },
+            }),
+            _ => parsed.errors.extend(row_errors),
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// Vendor spreadsheets either contain a full RFC 3339 timestamp or a date
+fn parse_date(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Ok(date.with_timezone(&Utc));
+    }
+
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|date| date.and_hms_opt(0, 0, 0).unwrap().and_utc())
+        .map_err(|_| format!("`{value}` is not a date, use YYYY-MM-DD"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_devices;
+
+    #[test]
+    fn parse_devices_works() {
+        let owner = uuid::Uuid::new_v4();
+        let content = format!(
+            "Name,Board,SN,Barcode,Received_Date,HW_Phase,Note,Owner,Unknown\n\
+             phone,main,SN-1,BC-1,2023-08-01,EVT,first batch,{owner},ignored\n\
+             tablet,,,,2023-08-01T10:00:00Z,,,{owner},\n"
+        );
+
+        let parsed = parse_devices(content.as_bytes()).unwrap();
+
+        assert_eq!(parsed.total_rows, 2);
+        assert!(parsed.errors.is_empty());
+        assert_eq!(parsed.devices[0].request.name, "phone");
+        assert_eq!(parsed.devices[0].request.sn.as_deref(), Some("SN-1"));
+        assert_eq!(parsed.devices[0].request.owner_id, owner);
+        assert_eq!(
+            parsed.devices[0]
+                .request
+                .received_date
+                .unwrap()
+                .to_rfc3339(),
+            "2023-08-01T00:00:00+00:00"
+        );
+        assert_eq!(parsed.devices[1].request.board, None);
+    }
+
+    #[test]
+    fn parse_devices_reports_every_invalid_cell() {
+        let owner = uuid::Uuid::new_v4();
+        let content = format!(
+            "name,owner,received_date\n\
+             ,not-an-id,yesterday\n\
+             phone,{owner},2023-08-01\n"
+        );
+
+        let parsed = parse_devices(content.as_bytes()).unwrap();
+
+        assert_eq!(parsed.total_rows, 2);
+        assert_eq!(parsed.devices.len(), 1);
+        let columns: Vec<_> = parsed
+            .errors
+            .iter()
+            .map(|e| (e.row, e.column.as_deref().unwrap()))
+            .collect();
+        assert_eq!(
+            columns,
+            vec![(2, "name"), (2, "owner"), (2, "received_date")]
+        );
+    }
+
+    #[test]
+    fn parse_devices_reports_the_line_a_multi_line_row_starts_at() {
+        let owner = uuid::Uuid::new_v4();
+        let content = format!(
+            "name,owner,note\n\
+             phone,{owner},\"first line\nsecond line\"\n\
+             ,{owner},\n"
+        );
+
+        let parsed = parse_devices(content.as_bytes()).unwrap();
+
+        assert_eq!(parsed.devices[0].row, 2);
+        assert_eq!(parsed.errors[0].row, 4);
+    }
+
+    #[test]
+    fn parse_devices_requires_name_and_owner_columns() {
+        let error = parse_devices(b"name,board\nphone,main\n").unwrap_err();
+
+        assert_eq!(error, "missing required columns: owner");
+    }
+}
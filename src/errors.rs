@@ -1,26 +1,80 @@
-use axum::extract::rejection::JsonRejection;
+use axum::extract::multipart::{MultipartError, MultipartRejection};
+use axum::extract::rejection::{JsonRejection, QueryRejection};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
 
+use crate::models::device_status::DeviceStatus;
 use crate::models::error_response::ErrorResposne;
 
 #[derive(Debug, thiserror::Error)]
 pub enum AppError {
     #[error("json decode failed")]
     JsonError,
+    #[error("invalid query: {0}")]
+    InvalidQuery(String),
+    #[error("invalid request: {0}")]
+    InvalidRequest(String),
+    #[error("resource not found")]
+    NotFound,
+    #[error("{0}")]
+    Conflict(String),
+    #[error("a device can't move from {from} to {to}")]
+    InvalidTransition {
+        from: DeviceStatus,
+        to: DeviceStatus,
+    },
+    #[error("{0}")]
+    PreconditionFailed(String),
+    #[error("the If-Match header is required")]
+    PreconditionRequired,
+    #[error("{0}")]
+    PayloadTooLarge(String),
+    #[error("{0}")]
+    UnsupportedMediaType(String),
+    #[error("the label printer is unavailable")]
+    PrinterUnavailable(#[source] anyhow::Error),
     #[error(transparent)]
-    UnexpectedError(#[from] anyhow::Error),
+    UnexpectedError(anyhow::Error),
     #[error(transparent)]
     Auth(#[from] AuthError),
 }
 
+/// Repositories return `anyhow::Error`, an `AppError` inside it is kept so a
+/// repository can report an expected failure such as a conflict
+impl From<anyhow::Error> for AppError {
+    fn from(e: anyhow::Error) -> Self {
+        match e.downcast::<AppError>() {
+            Ok(e) => e,
+            Err(e) => Self::UnexpectedError(e),
+        }
+    }
+}
+
 impl From<JsonRejection> for AppError {
     fn from(_: JsonRejection) -> Self {
         Self::JsonError
     }
 }
 
+impl From<QueryRejection> for AppError {
+    fn from(rejection: QueryRejection) -> Self {
+        Self::InvalidQuery(rejection.body_text())
+    }
+}
+
+impl From<MultipartRejection> for AppError {
+    fn from(rejection: MultipartRejection) -> Self {
+        Self::InvalidRequest(rejection.body_text())
+    }
+}
+
+impl From<MultipartError> for AppError {
+    fn from(e: MultipartError) -> Self {
+        Self::InvalidRequest(e.body_text())
+    }
+}
+
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status, error_message) = match self {
@@ -31,6 +85,18 @@ impl IntoResponse for AppError {
                 AuthError::Forbidden => (StatusCode::FORBIDDEN, e.to_string()),
             },
             AppError::JsonError => (StatusCode::BAD_REQUEST, self.to_string()),
+            AppError::InvalidQuery(_) => (StatusCode::BAD_REQUEST, self.to_string()),
+            AppError::InvalidRequest(_) => (StatusCode::BAD_REQUEST, self.to_string()),
+            AppError::NotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::Conflict(_) => (StatusCode::CONFLICT, self.to_string()),
+            AppError::InvalidTransition { .. } => (StatusCode::CONFLICT, self.to_string()),
+            AppError::PreconditionFailed(_) => (StatusCode::PRECONDITION_FAILED, self.to_string()),
+            AppError::PreconditionRequired => (StatusCode::PRECONDITION_REQUIRED, self.to_string()),
+            AppError::PayloadTooLarge(_) => (StatusCode::PAYLOAD_TOO_LARGE, self.to_string()),
+            AppError::UnsupportedMediaType(_) => {
+                (StatusCode::UNSUPPORTED_MEDIA_TYPE, self.to_string())
+            }
+            AppError::PrinterUnavailable(_) => (StatusCode::SERVICE_UNAVAILABLE, self.to_string()),
         };
 
         let resp = ErrorResposne {
@@ -0,0 +1,85 @@
+//! Code 128 encoding with code set B, which covers printable ASCII
+
+/// The bar and space widths of every symbol value, starting with a bar
+const PATTERNS: [&str; 107] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212",
+    "221213", "221312", "231212", "112232", "122132", "122231", "113222", "123122", "123221",
+    "223211", "221132", "221231", "213212", "223112", "312131", "311222", "321122", "321221",
+    "312212", "322112", "322211", "212123", "212321", "232121", "111323", "131123", "131321",
+    "112313", "132113", "132311", "211313", "231113", "231311", "112133", "112331", "132131",
+    "113123", "113321", "133121", "313121", "211331", "231131", "213113", "213311", "213131",
+    "311123", "311321", "331121", "312113", "312311", "332111", "314111", "221411", "431111",
+    "111224", "111422", "121124", "121421", "141122", "141221", "112214", "112412", "122114",
+    "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111", "111242",
+    "121142", "121241", "114212", "124112", "124211", "411212", "421112", "421211", "212141",
+    "214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311",
+    "113141", "114131", "311141", "411131", "211412", "211214", "211232", "2331112",
+];
+
+const START_B: usize = 104;
+const STOP: usize = 106;
+
+/// The symbol values of `content` including the start, checksum and stop
+fn values(content: &str) -> anyhow::Result<Vec<usize>> {
+    let mut values = vec![START_B];
+    for c in content.chars() {
+        if !(' '..='~').contains(&c) {
+            anyhow::bail!("`{c}` can't be encoded in Code 128");
+        }
+        values.push(c as usize - ' ' as usize);
+    }
+
+    let checksum = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| i.max(1) * value)
+        .sum::<usize>()
+        % 103;
+    values.push(checksum);
+    values.push(STOP);
+
+    Ok(values)
+}
+
+/// Encode `content` as modules from left to right, `true` is a bar
+pub fn encode(content: &str) -> anyhow::Result<Vec<bool>> {
+    let mut modules = vec![];
+    for value in values(content)? {
+        for (i, width) in PATTERNS[value].bytes().enumerate() {
+            let bar = i % 2 == 0;
+            modules.extend(std::iter::repeat_n(bar, (width - b'0') as usize));
+        }
+    }
+
+    Ok(modules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode, values, PATTERNS};
+
+    #[test]
+    fn every_pattern_is_eleven_modules_wide() {
+        for pattern in &PATTERNS[..106] {
+            let width: u32 = pattern.bytes().map(|w| (w - b'0') as u32).sum();
+            assert_eq!(width, 11, "{pattern}");
+        }
+    }
+
+    #[test]
+    fn checksum_is_correct() {
+        let values = values("PJJ123C").unwrap();
+
+        // 104 + 48 * 1 + 42 * 2 + 42 * 3 + 17 * 4 + 18 * 5 + 19 * 6 + 35 * 7 = 879
+        assert_eq!(values[values.len() - 2], 879 % 103);
+    }
+
+    #[test]
+    fn encode_works() {
+        let modules = encode("AB").unwrap();
+
+        // Start, two characters and checksum are 11 modules, stop is 13
+        assert_eq!(modules.len(), 11 * 4 + 13);
+        assert!(encode("é").is_err());
+    }
+}
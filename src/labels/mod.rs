@@ -0,0 +1,152 @@
+//! Printable asset labels of devices
+
+mod code128;
+pub mod printer;
+mod render;
+pub mod zpl;
+
+pub use render::{pdf_sheet, png, svg};
+
+use crate::models::device::Device;
+use crate::models::label::LabelKind;
+
+/// The quiet zones required around the symbols, in modules
+const QR_QUIET_ZONE: usize = 4;
+const CODE128_QUIET_ZONE: usize = 10;
+
+/// The height of a Code 128 barcode, in modules
+const CODE128_HEIGHT: usize = 40;
+
+/// A barcode as a grid of modules, the quiet zone included
+#[derive(Debug)]
+pub struct Symbol {
+    width: usize,
+    rows: usize,
+    /// The height of a row in modules, a linear barcode is a single tall row
+    row_height: usize,
+    dark: Vec<bool>,
+}
+
+impl Symbol {
+    pub fn qr(content: &str) -> anyhow::Result<Self> {
+        let code = qrcode::QrCode::new(content)?;
+        let size = code.width();
+        let colors = code.to_colors();
+
+        let width = size + 2 * QR_QUIET_ZONE;
+        let mut dark = vec![false; width * width];
+        for y in 0..size {
+            for x in 0..size {
+                dark[(y + QR_QUIET_ZONE) * width + x + QR_QUIET_ZONE] =
+                    colors[y * size + x] == qrcode::Color::Dark;
+            }
+        }
+
+        Ok(Self {
+            width,
+            rows: width,
+            row_height: 1,
+            dark,
+        })
+    }
+
+    pub fn code128(content: &str) -> anyhow::Result<Self> {
+        let bars = code128::encode(content)?;
+        let quiet_zone = std::iter::repeat_n(false, CODE128_QUIET_ZONE);
+        let dark: Vec<_> = quiet_zone.clone().chain(bars).chain(quiet_zone).collect();
+
+        Ok(Self {
+            width: dark.len(),
+            rows: 1,
+            row_height: CODE128_HEIGHT,
+            dark,
+        })
+    }
+
+    /// The width in modules
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The height in modules
+    pub fn height(&self) -> usize {
+        self.rows * self.row_height
+    }
+
+    /// The dark areas as `(x, y, width, height)` rectangles in modules, the
+    /// adjacent dark modules of a row are merged
+    pub fn rects(&self) -> Vec<(usize, usize, usize, usize)> {
+        let mut rects = vec![];
+        for row in 0..self.rows {
+            let modules = &self.dark[row * self.width..(row + 1) * self.width];
+            let mut x = 0;
+            while x < self.width {
+                if !modules[x] {
+                    x += 1;
+                    continue;
+                }
+                let start = x;
+                while x < self.width && modules[x] {
+                    x += 1;
+                }
+                rects.push((start, row * self.row_height, x - start, self.row_height));
+            }
+        }
+
+        rects
+    }
+}
+
+/// The symbol of a device together with the caption printed under it
+#[derive(Debug)]
+pub struct Label {
+    pub symbol: Symbol,
+    pub caption: Vec<String>,
+}
+
+impl Label {
+    /// The symbol encodes the barcode of the device, or its id if the device
+    /// has no barcode. The caption is the name and the serial number.
+    pub fn for_device(device: &Device, kind: LabelKind) -> anyhow::Result<Self> {
+        let id = device.id.to_string();
+        let content = device.barcode.as_deref().unwrap_or(&id);
+        let symbol = match kind {
+            LabelKind::Qr => Symbol::qr(content)?,
+            LabelKind::Code128 => Symbol::code128(content)?,
+        };
+
+        let mut caption = vec![device.name.clone()];
+        if let Some(sn) = &device.sn {
+            caption.push(format!("SN: {sn}"));
+        }
+
+        Ok(Self { symbol, caption })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Symbol;
+
+    #[test]
+    fn qr_symbol_has_quiet_zone() {
+        let symbol = Symbol::qr("hello").unwrap();
+
+        // Version 1 is 21 modules wide
+        assert_eq!(symbol.width(), 21 + 8);
+        assert_eq!(symbol.height(), symbol.width());
+        assert!(symbol
+            .rects()
+            .iter()
+            .all(|&(x, y, w, _)| x >= 4 && y >= 4 && x + w <= 25));
+    }
+
+    #[test]
+    fn code128_symbol_is_a_single_row_of_bars() {
+        let symbol = Symbol::code128("AB").unwrap();
+
+        assert_eq!(symbol.width(), 11 * 4 + 13 + 20);
+        assert_eq!(symbol.height(), 40);
+        assert!(symbol.rects().iter().all(|&(_, y, _, h)| y == 0 && h == 40));
+    }
+}
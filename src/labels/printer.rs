@@ -0,0 +1,32 @@
+use std::time::Duration;
+
+use anyhow::Context;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+use crate::configuration::PrinterSettings;
+
+/// Send raw ZPL to the printer, the printer prints once the connection is
+/// closed
+pub async fn send(settings: &PrinterSettings, zpl: &str) -> anyhow::Result<()> {
+    let timeout = Duration::from_millis(settings.timeout_milliseconds);
+    let address = (settings.host.as_str(), settings.port);
+
+    let print = async {
+        let mut stream = TcpStream::connect(address)
+            .await
+            .context("Failed to connect to the printer")?;
+        stream
+            .write_all(zpl.as_bytes())
+            .await
+            .context("Failed to send the labels to the printer")?;
+        stream
+            .shutdown()
+            .await
+            .context("Failed to close the printer connection")
+    };
+
+    tokio::time::timeout(timeout, print)
+        .await
+        .context("The printer didn't respond in time")?
+}
@@ -0,0 +1,279 @@
+use anyhow::Context;
+use embedded_graphics::mono_font::iso_8859_1::FONT_9X15;
+use embedded_graphics::mono_font::MonoTextStyle;
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::{
+    Dimensions, DrawTarget, Drawable, OriginDimensions, Pixel, Point, Size,
+};
+use embedded_graphics::text::{Alignment, Baseline, Text, TextStyleBuilder};
+use printpdf::{BuiltinFont, Mm, PdfDocument, Rect};
+
+use super::Label;
+
+/// The size of a module in pixels
+const QR_MODULE_SIZE: usize = 8;
+const CODE128_MODULE_SIZE: usize = 2;
+
+const CAPTION_FONT_SIZE: usize = 14;
+const CAPTION_LINE_HEIGHT: usize = 18;
+
+fn module_size(label: &Label) -> usize {
+    if label.symbol.rows == 1 {
+        CODE128_MODULE_SIZE
+    } else {
+        QR_MODULE_SIZE
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Render a label as an SVG image
+pub fn svg(label: &Label) -> String {
+    let scale = module_size(label);
+    let width = label.symbol.width() * scale;
+    let symbol_height = label.symbol.height() * scale;
+    let height =
+        symbol_height + label.caption.len() * CAPTION_LINE_HEIGHT + CAPTION_LINE_HEIGHT / 2;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\">\
+         <rect width=\"100%\" height=\"100%\" fill=\"#fff\"/><path fill=\"#000\" d=\""
+    );
+    for (x, y, w, h) in label.symbol.rects() {
+        svg.push_str(&format!(
+            "M{} {}h{}v{}h-{}z",
+            x * scale,
+            y * scale,
+            w * scale,
+            h * scale,
+            w * scale
+        ));
+    }
+    svg.push_str("\"/>");
+
+    for (i, line) in label.caption.iter().enumerate() {
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"{CAPTION_FONT_SIZE}\" \
+             text-anchor=\"middle\">{}</text>",
+            width / 2,
+            symbol_height + (i + 1) * CAPTION_LINE_HEIGHT,
+            escape(line)
+        ));
+    }
+    svg.push_str("</svg>");
+
+    svg
+}
+
+/// A white 8-bit grayscale image
+struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![u8::MAX; width * height],
+        }
+    }
+
+    fn fill(&mut self, x: usize, y: usize, w: usize, h: usize) {
+        for row in y..(y + h).min(self.height) {
+            let start = row * self.width + x.min(self.width);
+            let end = row * self.width + (x + w).min(self.width);
+            self.pixels[start..end].fill(0);
+        }
+    }
+}
+
+impl OriginDimensions for Canvas {
+    fn size(&self) -> Size {
+        Size::new(self.width as u32, self.height as u32)
+    }
+}
+
+impl DrawTarget for Canvas {
+    type Color = BinaryColor;
+    type Error = std::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let bounds = self.bounding_box();
+        for Pixel(point, color) in pixels {
+            if bounds.contains(point) && color.is_on() {
+                self.pixels[point.y as usize * self.width + point.x as usize] = 0;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Render a label as a PNG image, the caption uses a built-in bitmap font
+pub fn png(label: &Label) -> anyhow::Result<Vec<u8>> {
+    let scale = module_size(label);
+    let symbol_width = label.symbol.width() * scale;
+    let caption_width = label
+        .caption
+        .iter()
+        .map(|line| (line.chars().count() + 2) * FONT_9X15.character_size.width as usize)
+        .max()
+        .unwrap_or_default();
+    let width = symbol_width.max(caption_width);
+    let symbol_height = label.symbol.height() * scale;
+    let height =
+        symbol_height + label.caption.len() * CAPTION_LINE_HEIGHT + CAPTION_LINE_HEIGHT / 2;
+
+    let mut canvas = Canvas::new(width, height);
+    let offset = (width - symbol_width) / 2;
+    for (x, y, w, h) in label.symbol.rects() {
+        canvas.fill(offset + x * scale, y * scale, w * scale, h * scale);
+    }
+
+    let character_style = MonoTextStyle::new(&FONT_9X15, BinaryColor::On);
+    let text_style = TextStyleBuilder::new()
+        .alignment(Alignment::Center)
+        .baseline(Baseline::Alphabetic)
+        .build();
+    for (i, line) in label.caption.iter().enumerate() {
+        let position = Point::new(
+            (width / 2) as i32,
+            (symbol_height + (i + 1) * CAPTION_LINE_HEIGHT) as i32,
+        );
+        Text::with_text_style(line, position, character_style, text_style)
+            .draw(&mut canvas)
+            .expect("drawing on a canvas never fails");
+    }
+
+    let mut content = vec![];
+    let mut encoder = png::Encoder::new(&mut content, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder
+        .write_header()
+        .context("Failed to write the png header")?;
+    writer
+        .write_image_data(&canvas.pixels)
+        .context("Failed to write the png image")?;
+    writer.finish().context("Failed to finish the png image")?;
+
+    Ok(content)
+}
+
+/// An A4 sheet of 3 x 8 labels, a common layout of adhesive label sheets
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const COLUMNS: usize = 3;
+const ROWS: usize = 8;
+const LABEL_WIDTH: f32 = PAGE_WIDTH / COLUMNS as f32;
+const LABEL_HEIGHT: f32 = PAGE_HEIGHT / ROWS as f32;
+const LABEL_MARGIN: f32 = 3.0;
+
+/// The caption of a label on a sheet, in points and millimeters
+const SHEET_FONT_SIZE: f32 = 8.0;
+const SHEET_LINE_HEIGHT: f32 = 3.5;
+const SHEET_CAPTION_LENGTH: usize = 40;
+
+/// Lay out the labels on as many A4 pages as needed as a PDF document
+pub fn pdf_sheet(labels: &[Label]) -> anyhow::Result<Vec<u8>> {
+    let (doc, page, layer) =
+        PdfDocument::new("Device labels", Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Labels");
+    let font = doc
+        .add_builtin_font(BuiltinFont::Helvetica)
+        .context("Failed to add the label font")?;
+
+    let mut layer = doc.get_page(page).get_layer(layer);
+    for (i, label) in labels.iter().enumerate() {
+        let slot = i % (COLUMNS * ROWS);
+        if i > 0 && slot == 0 {
+            let (page, new_layer) = doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Labels");
+            layer = doc.get_page(page).get_layer(new_layer);
+        }
+
+        // PDF coordinates start from the bottom left corner of the page
+        let left = (slot % COLUMNS) as f32 * LABEL_WIDTH + LABEL_MARGIN;
+        let top = PAGE_HEIGHT - (slot / COLUMNS) as f32 * LABEL_HEIGHT - LABEL_MARGIN;
+        let caption_height = label.caption.len() as f32 * SHEET_LINE_HEIGHT;
+        let box_width = LABEL_WIDTH - 2.0 * LABEL_MARGIN;
+        let box_height = LABEL_HEIGHT - 2.0 * LABEL_MARGIN - caption_height;
+
+        let symbol = &label.symbol;
+        let module = (box_width / symbol.width() as f32).min(box_height / symbol.height() as f32);
+        let symbol_left = left + (box_width - module * symbol.width() as f32) / 2.0;
+        for (x, y, w, h) in symbol.rects() {
+            let x = symbol_left + x as f32 * module;
+            let y = top - y as f32 * module;
+            layer.add_rect(Rect::new(
+                Mm(x),
+                Mm(y - h as f32 * module),
+                Mm(x + w as f32 * module),
+                Mm(y),
+            ));
+        }
+
+        let caption_top = top - module * symbol.height() as f32;
+        for (i, line) in label.caption.iter().enumerate() {
+            let line: String = if line.chars().count() > SHEET_CAPTION_LENGTH {
+                line.chars()
+                    .take(SHEET_CAPTION_LENGTH - 3)
+                    .chain("...".chars())
+                    .collect()
+            } else {
+                line.clone()
+            };
+            layer.use_text(
+                line,
+                SHEET_FONT_SIZE,
+                Mm(left),
+                Mm(caption_top - (i + 1) as f32 * SHEET_LINE_HEIGHT),
+                &font,
+            );
+        }
+    }
+
+    doc.save_to_bytes()
+        .context("Failed to write the label sheet")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pdf_sheet, png, svg};
+    use crate::labels::{Label, Symbol};
+
+    fn label(caption: &str) -> Label {
+        Label {
+            symbol: Symbol::qr("device").unwrap(),
+            caption: vec![caption.to_owned(), "SN: 1".to_owned()],
+        }
+    }
+
+    #[test]
+    fn svg_escapes_the_caption() {
+        let svg = svg(&label("<phone> & tablet"));
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("&lt;phone&gt; &amp; tablet"));
+    }
+
+    #[test]
+    fn png_and_pdf_are_rendered() {
+        let png = png(&label("phone")).unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+
+        let labels: Vec<_> = (0..30).map(|i| label(&format!("device-{i}"))).collect();
+        let pdf = pdf_sheet(&labels).unwrap();
+        assert!(pdf.starts_with(b"%PDF"));
+    }
+}
@@ -0,0 +1,148 @@
+//! ZPL II labels from the label templates.
+//!
+//! A template is a ZPL document where `{{field}}` is replaced by a field of
+//! the device. The values are hex escaped for `^FH`, so every field with a
+//! placeholder should start with `^FH^FD` and a value can never inject a
+//! command.
+
+use crate::models::device::Device;
+
+/// The fields a template can refer to, `code` is the barcode of the device
+/// or its id if the device has no barcode
+pub const FIELDS: [&str; 10] = [
+    "id", "code", "name", "owner_id", "board", "sn", "barcode", "hw_phase", "note", "status",
+];
+
+/// The placeholders of a template in order, with their byte ranges
+fn placeholders(template: &str) -> Vec<(std::ops::Range<usize>, &str)> {
+    let mut placeholders = vec![];
+    let mut rest = 0;
+    while let Some(start) = template[rest..].find("{{").map(|i| rest + i) {
+        let Some(end) = template[start..].find("}}").map(|i| start + i + 2) else {
+            break;
+        };
+        placeholders.push((start..end, template[start + 2..end - 2].trim()));
+        rest = end;
+    }
+
+    placeholders
+}
+
+/// Check the template is a single label which only refers to known fields
+pub fn validate(template: &str) -> Result<(), String> {
+    let trimmed = template.trim();
+    if !trimmed.starts_with("^XA") || !trimmed.ends_with("^XZ") {
+        return Err("a template must start with ^XA and end with ^XZ".to_owned());
+    }
+
+    let unknown: Vec<_> = placeholders(template)
+        .into_iter()
+        .map(|(_, field)| field)
+        .filter(|field| !FIELDS.contains(field))
+        .collect();
+    if !unknown.is_empty() {
+        return Err(format!("unknown fields: {}", unknown.join(", ")));
+    }
+
+    Ok(())
+}
+
+/// Escape a value for a `^FH` field, `_` is the default hex indicator
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '_' | '^' | '~' => escaped.push_str(&format!("_{:02X}", c as u32)),
+            // A line break would end the field data on some printers
+            '\r' | '\n' => escaped.push(' '),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Fill the template with the fields of the device, null fields are empty
+pub fn render(template: &str, device: &Device) -> String {
+    let value = |field: &str| -> String {
+        let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+        match field {
+            "id" => device.id.to_string(),
+            "code" => device
+                .barcode
+                .clone()
+                .unwrap_or_else(|| device.id.to_string()),
+            "name" => device.name.clone(),
+            "owner_id" => device.owner_id.to_string(),
+            "board" => optional(&device.board),
+            "sn" => optional(&device.sn),
+            "barcode" => optional(&device.barcode),
+            "hw_phase" => optional(&device.hw_phase),
+            "note" => optional(&device.note),
+            "status" => device.status.to_string(),
+            _ => String::new(),
+        }
+    };
+
+    let mut zpl = String::with_capacity(template.len());
+    let mut rest = 0;
+    for (range, field) in placeholders(template) {
+        zpl.push_str(&template[rest..range.start]);
+        zpl.push_str(&escape(&value(field)));
+        rest = range.end;
+    }
+    zpl.push_str(&template[rest..]);
+
+    zpl
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render, validate};
+    use crate::models::device::Device;
+    use crate::models::device_status::DeviceStatus;
+
+    #[test]
+    fn render_escapes_the_values() {
+        let device = Device {
+            id: uuid::Uuid::new_v4(),
+            name: "phone^XZ~JR_1".to_owned(),
+            owner_id: uuid::Uuid::new_v4(),
+            board: None,
+            sn: Some("SN-1".to_owned()),
+            barcode: None,
+            received_date: None,
+            hw_phase: None,
+            note: None,
+            device_type_id: None,
+            attributes: serde_json::json!({}),
+            status: DeviceStatus::Received,
+            status_changed_at: None,
+            location_id: None,
+            version: 1,
+        };
+
+        let zpl = render(
+            "^XA^FH^FD{{ name }}^FS^FH^FD{{code}}|{{board}}|{{sn}}^FS^XZ",
+            &device,
+        );
+
+        assert_eq!(
+            zpl,
+            format!(
+                "^XA^FH^FDphone_5EXZ_7EJR_5F1^FS^FH^FD{}||SN-1^FS^XZ",
+                device.id
+            )
+        );
+    }
+
+    #[test]
+    fn validate_works() {
+        assert!(validate("^XA^FH^FD{{name}}^FS^XZ\n").is_ok());
+        assert_eq!(
+            validate("^XA^FH^FD{{owner}}^FS^XZ"),
+            Err("unknown fields: owner".to_owned())
+        );
+        assert!(validate("^FD{{name}}^FS").is_err());
+    }
+}
@@ -1,10 +1,17 @@
+pub mod attributes;
 pub mod configuration;
+pub mod csv_export;
+pub mod csv_import;
 pub mod errors;
+pub mod labels;
+pub mod mentions;
 pub mod models;
 pub mod password;
 pub mod repositories;
 pub mod routes;
 pub mod startup;
+pub mod storage;
 pub mod telemetry;
+pub mod thumbnails;
 pub mod utils;
 pub mod middlewares;
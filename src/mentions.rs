@@ -0,0 +1,47 @@
+/// The characters of a mentioned username
+fn is_username_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '.' | '_' | '-')
+}
+
+/// The usernames mentioned as `@username` in a text, in order of appearance
+/// without duplicates. A mention starts a word so an email address isn't one,
+/// and a trailing dot ends the sentence rather than the username.
+pub fn parse(text: &str) -> Vec<String> {
+    let mut usernames: Vec<String> = vec![];
+    let mut previous = None;
+
+    for (i, c) in text.char_indices() {
+        if c == '@' && !previous.is_some_and(|p: char| is_username_char(p) || p == '@') {
+            let username = text[i + 1..]
+                .split(|c: char| !is_username_char(c))
+                .next()
+                .unwrap_or_default()
+                .trim_end_matches('.');
+            if !username.is_empty() && !usernames.iter().any(|u| u == username) {
+                usernames.push(username.to_owned());
+            }
+        }
+        previous = Some(c);
+    }
+
+    usernames
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    #[test]
+    fn parse_finds_every_mention_once() {
+        let usernames = parse("@alice the board of @bob.smith is cracked, cc @alice and @carol_1.");
+
+        assert_eq!(usernames, vec!["alice", "bob.smith", "carol_1"]);
+    }
+
+    #[test]
+    fn parse_ignores_email_addresses_and_lone_at_signs() {
+        let usernames = parse("mail vendor@example.com @ noon, @@twice (@dave)");
+
+        assert_eq!(usernames, vec!["dave"]);
+    }
+}
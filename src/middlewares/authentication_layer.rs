@@ -16,7 +16,7 @@ use crate::models::login::Claims;
 
 fn validate_permissions(claims: &Claims, require_permission: Arc<Permission>) -> bool {
     match *require_permission {
-        Permission::Role(ref require_role) => claims.roles.contains(&require_role),
+        Permission::Role(ref require_role) => claims.roles.contains(require_role),
         Permission::IndividualPermission(ref permissions) => permissions
             .iter()
             .all(|permission| claims.permissions.contains(permission)),
@@ -70,6 +70,7 @@ pub async fn authentication_layer<B>(
     // If all pass, creaet a `AuthenticatedUser` and insert to extension for later use
     request.extensions_mut().insert(AuthenticatedUser {
         user_id: token_data.claims.sub,
+        permissions: token_data.claims.permissions,
     });
 
     // continue next processing
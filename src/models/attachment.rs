@@ -0,0 +1,95 @@
+use chrono::{DateTime, Utc};
+
+/// A file attached to a device, such as a photo or an invoice. The content is
+/// kept in the blob storage under `storage_key`.
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Attachment {
+    pub id: uuid::Uuid,
+    pub device_id: uuid::Uuid,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    #[serde(skip)]
+    pub storage_key: String,
+    pub uploaded_by: Option<uuid::Uuid>,
+    pub created_at: DateTime<Utc>,
+    /// Whether the attachment is a photo with a thumbnail of every size
+    pub has_thumbnails: bool,
+}
+
+impl Attachment {
+    /// Every key of the attachment in the blob storage, the thumbnails included
+    pub fn storage_keys(&self) -> Vec<String> {
+        let mut keys = vec![self.storage_key.clone()];
+        if self.has_thumbnails {
+            keys.extend(
+                ThumbnailSize::ALL
+                    .iter()
+                    .map(|size| size.storage_key(&self.storage_key)),
+            );
+        }
+
+        keys
+    }
+}
+
+/// The sizes of the thumbnails of a photo
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThumbnailSize {
+    /// For the list views
+    #[default]
+    Small,
+    /// For the detail views
+    Medium,
+}
+
+impl ThumbnailSize {
+    pub const ALL: [Self; 2] = [Self::Small, Self::Medium];
+
+    /// The longest edge in pixels, a thumbnail keeps the aspect ratio
+    pub fn pixels(self) -> u32 {
+        match self {
+            Self::Small => 128,
+            Self::Medium => 512,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Small => "small",
+            Self::Medium => "medium",
+        }
+    }
+
+    /// A thumbnail is stored next to the original
+    pub fn storage_key(self, original: &str) -> String {
+        format!("{original}.{}.jpg", self.as_str())
+    }
+}
+
+/// The query string of `GET /devices/:id/attachments/:attachment_id/thumbnail`
+#[derive(Debug, serde::Deserialize)]
+pub struct ThumbnailQuery {
+    #[serde(default)]
+    pub size: ThumbnailSize,
+}
+
+/// The metadata of an uploaded file whose content is already stored
+#[derive(Debug)]
+pub struct NewAttachment {
+    pub id: uuid::Uuid,
+    pub device_id: uuid::Uuid,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub storage_key: String,
+    pub uploaded_by: uuid::Uuid,
+    pub has_thumbnails: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct AttachmentListResponse {
+    pub items: Vec<Attachment>,
+}
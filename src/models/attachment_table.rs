@@ -0,0 +1,13 @@
+#[derive(Debug, Clone, Copy, sea_query::Iden)]
+pub enum DeviceAttachments {
+    Table,
+    Id,
+    DeviceId,
+    FileName,
+    ContentType,
+    SizeBytes,
+    StorageKey,
+    UploadedBy,
+    CreatedAt,
+    HasThumbnails,
+}
@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+
+/// A board of the catalog, devices refer to a board by its `full_name`
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Board {
+    pub id: uuid::Uuid,
+    pub name: String,
+    /// Empty if the board has a single revision
+    pub revision: String,
+    pub vendor: Option<String>,
+    pub description: Option<String>,
+    /// The name followed by the revision, e.g. `main-board rev B`
+    pub full_name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The body of creating or replacing a board
+#[derive(Debug, serde::Deserialize)]
+pub struct BoardRequest {
+    pub name: String,
+    #[serde(default)]
+    pub revision: String,
+    pub vendor: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct BoardListResponse {
+    pub items: Vec<Board>,
+}
@@ -0,0 +1,11 @@
+#[derive(Debug, Clone, Copy, sea_query::Iden)]
+pub enum Boards {
+    Table,
+    Id,
+    Name,
+    Revision,
+    Vendor,
+    Description,
+    FullName,
+    CreatedAt,
+}
@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+
+/// A comment on a device, `updatedAt` is set once the comment is edited
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Comment {
+    pub id: uuid::Uuid,
+    pub device_id: uuid::Uuid,
+    /// `None` once the author is deleted
+    pub author_id: Option<uuid::Uuid>,
+    pub author_username: Option<String>,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    #[sqlx(skip)]
+    pub mentions: Vec<MentionedUser>,
+}
+
+/// A user mentioned as `@username` in a comment
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct MentionedUser {
+    pub user_id: uuid::Uuid,
+    pub username: String,
+}
+
+/// The body of creating or editing a comment
+#[derive(Debug, serde::Deserialize)]
+pub struct CommentRequest {
+    pub body: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct CommentListResponse {
+    pub items: Vec<Comment>,
+}
+
+/// A mention of a user, what a notification is made of
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Mention {
+    #[sqlx(rename = "id")]
+    pub comment_id: uuid::Uuid,
+    pub device_id: uuid::Uuid,
+    pub author_id: Option<uuid::Uuid>,
+    pub author_username: Option<String>,
+    pub body: String,
+    /// When the user was first mentioned in the comment
+    pub mentioned_at: DateTime<Utc>,
+}
+
+/// The query string of `GET /mentions`
+#[derive(Debug, serde::Deserialize)]
+pub struct ListMentionsQuery {
+    /// Only the mentions after this time, for polling notifiers
+    pub since: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct MentionListResponse {
+    pub items: Vec<Mention>,
+}
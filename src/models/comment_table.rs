@@ -0,0 +1,18 @@
+#[derive(Debug, Clone, Copy, sea_query::Iden)]
+pub enum DeviceComments {
+    Table,
+    Id,
+    DeviceId,
+    AuthorId,
+    Body,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Debug, Clone, Copy, sea_query::Iden)]
+pub enum DeviceCommentMentions {
+    Table,
+    CommentId,
+    UserId,
+    CreatedAt,
+}
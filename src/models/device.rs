@@ -0,0 +1,256 @@
+use chrono::{DateTime, Utc};
+
+use super::device_status::DeviceStatus;
+use super::tag::TagMatch;
+
+/// The limits of the `varchar` columns of the `devices` table
+pub const MAX_NAME_LENGTH: usize = 1024;
+pub const MAX_FIELD_LENGTH: usize = 128;
+
+/// A device stored in the `devices` table
+#[derive(Debug, Clone, serde::Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Device {
+    pub id: uuid::Uuid,
+    pub name: String,
+    pub owner_id: uuid::Uuid,
+    pub board: Option<String>,
+    pub sn: Option<String>,
+    pub barcode: Option<String>,
+    pub received_date: Option<DateTime<Utc>>,
+    pub hw_phase: Option<String>,
+    pub note: Option<String>,
+    pub device_type_id: Option<uuid::Uuid>,
+    /// The custom attributes described by the schema of the device type
+    pub attributes: serde_json::Value,
+    pub status: DeviceStatus,
+    pub status_changed_at: Option<DateTime<Utc>>,
+    /// The innermost location holding the device, moved by `POST /devices/:id/move`
+    pub location_id: Option<uuid::Uuid>,
+    /// Bumped by every change of the device, it is sent as the `ETag` header
+    /// instead of in the body
+    #[serde(skip)]
+    pub version: i32,
+}
+
+impl Device {
+    pub fn etag(&self) -> String {
+        format!("\"{}\"", self.version)
+    }
+}
+
+/// The `If-Match` header of a change to a device, either `*` or the entity
+/// tags of the versions the client has seen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IfMatch {
+    Any,
+    Tags(Vec<String>),
+}
+
+impl IfMatch {
+    pub fn parse(value: &str) -> Self {
+        if value.trim() == "*" {
+            return Self::Any;
+        }
+
+        Self::Tags(value.split(',').map(|tag| tag.trim().to_owned()).collect())
+    }
+
+    /// A weak entity tag never matches, `If-Match` uses the strong comparison
+    pub fn matches(&self, device: &Device) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Tags(tags) => tags.contains(&device.etag()),
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateDeviceRequest {
+    pub name: String,
+    pub owner_id: uuid::Uuid,
+    pub board: Option<String>,
+    pub sn: Option<String>,
+    pub barcode: Option<String>,
+    pub received_date: Option<DateTime<Utc>>,
+    pub hw_phase: Option<String>,
+    pub note: Option<String>,
+    pub device_type_id: Option<uuid::Uuid>,
+    #[serde(default)]
+    pub attributes: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The payload of `PUT /devices/:id`, it replaces every field of the device
+/// but the status and the location
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateDeviceRequest {
+    pub name: String,
+    pub owner_id: uuid::Uuid,
+    pub board: Option<String>,
+    pub sn: Option<String>,
+    pub barcode: Option<String>,
+    pub received_date: Option<DateTime<Utc>>,
+    pub hw_phase: Option<String>,
+    pub note: Option<String>,
+    pub device_type_id: Option<uuid::Uuid>,
+    #[serde(default)]
+    pub attributes: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The sortable columns of a device
+#[derive(Debug, Clone, Copy, Default, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DeviceSortColumn {
+    Id,
+    #[default]
+    Name,
+    OwnerId,
+    Board,
+    Sn,
+    Barcode,
+    ReceivedDate,
+    HwPhase,
+    Note,
+    DeviceTypeId,
+    Status,
+}
+
+#[derive(Debug, Clone, Copy, Default, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// The query string of `GET /devices`.
+///
+/// Every filter is optional, `cursor` is the `nextCursor` returned by the
+/// previous page and can be combined with `limit` instead of `offset`.
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListDevicesQuery {
+    pub owner_id: Option<uuid::Uuid>,
+    pub board: Option<String>,
+    pub hw_phase: Option<String>,
+    pub device_type_id: Option<uuid::Uuid>,
+    pub status: Option<DeviceStatus>,
+    /// The devices in this location or in any location under it
+    pub location_id: Option<uuid::Uuid>,
+    /// A JSON object the attributes must contain, e.g. `{"ramGb":8}`
+    #[serde(default, deserialize_with = "json_object")]
+    pub attributes: Option<serde_json::Map<String, serde_json::Value>>,
+    /// Comma separated tag names, combined by `tagMatch`
+    #[serde(default, deserialize_with = "tag_names")]
+    pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub tag_match: TagMatch,
+    pub received_from: Option<DateTime<Utc>>,
+    pub received_to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub sort_by: DeviceSortColumn,
+    #[serde(default)]
+    pub order: SortOrder,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+    pub cursor: Option<uuid::Uuid>,
+}
+
+/// Read a query string parameter holding a JSON object
+fn json_object<'de, D>(
+    deserializer: D,
+) -> Result<Option<serde_json::Map<String, serde_json::Value>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    <Option<String> as serde::Deserialize>::deserialize(deserializer)?
+        .map(|value| serde_json::from_str(&value).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+/// Read a query string parameter holding comma separated tag names, the names
+/// are lowercased like the stored tags and a blank parameter is no filter
+fn tag_names<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let names = <Option<String> as serde::Deserialize>::deserialize(deserializer)?.map(|value| {
+        value
+            .split(',')
+            .map(|name| name.trim().to_lowercase())
+            .filter(|name| !name.is_empty())
+            .collect::<Vec<_>>()
+    });
+
+    Ok(names.filter(|names| !names.is_empty()))
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceListResponse {
+    pub items: Vec<Device>,
+    /// The number of devices matching the filters, regardless of the pagination
+    pub total: i64,
+    pub next_cursor: Option<uuid::Uuid>,
+}
+
+/// A deleted device waiting in the trash to be restored or purged
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashedDevice {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub device: Device,
+    pub deleted_at: DateTime<Utc>,
+    pub deleted_by: uuid::Uuid,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct TrashedDeviceListResponse {
+    pub items: Vec<TrashedDevice>,
+}
+
+/// The devices removed for good by `DELETE /devices/trash`
+#[derive(Debug, serde::Serialize)]
+pub struct PurgeDevicesResponse {
+    pub purged: Vec<uuid::Uuid>,
+}
+
+/// The query string of `GET /devices/search`
+#[derive(Debug, serde::Deserialize)]
+pub struct SearchDevicesQuery {
+    pub q: String,
+    pub limit: Option<u64>,
+}
+
+/// The fields of a device which matched the search, the matched words are
+/// wrapped in `<mark></mark>`
+#[derive(Debug, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceHighlights {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub board: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sn: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub barcode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceSearchHit {
+    pub device: Device,
+    pub rank: f32,
+    pub highlights: DeviceHighlights,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct DeviceSearchResponse {
+    pub items: Vec<DeviceSearchHit>,
+}
@@ -0,0 +1,44 @@
+/// The formats of `GET /devices/export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    /// A JSON document per line, also known as JSON Lines
+    Ndjson,
+}
+
+impl ExportFormat {
+    /// Pick the first format the `Accept` header asks for
+    pub fn from_accept(accept: &str) -> Option<Self> {
+        accept
+            .split(',')
+            .map(|media_type| media_type.split(';').next().unwrap_or_default().trim())
+            .find_map(|media_type| match media_type {
+                "text/csv" => Some(Self::Csv),
+                "application/x-ndjson" | "application/jsonl" => Some(Self::Ndjson),
+                _ => None,
+            })
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Ndjson => "ndjson",
+        }
+    }
+}
+
+/// The query string of `GET /devices/export` besides the filters of
+/// [`super::device::ListDevicesQuery`], the format parameter wins over the
+/// `Accept` header
+#[derive(Debug, serde::Deserialize)]
+pub struct ExportDevicesQuery {
+    pub format: Option<ExportFormat>,
+}
@@ -0,0 +1,133 @@
+use chrono::{DateTime, Utc};
+
+use super::device::Device;
+
+/// The kind of mutation recorded in the history of a device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum DeviceHistoryAction {
+    Create,
+    Update,
+    Delete,
+    StatusChange,
+    Move,
+    Restore,
+    Purge,
+}
+
+impl DeviceHistoryAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Update => "update",
+            Self::Delete => "delete",
+            Self::StatusChange => "status_change",
+            Self::Move => "move",
+            Self::Restore => "restore",
+            Self::Purge => "purge",
+        }
+    }
+}
+
+/// An append-only record of a mutation of a device
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceHistoryEntry {
+    pub id: uuid::Uuid,
+    pub device_id: uuid::Uuid,
+    pub action: DeviceHistoryAction,
+    pub changed_by: uuid::Uuid,
+    pub changed_at: DateTime<Utc>,
+    /// `{ "<field>": { "old": <value>, "new": <value> } }` for every changed field
+    pub changes: serde_json::Value,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct DeviceHistoryListResponse {
+    pub items: Vec<DeviceHistoryEntry>,
+}
+
+/// Compute the changed fields between two versions of a device, a missing
+/// version (before a create or after a delete) counts as every field is null
+pub fn diff(old: Option<&Device>, new: Option<&Device>) -> serde_json::Value {
+    let to_object = |device: Option<&Device>| match device.map(serde_json::to_value) {
+        Some(Ok(serde_json::Value::Object(object))) => object,
+        _ => serde_json::Map::new(),
+    };
+    let old = to_object(old);
+    let new = to_object(new);
+
+    let mut changes = serde_json::Map::new();
+    for key in old.keys().chain(new.keys()) {
+        let old_value = old.get(key).unwrap_or(&serde_json::Value::Null);
+        let new_value = new.get(key).unwrap_or(&serde_json::Value::Null);
+        if old_value != new_value && !changes.contains_key(key) {
+            changes.insert(
+                key.clone(),
+                serde_json::json!({ "old": old_value, "new": new_value }),
+            );
+        }
+    }
+
+    serde_json::Value::Object(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{device::Device, device_status::DeviceStatus};
+
+    use super::diff;
+
+    fn device() -> Device {
+        Device {
+            id: uuid::Uuid::new_v4(),
+            name: "phone".to_string(),
+            owner_id: uuid::Uuid::new_v4(),
+            board: None,
+            sn: Some("SN-1".to_string()),
+            barcode: None,
+            received_date: None,
+            hw_phase: Some("EVT".to_string()),
+            note: None,
+            device_type_id: None,
+            attributes: serde_json::json!({}),
+            status: DeviceStatus::Received,
+            status_changed_at: None,
+            location_id: None,
+            version: 1,
+        }
+    }
+
+    #[test]
+    fn diff_only_contains_changed_fields() {
+        let old = device();
+        let mut new = device();
+        new.id = old.id;
+        new.owner_id = old.owner_id;
+        new.hw_phase = Some("DVT".to_string());
+        new.note = Some("moved to DVT".to_string());
+
+        assert_eq!(
+            diff(Some(&old), Some(&new)),
+            serde_json::json!({
+                "hwPhase": { "old": "EVT", "new": "DVT" },
+                "note": { "old": null, "new": "moved to DVT" },
+            })
+        );
+    }
+
+    #[test]
+    fn diff_of_a_created_device_contains_every_non_null_field() {
+        let new = device();
+
+        let changes = diff(None, Some(&new));
+
+        assert_eq!(
+            changes["name"],
+            serde_json::json!({ "old": null, "new": "phone" })
+        );
+        assert_eq!(changes["status"]["new"], "received");
+        assert!(changes.get("board").is_none());
+    }
+}
@@ -0,0 +1,11 @@
+#[derive(Debug, Clone, Copy, sea_query::Iden)]
+pub enum DeviceHistory {
+    Table,
+    Id,
+    DeviceId,
+    Action,
+    ChangedBy,
+    ChangedAt,
+    Changes,
+    Seq,
+}
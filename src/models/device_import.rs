@@ -0,0 +1,28 @@
+/// The query string of `POST /devices/import`
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportDevicesQuery {
+    /// Only validate the file without writing anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// A problem found in a row of the imported file
+#[derive(Debug, PartialEq, Eq, serde::Serialize)]
+pub struct ImportRowError {
+    /// The line number in the file, the header is line 1
+    pub row: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportDevicesResponse {
+    pub dry_run: bool,
+    pub total_rows: usize,
+    /// The number of created devices, always 0 in a dry run or if any row is invalid
+    pub imported: usize,
+    pub errors: Vec<ImportRowError>,
+}
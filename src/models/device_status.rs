@@ -0,0 +1,93 @@
+use chrono::{DateTime, Utc};
+
+/// The lifecycle state of a device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum DeviceStatus {
+    Received,
+    InInventory,
+    InUse,
+    InRepair,
+    Lost,
+    Retired,
+    Scrapped,
+}
+
+impl DeviceStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Received => "received",
+            Self::InInventory => "in_inventory",
+            Self::InUse => "in_use",
+            Self::InRepair => "in_repair",
+            Self::Lost => "lost",
+            Self::Retired => "retired",
+            Self::Scrapped => "scrapped",
+        }
+    }
+
+    /// The transition table of the lifecycle, a scrapped device can't move anymore
+    pub fn can_transition_to(&self, to: DeviceStatus) -> bool {
+        use DeviceStatus::*;
+
+        matches!(
+            (self, to),
+            (Received, InInventory | InUse | InRepair | Lost)
+                | (InInventory, InUse | InRepair | Lost | Retired)
+                | (InUse, InInventory | InRepair | Lost | Retired)
+                | (InRepair, InInventory | InUse | Retired | Scrapped)
+                | (Lost, InInventory | Scrapped)
+                | (Retired, InInventory | Scrapped)
+        )
+    }
+}
+
+impl std::fmt::Display for DeviceStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A record of a device moving from a state to another one
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceStatusChange {
+    pub id: uuid::Uuid,
+    pub device_id: uuid::Uuid,
+    pub from_status: DeviceStatus,
+    pub to_status: DeviceStatus,
+    pub changed_by: uuid::Uuid,
+    pub changed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct DeviceStatusChangeListResponse {
+    pub items: Vec<DeviceStatusChange>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DeviceStatus::{self, *};
+
+    #[test]
+    fn can_transition_to_works() {
+        let test_cases: Vec<(DeviceStatus, DeviceStatus, bool)> = vec![
+            (Received, InInventory, true),
+            (InInventory, InUse, true),
+            (InUse, InRepair, true),
+            (InRepair, Scrapped, true),
+            (Lost, InInventory, true),
+            (Retired, Scrapped, true),
+            (Received, Scrapped, false),
+            (InUse, Received, false),
+            (InUse, InUse, false),
+            (Lost, InUse, false),
+            (Scrapped, InInventory, false),
+        ];
+
+        for (from, to, expected) in test_cases {
+            assert_eq!(from.can_transition_to(to), expected, "{from} -> {to}");
+        }
+    }
+}
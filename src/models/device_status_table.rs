@@ -0,0 +1,11 @@
+#[derive(Debug, Clone, Copy, sea_query::Iden)]
+pub enum DeviceStatusChanges {
+    Table,
+    Id,
+    DeviceId,
+    FromStatus,
+    ToStatus,
+    ChangedBy,
+    ChangedAt,
+    Seq,
+}
@@ -0,0 +1,21 @@
+#[derive(Debug, Clone, Copy, sea_query::Iden)]
+pub enum Devices {
+    Table,
+    Id,
+    Name,
+    OwnerId,
+    Board,
+    Sn,
+    Barcode,
+    ReceivedDate,
+    HwPhase,
+    Note,
+    DeviceTypeId,
+    Attributes,
+    Status,
+    StatusChangedAt,
+    LocationId,
+    Version,
+    DeletedAt,
+    DeletedBy,
+}
@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+
+/// A kind of device, e.g. a phone or a charger
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceType {
+    pub id: uuid::Uuid,
+    pub name: String,
+    /// The JSON Schema of the custom attributes of the devices of this type
+    pub attribute_schema: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The body of creating or replacing a device type, a missing
+/// `attributeSchema` accepts any attributes
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceTypeRequest {
+    pub name: String,
+    #[serde(default = "any_attributes")]
+    pub attribute_schema: serde_json::Value,
+}
+
+fn any_attributes() -> serde_json::Value {
+    serde_json::json!({})
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct DeviceTypeListResponse {
+    pub items: Vec<DeviceType>,
+}
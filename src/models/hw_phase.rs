@@ -0,0 +1,43 @@
+use chrono::{DateTime, Utc};
+
+use super::device::Device;
+
+/// A hardware phase of the catalog, devices move through the phases in the
+/// order of `position`
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct HwPhase {
+    pub id: uuid::Uuid,
+    pub name: String,
+    pub position: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The body of creating or replacing a hardware phase. The phase is inserted
+/// at `position` and the following phases move one place down, the phase goes
+/// last if `position` is missing.
+#[derive(Debug, serde::Deserialize)]
+pub struct HwPhaseRequest {
+    pub name: String,
+    pub position: Option<i32>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct HwPhaseListResponse {
+    pub items: Vec<HwPhase>,
+}
+
+/// The body of `POST /devices/promote`
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromoteDevicesRequest {
+    pub ids: Vec<uuid::Uuid>,
+    /// The phase to move every device to, each device moves to its next
+    /// phase if missing. Skipping phases requires the override permission.
+    pub to_phase: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct PromoteDevicesResponse {
+    pub items: Vec<Device>,
+}
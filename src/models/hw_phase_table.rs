@@ -0,0 +1,8 @@
+#[derive(Debug, Clone, Copy, sea_query::Iden)]
+pub enum HwPhases {
+    Table,
+    Id,
+    Name,
+    Position,
+    CreatedAt,
+}
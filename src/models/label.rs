@@ -0,0 +1,69 @@
+/// The symbologies of a device label
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelKind {
+    #[default]
+    Qr,
+    Code128,
+}
+
+/// The formats of a single device label
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelFormat {
+    #[default]
+    Svg,
+    Png,
+    /// ZPL II for thermal printers, the symbology comes from the template
+    Zpl,
+}
+
+/// The query string of `GET /devices/:id/label`
+#[derive(Debug, serde::Deserialize)]
+pub struct LabelQuery {
+    #[serde(default)]
+    pub kind: LabelKind,
+    #[serde(default)]
+    pub format: LabelFormat,
+    /// The label template of the ZPL format, the default template if missing
+    pub template: Option<uuid::Uuid>,
+}
+
+/// The formats of many device labels
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchLabelFormat {
+    /// A4 sheets
+    #[default]
+    Pdf,
+    /// A ZPL II label per device
+    Zpl,
+}
+
+/// The body of `POST /devices/labels`, the labels are in the order of `ids`
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchLabelsRequest {
+    pub ids: Vec<uuid::Uuid>,
+    #[serde(default)]
+    pub kind: LabelKind,
+    #[serde(default)]
+    pub format: BatchLabelFormat,
+    /// The label template of the ZPL format, the default template if missing
+    pub template_id: Option<uuid::Uuid>,
+}
+
+/// The body of `POST /devices/labels/print`
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrintLabelsRequest {
+    pub ids: Vec<uuid::Uuid>,
+    /// The label template, the default template if missing
+    pub template_id: Option<uuid::Uuid>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct PrintLabelsResponse {
+    /// The number of labels sent to the printer
+    pub printed: usize,
+}
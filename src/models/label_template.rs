@@ -0,0 +1,30 @@
+use chrono::{DateTime, Utc};
+
+/// A ZPL II document with `{{field}}` placeholders for the device fields,
+/// see [`crate::labels::zpl`]
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelTemplate {
+    pub id: uuid::Uuid,
+    pub name: String,
+    pub zpl: String,
+    /// The template used when a request doesn't pick one
+    pub is_default: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The body of creating or replacing a label template, making a template the
+/// default one unsets the previous default
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelTemplateRequest {
+    pub name: String,
+    pub zpl: String,
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct LabelTemplateListResponse {
+    pub items: Vec<LabelTemplate>,
+}
@@ -0,0 +1,9 @@
+#[derive(Debug, Clone, Copy, sea_query::Iden)]
+pub enum LabelTemplates {
+    Table,
+    Id,
+    Name,
+    Zpl,
+    IsDefault,
+    CreatedAt,
+}
@@ -0,0 +1,40 @@
+use chrono::{DateTime, Utc};
+
+/// A record of a device lent to a user, the loan is open until `returned_at` is set
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Loan {
+    pub id: uuid::Uuid,
+    pub device_id: uuid::Uuid,
+    pub borrower_id: uuid::Uuid,
+    pub lent_by: uuid::Uuid,
+    pub checked_out_at: DateTime<Utc>,
+    pub expected_return_at: DateTime<Utc>,
+    pub returned_at: Option<DateTime<Utc>>,
+    pub returned_by: Option<uuid::Uuid>,
+    pub note: Option<String>,
+}
+
+/// The payload of `POST /devices/:id/checkout`, the device is lent to the
+/// authenticated user if `borrower_id` is missing
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckoutRequest {
+    pub borrower_id: Option<uuid::Uuid>,
+    pub expected_return_at: DateTime<Utc>,
+    pub note: Option<String>,
+}
+
+/// The query string of `GET /loans`
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListLoansQuery {
+    pub borrower_id: Option<uuid::Uuid>,
+    #[serde(default)]
+    pub overdue: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct LoanListResponse {
+    pub items: Vec<Loan>,
+}
@@ -0,0 +1,13 @@
+#[derive(Debug, Clone, Copy, sea_query::Iden)]
+pub enum DeviceLoans {
+    Table,
+    Id,
+    DeviceId,
+    BorrowerId,
+    LentBy,
+    CheckedOutAt,
+    ExpectedReturnAt,
+    ReturnedAt,
+    ReturnedBy,
+    Note,
+}
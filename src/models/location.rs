@@ -0,0 +1,120 @@
+use chrono::{DateTime, Utc};
+
+/// The level of a location in the tree, a location can only be under a
+/// location of a higher level, e.g. a shelf in a cabinet or directly in a room
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    serde::Serialize,
+    serde::Deserialize,
+    sqlx::Type,
+)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum LocationKind {
+    Site,
+    Room,
+    Cabinet,
+    Shelf,
+}
+
+impl LocationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Site => "site",
+            Self::Room => "room",
+            Self::Cabinet => "cabinet",
+            Self::Shelf => "shelf",
+        }
+    }
+
+    /// Sites are the roots of the tree, the other kinds can only be under a
+    /// location of a higher level
+    pub fn can_be_under(&self, parent: Option<LocationKind>) -> bool {
+        match parent {
+            None => *self == Self::Site,
+            Some(parent) => parent < *self,
+        }
+    }
+}
+
+impl std::fmt::Display for LocationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A physical place where devices are kept
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    pub id: uuid::Uuid,
+    pub parent_id: Option<uuid::Uuid>,
+    pub kind: LocationKind,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A location with the locations above it
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationDetail {
+    #[serde(flatten)]
+    pub location: Location,
+    /// The locations from the site down to the parent of the location
+    pub path: Vec<Location>,
+}
+
+/// The body of creating or replacing a location
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationRequest {
+    pub parent_id: Option<uuid::Uuid>,
+    pub kind: LocationKind,
+    pub name: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct LocationListResponse {
+    pub items: Vec<Location>,
+}
+
+/// The body of `POST /devices/:id/move`, a null location takes the device out
+/// of every location
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveDeviceRequest {
+    pub location_id: Option<uuid::Uuid>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LocationKind::{self, *};
+
+    #[test]
+    fn can_be_under_works() {
+        let test_cases: Vec<(LocationKind, Option<LocationKind>, bool)> = vec![
+            (Site, None, true),
+            (Room, Some(Site), true),
+            (Shelf, Some(Room), true),
+            (Shelf, Some(Cabinet), true),
+            (Room, None, false),
+            (Site, Some(Site), false),
+            (Cabinet, Some(Shelf), false),
+            (Room, Some(Room), false),
+        ];
+
+        for (kind, parent, expected) in test_cases {
+            assert_eq!(
+                kind.can_be_under(parent),
+                expected,
+                "{kind} under {parent:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,9 @@
+#[derive(Debug, Clone, Copy, sea_query::Iden)]
+pub enum Locations {
+    Table,
+    Id,
+    ParentId,
+    Kind,
+    Name,
+    CreatedAt,
+}
@@ -1,3 +1,7 @@
+use anyhow::Context;
+
+use crate::errors::AuthError;
+
 #[derive(serde::Deserialize)]
 pub struct LoginRequest {
     pub username: String,
@@ -20,4 +24,20 @@ pub struct Claims {
 #[derive(Debug, Clone)]
 pub struct AuthenticatedUser {
     pub user_id: String,
+    pub permissions: Vec<String>,
+}
+
+impl AuthenticatedUser {
+    /// Parse the user id from the `sub` claim of the token
+    pub fn id(&self) -> Result<uuid::Uuid, AuthError> {
+        uuid::Uuid::parse_str(&self.user_id)
+            .context("Failed to parse the user id of the token")
+            .map_err(AuthError::InvalidCredentials)
+    }
+
+    /// Whether the token grants `permission`, for the checks which depend on
+    /// the request instead of the route
+    pub fn has_permission(&self, permission: &str) -> bool {
+        self.permissions.iter().any(|p| p == permission)
+    }
 }
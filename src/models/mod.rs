@@ -1,5 +1,38 @@
+pub mod attachment;
+pub mod attachment_table;
+pub mod board;
+pub mod board_table;
+pub mod comment;
+pub mod comment_table;
 pub mod credentials;
+pub mod device;
+pub mod device_export;
+pub mod device_history;
+pub mod device_history_table;
+pub mod device_import;
+pub mod device_status;
+pub mod device_status_table;
+pub mod device_table;
+pub mod device_type;
+pub mod device_type_table;
 pub mod error_response;
+pub mod hw_phase;
+pub mod hw_phase_table;
+pub mod label;
+pub mod label_template;
+pub mod label_template_table;
+pub mod loan;
+pub mod loan_table;
+pub mod location;
+pub mod location_table;
 pub mod login;
-pub mod user_table;
+pub mod owner;
+pub mod owner_table;
 pub mod permission;
+pub mod reservation;
+pub mod reservation_table;
+pub mod tag;
+pub mod tag_table;
+pub mod team;
+pub mod team_table;
+pub mod user_table;
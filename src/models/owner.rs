@@ -0,0 +1,27 @@
+/// The owner of devices, either a user or a team. The owners created before
+/// they could be linked belong to neither.
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Owner {
+    pub id: uuid::Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub user_id: Option<uuid::Uuid>,
+    pub team_id: Option<uuid::Uuid>,
+}
+
+/// The body of creating or replacing an owner, exactly one of `userId` and
+/// `teamId` is required
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnerRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub user_id: Option<uuid::Uuid>,
+    pub team_id: Option<uuid::Uuid>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct OwnerListResponse {
+    pub items: Vec<Owner>,
+}
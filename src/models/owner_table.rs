@@ -0,0 +1,9 @@
+#[derive(Debug, Clone, Copy, sea_query::Iden)]
+pub enum Owners {
+    Table,
+    Id,
+    Name,
+    Description,
+    UserId,
+    TeamId,
+}
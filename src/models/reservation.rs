@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+
+use super::device::Device;
+
+/// A booking of a device for the window `[starts_at, ends_at)`
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Reservation {
+    pub id: uuid::Uuid,
+    pub device_id: uuid::Uuid,
+    pub reserved_by: uuid::Uuid,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub purpose: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateReservationRequest {
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub purpose: Option<String>,
+}
+
+/// A time window in the query string, both ends are optional
+#[derive(Debug, serde::Deserialize)]
+pub struct TimeWindowQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// The query string of `GET /devices/available`, both ends are required. The
+/// devices are paginated like `GET /devices`.
+#[derive(Debug, serde::Deserialize)]
+pub struct AvailabilityQuery {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub limit: Option<u64>,
+    pub cursor: Option<uuid::Uuid>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ReservationListResponse {
+    pub items: Vec<Reservation>,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AvailableDevicesResponse {
+    pub items: Vec<Device>,
+    pub next_cursor: Option<uuid::Uuid>,
+}
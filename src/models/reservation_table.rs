@@ -0,0 +1,11 @@
+#[derive(Debug, Clone, Copy, sea_query::Iden)]
+pub enum DeviceReservations {
+    Table,
+    Id,
+    DeviceId,
+    ReservedBy,
+    StartsAt,
+    EndsAt,
+    Purpose,
+    CreatedAt,
+}
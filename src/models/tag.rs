@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+
+/// A tag grouping devices ad hoc, such as `thermal-test`
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Tag {
+    pub id: uuid::Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A tag with the number of devices tagged with it
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TagUsage {
+    pub id: uuid::Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub device_count: i64,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct TagUsageListResponse {
+    pub items: Vec<TagUsage>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct TagListResponse {
+    pub items: Vec<Tag>,
+}
+
+/// The body of `POST /devices/:id/tags`, the missing tags are created
+#[derive(Debug, serde::Deserialize)]
+pub struct TagDeviceRequest {
+    pub tags: Vec<String>,
+}
+
+/// The body of `POST /devices/tags`, every device gets the tags of `add` and
+/// loses the tags of `remove`
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetagDevicesRequest {
+    pub device_ids: Vec<uuid::Uuid>,
+    #[serde(default)]
+    pub add: Vec<String>,
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+/// How the tags of the `tags` filter of `GET /devices` are combined
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagMatch {
+    /// A device has at least one of the tags
+    #[default]
+    Any,
+    /// A device has every tag
+    All,
+}
@@ -0,0 +1,15 @@
+#[derive(Debug, Clone, Copy, sea_query::Iden)]
+pub enum Tags {
+    Table,
+    Id,
+    Name,
+    CreatedAt,
+}
+
+#[derive(Debug, Clone, Copy, sea_query::Iden)]
+pub enum DeviceTags {
+    Table,
+    DeviceId,
+    TagId,
+    CreatedAt,
+}
@@ -0,0 +1,86 @@
+use chrono::{DateTime, Utc};
+
+/// The role of a user in a team, managers can change the members
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, sqlx::Type,
+)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum TeamRole {
+    #[default]
+    Member,
+    Manager,
+}
+
+impl TeamRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Member => "member",
+            Self::Manager => "manager",
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Team {
+    pub id: uuid::Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    /// The permissions granted to every member
+    pub permissions: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The body of creating or replacing a team
+#[derive(Debug, serde::Deserialize)]
+pub struct TeamRequest {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub permissions: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct TeamListResponse {
+    pub items: Vec<Team>,
+}
+
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamMember {
+    pub user_id: uuid::Uuid,
+    pub username: String,
+    pub role: TeamRole,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The body of `POST /teams/:id/members`, adding an existing member changes
+/// the role
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamMemberRequest {
+    pub user_id: uuid::Uuid,
+    #[serde(default)]
+    pub role: TeamRole,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct TeamMemberListResponse {
+    pub items: Vec<TeamMember>,
+}
+
+/// A team of a user with what it grants, read when the user logs in
+#[derive(Debug, sqlx::FromRow)]
+pub struct Membership {
+    pub team_id: uuid::Uuid,
+    pub role: TeamRole,
+    pub permissions: Vec<String>,
+}
+
+impl Membership {
+    /// The role carried by the token, e.g. `team:<id>:manager`
+    pub fn claim_role(&self) -> String {
+        format!("team:{}:{}", self.team_id, self.role.as_str())
+    }
+}
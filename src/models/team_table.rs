@@ -0,0 +1,18 @@
+#[derive(Debug, Clone, Copy, sea_query::Iden)]
+pub enum Teams {
+    Table,
+    Id,
+    Name,
+    Description,
+    Permissions,
+    CreatedAt,
+}
+
+#[derive(Debug, Clone, Copy, sea_query::Iden)]
+pub enum TeamMembers {
+    Table,
+    TeamId,
+    UserId,
+    Role,
+    CreatedAt,
+}
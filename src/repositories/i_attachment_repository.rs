@@ -0,0 +1,27 @@
+use crate::models::attachment::{Attachment, NewAttachment};
+
+#[async_trait::async_trait]
+pub trait IAttachmentRepository {
+    /// Every attachment of a device, the oldest first
+    async fn list(&self, device_id: uuid::Uuid) -> anyhow::Result<Vec<Attachment>>;
+
+    /// Every attachment of the devices among `device_ids`
+    async fn list_many(&self, device_ids: &[uuid::Uuid]) -> anyhow::Result<Vec<Attachment>>;
+
+    async fn get(
+        &self,
+        device_id: uuid::Uuid,
+        id: uuid::Uuid,
+    ) -> anyhow::Result<Option<Attachment>>;
+
+    /// Record an uploaded file, an unknown device fails with `AppError::NotFound`
+    async fn create(&self, attachment: NewAttachment) -> anyhow::Result<Attachment>;
+
+    /// Delete an attachment and return it so its content can be removed from
+    /// the storage, return `None` if the attachment doesn't exist
+    async fn delete(
+        &self,
+        device_id: uuid::Uuid,
+        id: uuid::Uuid,
+    ) -> anyhow::Result<Option<Attachment>>;
+}
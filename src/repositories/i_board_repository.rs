@@ -0,0 +1,27 @@
+use crate::models::board::{Board, BoardRequest};
+
+#[async_trait::async_trait]
+pub trait IBoardRepository {
+    /// Every board ordered by name and revision
+    async fn list(&self) -> anyhow::Result<Vec<Board>>;
+
+    async fn get(&self, id: uuid::Uuid) -> anyhow::Result<Option<Board>>;
+
+    /// Creating or renaming a board to the name and revision of another board
+    /// fails with `AppError::Conflict`
+    async fn create(&self, request: BoardRequest) -> anyhow::Result<Board>;
+
+    /// Replace the fields of a board, the devices follow a new name or
+    /// revision and record it in their history. Return `None` if the board
+    /// doesn't exist.
+    async fn update(
+        &self,
+        id: uuid::Uuid,
+        request: BoardRequest,
+        updated_by: uuid::Uuid,
+    ) -> anyhow::Result<Option<Board>>;
+
+    /// Delete a board, return `false` if the board doesn't exist. Deleting a
+    /// board used by devices fails with `AppError::Conflict`.
+    async fn delete(&self, id: uuid::Uuid) -> anyhow::Result<bool>;
+}
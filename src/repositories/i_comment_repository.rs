@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+
+use crate::models::comment::{Comment, Mention};
+
+#[async_trait::async_trait]
+pub trait ICommentRepository {
+    /// Every comment of a device, the oldest first
+    async fn list(&self, device_id: uuid::Uuid) -> anyhow::Result<Vec<Comment>>;
+
+    async fn get(&self, device_id: uuid::Uuid, id: uuid::Uuid) -> anyhow::Result<Option<Comment>>;
+
+    /// Create a comment mentioning the existing users among `mentions`, an
+    /// unknown device fails with `AppError::NotFound`
+    async fn create(
+        &self,
+        device_id: uuid::Uuid,
+        author_id: uuid::Uuid,
+        body: String,
+        mentions: &[String],
+    ) -> anyhow::Result<Comment>;
+
+    /// Replace the body and the mentions of a comment, the users still
+    /// mentioned keep their mention. Return `None` if the comment doesn't exist.
+    async fn update(
+        &self,
+        device_id: uuid::Uuid,
+        id: uuid::Uuid,
+        body: String,
+        mentions: &[String],
+    ) -> anyhow::Result<Option<Comment>>;
+
+    /// Delete a comment, return `false` if the comment doesn't exist
+    async fn delete(&self, device_id: uuid::Uuid, id: uuid::Uuid) -> anyhow::Result<bool>;
+
+    /// The mentions of a user on the devices out of the trash, the latest first
+    async fn mentions(
+        &self,
+        user_id: uuid::Uuid,
+        since: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<Vec<Mention>>;
+}
@@ -0,0 +1,154 @@
+use chrono::{DateTime, Utc};
+use futures_util::stream::BoxStream;
+
+use crate::errors::AppError;
+use crate::models::device::{
+    CreateDeviceRequest, Device, DeviceSearchHit, IfMatch, ListDevicesQuery, TrashedDevice,
+    UpdateDeviceRequest,
+};
+use crate::models::device_history::DeviceHistoryEntry;
+use crate::models::device_status::{DeviceStatus, DeviceStatusChange};
+
+#[async_trait::async_trait]
+pub trait IDeviceRepository {
+    /// List one page of devices matching the filters of `query`, together with
+    /// the number of matching devices. `cursor` is the last device of the
+    /// previous page, the page starts right after it in the requested order.
+    async fn list(
+        &self,
+        query: &ListDevicesQuery,
+        limit: u64,
+        cursor: Option<Device>,
+    ) -> anyhow::Result<(Vec<Device>, i64)>;
+
+    /// Stream every device matching the filters of `query` in the requested
+    /// order, the pagination of `query` is ignored. The rows are read from
+    /// the database as the stream is consumed.
+    async fn export(
+        &self,
+        query: &ListDevicesQuery,
+    ) -> anyhow::Result<BoxStream<'static, anyhow::Result<Device>>>;
+
+    /// Search devices by words of the name, board, serial number, barcode and
+    /// note, serial numbers and barcodes also match with typos. The best
+    /// matches come first.
+    async fn search(&self, text: &str, limit: u64) -> anyhow::Result<Vec<DeviceSearchHit>>;
+
+    /// The devices without any reservation overlapping `[from, to)`, by name
+    /// and after `cursor`
+    async fn available(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: u64,
+        cursor: Option<Device>,
+    ) -> anyhow::Result<Vec<Device>>;
+
+    async fn get(&self, id: uuid::Uuid) -> anyhow::Result<Option<Device>>;
+
+    /// The devices among `ids` which exist, in no particular order
+    async fn get_many(&self, ids: &[uuid::Uuid]) -> anyhow::Result<Vec<Device>>;
+
+    /// Barcodes and serial numbers are unique, at most one device matches
+    async fn get_by_barcode(&self, barcode: &str) -> anyhow::Result<Option<Device>>;
+
+    async fn get_by_sn(&self, sn: &str) -> anyhow::Result<Option<Device>>;
+
+    /// Every mutation below also appends an entry to the device history,
+    /// in the same transaction. Creating or updating a device with the serial
+    /// number or barcode of another device fails with `AppError::Conflict`.
+    async fn create(
+        &self,
+        request: CreateDeviceRequest,
+        created_by: uuid::Uuid,
+    ) -> anyhow::Result<Device>;
+
+    /// Create all the devices in one transaction and return the outcome of
+    /// every request, a request fails with the errors of `create`. Either all
+    /// of them are created or none, nothing is written on `dry_run`.
+    async fn create_many(
+        &self,
+        requests: Vec<CreateDeviceRequest>,
+        dry_run: bool,
+        created_by: uuid::Uuid,
+    ) -> anyhow::Result<Vec<Result<Device, AppError>>>;
+
+    /// Move every device to `to_phase`, or to the phase after its current one,
+    /// in one transaction. Moving backwards or past the last phase fails with
+    /// `AppError::Conflict`, skipping phases fails with `AuthError::Forbidden`
+    /// unless `allow_skip`.
+    async fn promote(
+        &self,
+        ids: &[uuid::Uuid],
+        to_phase: Option<&str>,
+        allow_skip: bool,
+        promoted_by: uuid::Uuid,
+    ) -> anyhow::Result<Vec<Device>>;
+
+    /// Replace the fields of a device, return `None` if the device doesn't exist.
+    /// A device whose version doesn't match `if_match` fails with
+    /// `AppError::PreconditionFailed`, the same goes for `delete`.
+    async fn update(
+        &self,
+        id: uuid::Uuid,
+        request: UpdateDeviceRequest,
+        if_match: &IfMatch,
+        updated_by: uuid::Uuid,
+    ) -> anyhow::Result<Option<Device>>;
+
+    /// Move a device to the trash, where every query above ignores it. Return
+    /// `false` if the device doesn't exist or is already in the trash.
+    async fn delete(
+        &self,
+        id: uuid::Uuid,
+        if_match: &IfMatch,
+        deleted_by: uuid::Uuid,
+    ) -> anyhow::Result<bool>;
+
+    /// The devices in the trash, the latest deleted first
+    async fn trash(&self) -> anyhow::Result<Vec<TrashedDevice>>;
+
+    /// Take a device out of the trash, return `None` if it isn't in the trash.
+    /// Restoring a device whose serial number or barcode has been taken by
+    /// another device fails with `AppError::Conflict`.
+    async fn restore(
+        &self,
+        id: uuid::Uuid,
+        restored_by: uuid::Uuid,
+    ) -> anyhow::Result<Option<Device>>;
+
+    /// Remove the devices deleted before `deleted_before` for good, with
+    /// everything attached to them, and return their ids
+    async fn purge(
+        &self,
+        deleted_before: DateTime<Utc>,
+        purged_by: uuid::Uuid,
+    ) -> anyhow::Result<Vec<uuid::Uuid>>;
+
+    /// Move a device to a location, or out of every location with `None`,
+    /// return `None` if the device doesn't exist. An unknown location fails
+    /// with `AppError::InvalidRequest`.
+    async fn relocate(
+        &self,
+        id: uuid::Uuid,
+        location_id: Option<uuid::Uuid>,
+        moved_by: uuid::Uuid,
+    ) -> anyhow::Result<Option<Device>>;
+
+    /// Move a device from the status `from` to `to` and record the change,
+    /// return `None` if the device isn't in the status `from` anymore
+    async fn transition(
+        &self,
+        id: uuid::Uuid,
+        from: DeviceStatus,
+        to: DeviceStatus,
+        changed_by: uuid::Uuid,
+    ) -> anyhow::Result<Option<Device>>;
+
+    /// Every status change of a device, the earliest first
+    async fn status_history(&self, id: uuid::Uuid) -> anyhow::Result<Vec<DeviceStatusChange>>;
+
+    /// Every mutation of a device, the earliest first. The history is kept
+    /// after the device is deleted.
+    async fn history(&self, id: uuid::Uuid) -> anyhow::Result<Vec<DeviceHistoryEntry>>;
+}
@@ -0,0 +1,26 @@
+use crate::models::device_type::{DeviceType, DeviceTypeRequest};
+
+#[async_trait::async_trait]
+pub trait IDeviceTypeRepository {
+    /// Every device type ordered by name
+    async fn list(&self) -> anyhow::Result<Vec<DeviceType>>;
+
+    async fn get(&self, id: uuid::Uuid) -> anyhow::Result<Option<DeviceType>>;
+
+    /// Creating or renaming a type to the name of another type fails with
+    /// `AppError::Conflict`
+    async fn create(&self, request: DeviceTypeRequest) -> anyhow::Result<DeviceType>;
+
+    /// Replace the name and the attribute schema of a type, return `None` if
+    /// the type doesn't exist. A schema which the attributes of existing
+    /// devices don't match fails with `AppError::Conflict`.
+    async fn update(
+        &self,
+        id: uuid::Uuid,
+        request: DeviceTypeRequest,
+    ) -> anyhow::Result<Option<DeviceType>>;
+
+    /// Delete a type, return `false` if the type doesn't exist. Deleting a
+    /// type used by devices fails with `AppError::Conflict`.
+    async fn delete(&self, id: uuid::Uuid) -> anyhow::Result<bool>;
+}
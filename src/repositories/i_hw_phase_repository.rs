@@ -0,0 +1,24 @@
+use crate::models::hw_phase::{HwPhase, HwPhaseRequest};
+
+#[async_trait::async_trait]
+pub trait IHwPhaseRepository {
+    /// Every phase in order
+    async fn list(&self) -> anyhow::Result<Vec<HwPhase>>;
+
+    /// Creating or renaming a phase to the name of another phase, regardless
+    /// of case, fails with `AppError::Conflict`
+    async fn create(&self, request: HwPhaseRequest) -> anyhow::Result<HwPhase>;
+
+    /// Rename or move a phase, the devices follow the new name and record it
+    /// in their history. Return `None` if the phase doesn't exist.
+    async fn update(
+        &self,
+        id: uuid::Uuid,
+        request: HwPhaseRequest,
+        updated_by: uuid::Uuid,
+    ) -> anyhow::Result<Option<HwPhase>>;
+
+    /// Delete a phase, return `false` if the phase doesn't exist. Deleting a
+    /// phase used by devices fails with `AppError::Conflict`.
+    async fn delete(&self, id: uuid::Uuid) -> anyhow::Result<bool>;
+}
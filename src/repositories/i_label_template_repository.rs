@@ -0,0 +1,26 @@
+use crate::models::label_template::{LabelTemplate, LabelTemplateRequest};
+
+#[async_trait::async_trait]
+pub trait ILabelTemplateRepository {
+    /// Every template ordered by name
+    async fn list(&self) -> anyhow::Result<Vec<LabelTemplate>>;
+
+    async fn get(&self, id: uuid::Uuid) -> anyhow::Result<Option<LabelTemplate>>;
+
+    /// The template used when a request doesn't pick one, if any
+    async fn get_default(&self) -> anyhow::Result<Option<LabelTemplate>>;
+
+    /// Creating or renaming a template to the name of another template fails
+    /// with `AppError::Conflict`
+    async fn create(&self, request: LabelTemplateRequest) -> anyhow::Result<LabelTemplate>;
+
+    /// Replace a template, return `None` if the template doesn't exist
+    async fn update(
+        &self,
+        id: uuid::Uuid,
+        request: LabelTemplateRequest,
+    ) -> anyhow::Result<Option<LabelTemplate>>;
+
+    /// Delete a template, return `false` if the template doesn't exist
+    async fn delete(&self, id: uuid::Uuid) -> anyhow::Result<bool>;
+}
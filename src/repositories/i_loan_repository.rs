@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+
+use crate::models::loan::Loan;
+
+#[async_trait::async_trait]
+pub trait ILoanRepository {
+    /// Open a loan of a device, return `None` if the device is already lent
+    async fn checkout(
+        &self,
+        device_id: uuid::Uuid,
+        borrower_id: uuid::Uuid,
+        lent_by: uuid::Uuid,
+        expected_return_at: DateTime<Utc>,
+        note: Option<String>,
+    ) -> anyhow::Result<Option<Loan>>;
+
+    /// Close the open loan of a device, return `None` if the device isn't lent
+    async fn checkin(
+        &self,
+        device_id: uuid::Uuid,
+        returned_by: uuid::Uuid,
+    ) -> anyhow::Result<Option<Loan>>;
+
+    /// Every loan of a device, the latest first
+    async fn history(&self, device_id: uuid::Uuid) -> anyhow::Result<Vec<Loan>>;
+
+    /// The open loans of the devices out of the trash, optionally only the
+    /// ones of a borrower or the ones which should have been returned before
+    /// `overdue_at`
+    async fn list_open(
+        &self,
+        borrower_id: Option<uuid::Uuid>,
+        overdue_at: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<Vec<Loan>>;
+}
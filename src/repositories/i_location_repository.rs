@@ -0,0 +1,29 @@
+use crate::models::location::{Location, LocationDetail, LocationRequest};
+
+#[async_trait::async_trait]
+pub trait ILocationRepository {
+    /// Every location ordered by name, the tree is built from the `parentId`
+    async fn list(&self) -> anyhow::Result<Vec<Location>>;
+
+    /// A location with its path from the site
+    async fn get(&self, id: uuid::Uuid) -> anyhow::Result<Option<LocationDetail>>;
+
+    /// Creating or renaming a location to the name of a sibling fails with
+    /// `AppError::Conflict`, an unknown parent or a parent of a lower level
+    /// fails with `AppError::InvalidRequest`
+    async fn create(&self, request: LocationRequest) -> anyhow::Result<Location>;
+
+    /// Replace the fields of a location, its sub-locations and devices move
+    /// along with it. Moving a location under itself fails with
+    /// `AppError::InvalidRequest`. Return `None` if the location doesn't exist.
+    async fn update(
+        &self,
+        id: uuid::Uuid,
+        request: LocationRequest,
+    ) -> anyhow::Result<Option<Location>>;
+
+    /// Delete a location, return `false` if the location doesn't exist.
+    /// Deleting a location with sub-locations or devices fails with
+    /// `AppError::Conflict`.
+    async fn delete(&self, id: uuid::Uuid) -> anyhow::Result<bool>;
+}
@@ -0,0 +1,21 @@
+use crate::models::owner::{Owner, OwnerRequest};
+
+#[async_trait::async_trait]
+pub trait IOwnerRepository {
+    /// Every owner ordered by name
+    async fn list(&self) -> anyhow::Result<Vec<Owner>>;
+
+    async fn get(&self, id: uuid::Uuid) -> anyhow::Result<Option<Owner>>;
+
+    /// A user or a team has a single owner, a second one fails with
+    /// `AppError::Conflict`. An unknown user or team fails with
+    /// `AppError::InvalidRequest`.
+    async fn create(&self, request: OwnerRequest) -> anyhow::Result<Owner>;
+
+    /// Replace the fields of an owner, return `None` if the owner doesn't exist
+    async fn update(&self, id: uuid::Uuid, request: OwnerRequest) -> anyhow::Result<Option<Owner>>;
+
+    /// Delete an owner, return `false` if the owner doesn't exist. Deleting an
+    /// owner of devices fails with `AppError::Conflict`.
+    async fn delete(&self, id: uuid::Uuid) -> anyhow::Result<bool>;
+}
@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+
+use crate::models::reservation::Reservation;
+
+#[async_trait::async_trait]
+pub trait IReservationRepository {
+    /// Book a device, return `None` if the window overlaps another reservation
+    async fn create(
+        &self,
+        device_id: uuid::Uuid,
+        reserved_by: uuid::Uuid,
+        starts_at: DateTime<Utc>,
+        ends_at: DateTime<Utc>,
+        purpose: Option<String>,
+    ) -> anyhow::Result<Option<Reservation>>;
+
+    /// The reservations of a device which overlap the window, the earliest first
+    async fn schedule(
+        &self,
+        device_id: uuid::Uuid,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<Vec<Reservation>>;
+
+    async fn get(
+        &self,
+        device_id: uuid::Uuid,
+        id: uuid::Uuid,
+    ) -> anyhow::Result<Option<Reservation>>;
+
+    /// Cancel a reservation, return `false` if the reservation doesn't exist
+    async fn delete(&self, device_id: uuid::Uuid, id: uuid::Uuid) -> anyhow::Result<bool>;
+}
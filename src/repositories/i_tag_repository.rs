@@ -0,0 +1,26 @@
+use crate::models::tag::{Tag, TagUsage};
+
+#[async_trait::async_trait]
+pub trait ITagRepository {
+    /// Every tag with its number of devices, ordered by name
+    async fn list(&self) -> anyhow::Result<Vec<TagUsage>>;
+
+    /// Delete a tag from every device, return `false` if the tag doesn't exist
+    async fn delete(&self, id: uuid::Uuid) -> anyhow::Result<bool>;
+
+    /// The tags of a device ordered by name
+    async fn device_tags(&self, device_id: uuid::Uuid) -> anyhow::Result<Vec<Tag>>;
+
+    /// Tag every device with `add`, creating the missing tags, and untag them
+    /// from `remove` at once. An unknown device fails with
+    /// `AppError::InvalidRequest` and changes nothing.
+    async fn retag(
+        &self,
+        device_ids: &[uuid::Uuid],
+        add: &[String],
+        remove: &[String],
+    ) -> anyhow::Result<()>;
+
+    /// Untag a device, return `false` if the device doesn't have the tag
+    async fn untag(&self, device_id: uuid::Uuid, name: &str) -> anyhow::Result<bool>;
+}
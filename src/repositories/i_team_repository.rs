@@ -0,0 +1,48 @@
+use crate::models::team::{Membership, Team, TeamMember, TeamMemberRequest, TeamRequest, TeamRole};
+
+#[async_trait::async_trait]
+pub trait ITeamRepository {
+    /// Every team ordered by name
+    async fn list(&self) -> anyhow::Result<Vec<Team>>;
+
+    async fn get(&self, id: uuid::Uuid) -> anyhow::Result<Option<Team>>;
+
+    /// Creating or renaming a team to the name of another team fails with
+    /// `AppError::Conflict`
+    async fn create(&self, request: TeamRequest) -> anyhow::Result<Team>;
+
+    /// Replace the fields of a team, return `None` if the team doesn't exist
+    async fn update(&self, id: uuid::Uuid, request: TeamRequest) -> anyhow::Result<Option<Team>>;
+
+    /// Delete a team with its memberships and its owner, return `false` if
+    /// the team doesn't exist. Deleting a team which owns devices fails with
+    /// `AppError::Conflict`.
+    async fn delete(&self, id: uuid::Uuid) -> anyhow::Result<bool>;
+
+    /// The members of a team ordered by username, `None` if the team doesn't
+    /// exist
+    async fn members(&self, team_id: uuid::Uuid) -> anyhow::Result<Option<Vec<TeamMember>>>;
+
+    /// The role of a user in a team, `None` if the user isn't a member
+    async fn role(
+        &self,
+        team_id: uuid::Uuid,
+        user_id: uuid::Uuid,
+    ) -> anyhow::Result<Option<TeamRole>>;
+
+    /// Add a user to a team or change the role of a member. Return `None` if
+    /// the team doesn't exist, an unknown user fails with
+    /// `AppError::InvalidRequest`.
+    async fn add_member(
+        &self,
+        team_id: uuid::Uuid,
+        request: TeamMemberRequest,
+    ) -> anyhow::Result<Option<TeamMember>>;
+
+    /// Remove a user from a team, return `false` if the user isn't a member
+    async fn remove_member(&self, team_id: uuid::Uuid, user_id: uuid::Uuid)
+        -> anyhow::Result<bool>;
+
+    /// The teams of a user with the permissions they grant
+    async fn memberships(&self, user_id: uuid::Uuid) -> anyhow::Result<Vec<Membership>>;
+}
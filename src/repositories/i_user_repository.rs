@@ -6,4 +6,6 @@ pub trait IUserRespository {
         &self,
         username: &str,
     ) -> anyhow::Result<Option<(uuid::Uuid, Secret<String>)>>;
+
+    async fn exists(&self, id: uuid::Uuid) -> anyhow::Result<bool>;
 }
@@ -1,2 +1,34 @@
+pub mod i_attachment_repository;
+pub mod i_board_repository;
+pub mod i_comment_repository;
+pub mod i_device_repository;
+pub mod i_device_type_repository;
+pub mod i_hw_phase_repository;
+pub mod i_label_template_repository;
+pub mod i_loan_repository;
+pub mod i_location_repository;
+pub mod i_owner_repository;
+pub mod i_reservation_repository;
+pub mod i_tag_repository;
+pub mod i_team_repository;
 pub mod i_user_repository;
+pub mod postgres_attachment_repository;
+pub mod postgres_board_repository;
+pub mod postgres_comment_repository;
+pub mod postgres_device_repository;
+pub mod postgres_device_type_repository;
+pub mod postgres_hw_phase_repository;
+pub mod postgres_label_template_repository;
+pub mod postgres_loan_repository;
+pub mod postgres_location_repository;
+pub mod postgres_owner_repository;
+pub mod postgres_reservation_repository;
+pub mod postgres_tag_repository;
+pub mod postgres_team_repository;
 pub mod postgres_user_repository;
+
+/// The SQLSTATEs of the constraint violations which the repositories turn
+/// into expected errors
+pub(crate) const UNIQUE_VIOLATION: &str = "23505";
+pub(crate) const FOREIGN_KEY_VIOLATION: &str = "23503";
+pub(crate) const EXCLUSION_VIOLATION: &str = "23P01";
@@ -0,0 +1,171 @@
+use anyhow::Context;
+use sea_query::{Expr, Order, PostgresQueryBuilder, Query};
+
+use crate::{
+    errors::AppError,
+    models::{
+        attachment::{Attachment, NewAttachment},
+        attachment_table::DeviceAttachments,
+    },
+    utils::PostgresSession,
+};
+
+use super::i_attachment_repository::IAttachmentRepository;
+use super::FOREIGN_KEY_VIOLATION;
+
+pub struct PostgresAttachmentRepository {
+    session: PostgresSession,
+}
+
+impl PostgresAttachmentRepository {
+    pub fn new(session: PostgresSession) -> Self {
+        Self { session }
+    }
+}
+
+const ATTACHMENT_COLUMNS: [DeviceAttachments; 9] = [
+    DeviceAttachments::Id,
+    DeviceAttachments::DeviceId,
+    DeviceAttachments::FileName,
+    DeviceAttachments::ContentType,
+    DeviceAttachments::SizeBytes,
+    DeviceAttachments::StorageKey,
+    DeviceAttachments::UploadedBy,
+    DeviceAttachments::CreatedAt,
+    DeviceAttachments::HasThumbnails,
+];
+
+#[async_trait::async_trait]
+impl IAttachmentRepository for PostgresAttachmentRepository {
+    async fn list(&self, device_id: uuid::Uuid) -> anyhow::Result<Vec<Attachment>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(ATTACHMENT_COLUMNS)
+            .from(DeviceAttachments::Table)
+            .and_where(Expr::col(DeviceAttachments::DeviceId).eq(device_id))
+            .order_by(DeviceAttachments::CreatedAt, Order::Asc)
+            .order_by(DeviceAttachments::Id, Order::Asc)
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Attachment>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to list the attachments of a device")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn list_many(&self, device_ids: &[uuid::Uuid]) -> anyhow::Result<Vec<Attachment>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(ATTACHMENT_COLUMNS)
+            .from(DeviceAttachments::Table)
+            .and_where(Expr::col(DeviceAttachments::DeviceId).is_in(device_ids.iter().copied()))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Attachment>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to list the attachments of devices")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn get(
+        &self,
+        device_id: uuid::Uuid,
+        id: uuid::Uuid,
+    ) -> anyhow::Result<Option<Attachment>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(ATTACHMENT_COLUMNS)
+            .from(DeviceAttachments::Table)
+            .and_where(Expr::col(DeviceAttachments::DeviceId).eq(device_id))
+            .and_where(Expr::col(DeviceAttachments::Id).eq(id))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Attachment>(&sql)
+            .fetch_optional(&mut *conn)
+            .await
+            .context("Failed to perform a sql to get an attachment")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn create(&self, attachment: NewAttachment) -> anyhow::Result<Attachment> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::insert()
+            .into_table(DeviceAttachments::Table)
+            .columns([
+                DeviceAttachments::Id,
+                DeviceAttachments::DeviceId,
+                DeviceAttachments::FileName,
+                DeviceAttachments::ContentType,
+                DeviceAttachments::SizeBytes,
+                DeviceAttachments::StorageKey,
+                DeviceAttachments::UploadedBy,
+                DeviceAttachments::HasThumbnails,
+            ])
+            .values_panic([
+                attachment.id.into(),
+                attachment.device_id.into(),
+                attachment.file_name.into(),
+                attachment.content_type.into(),
+                attachment.size_bytes.into(),
+                attachment.storage_key.into(),
+                attachment.uploaded_by.into(),
+                attachment.has_thumbnails.into(),
+            ])
+            .returning(Query::returning().columns(ATTACHMENT_COLUMNS))
+            .to_string(PostgresQueryBuilder);
+
+        let res = match sqlx::query_as::<_, Attachment>(&sql)
+            .fetch_one(&mut *conn)
+            .await
+        {
+            Ok(res) => res,
+            // The device was deleted while the file was uploaded
+            Err(sqlx::Error::Database(e))
+                if e.code().as_deref() == Some(FOREIGN_KEY_VIOLATION)
+                    && e.constraint() == Some("device_attachments_device_id_fkey") =>
+            {
+                return Err(AppError::NotFound)?;
+            }
+            Err(e) => Err(e)
+                .context("Failed to perform a sql to create an attachment")
+                .map_err(AppError::UnexpectedError)?,
+        };
+
+        Ok(res)
+    }
+
+    async fn delete(
+        &self,
+        device_id: uuid::Uuid,
+        id: uuid::Uuid,
+    ) -> anyhow::Result<Option<Attachment>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::delete()
+            .from_table(DeviceAttachments::Table)
+            .and_where(Expr::col(DeviceAttachments::DeviceId).eq(device_id))
+            .and_where(Expr::col(DeviceAttachments::Id).eq(id))
+            .returning(Query::returning().columns(ATTACHMENT_COLUMNS))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Attachment>(&sql)
+            .fetch_optional(&mut *conn)
+            .await
+            .context("Failed to perform a sql to delete an attachment")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+}
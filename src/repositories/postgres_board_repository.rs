@@ -0,0 +1,212 @@
+use anyhow::Context;
+use sea_query::{Expr, Order, PostgresQueryBuilder, Query};
+use sqlx::{Connection, Row};
+
+use crate::{
+    errors::AppError,
+    models::{
+        board::{Board, BoardRequest},
+        board_table::Boards,
+        device_table::Devices,
+    },
+    utils::PostgresSession,
+};
+
+use super::i_board_repository::IBoardRepository;
+use super::postgres_device_repository::record_cascaded_rename;
+use super::{FOREIGN_KEY_VIOLATION, UNIQUE_VIOLATION};
+
+pub struct PostgresBoardRepository {
+    session: PostgresSession,
+}
+
+impl PostgresBoardRepository {
+    pub fn new(session: PostgresSession) -> Self {
+        Self { session }
+    }
+}
+
+const BOARD_COLUMNS: [Boards; 7] = [
+    Boards::Id,
+    Boards::Name,
+    Boards::Revision,
+    Boards::Vendor,
+    Boards::Description,
+    Boards::FullName,
+    Boards::CreatedAt,
+];
+
+/// Turn a duplicated board into a conflict, any other error is unexpected
+fn write_error(e: sqlx::Error, request: &BoardRequest, context: &'static str) -> anyhow::Error {
+    match &e {
+        sqlx::Error::Database(db)
+            if db.code().as_deref() == Some(UNIQUE_VIOLATION)
+                && db.constraint() == Some("boards_full_name_key") =>
+        {
+            let full_name = match request.revision.as_str() {
+                "" => request.name.clone(),
+                revision => format!("{} rev {revision}", request.name),
+            };
+            AppError::Conflict(format!("the board `{full_name}` already exists")).into()
+        }
+        _ => AppError::UnexpectedError(anyhow::Error::new(e).context(context)).into(),
+    }
+}
+
+#[async_trait::async_trait]
+impl IBoardRepository for PostgresBoardRepository {
+    async fn list(&self) -> anyhow::Result<Vec<Board>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(BOARD_COLUMNS)
+            .from(Boards::Table)
+            .order_by(Boards::Name, Order::Asc)
+            .order_by(Boards::Revision, Order::Asc)
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Board>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to list boards")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn get(&self, id: uuid::Uuid) -> anyhow::Result<Option<Board>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(BOARD_COLUMNS)
+            .from(Boards::Table)
+            .and_where(Expr::col(Boards::Id).eq(id))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Board>(&sql)
+            .fetch_optional(&mut *conn)
+            .await
+            .context("Failed to perform a sql to get a board")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn create(&self, request: BoardRequest) -> anyhow::Result<Board> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::insert()
+            .into_table(Boards::Table)
+            .columns([
+                Boards::Id,
+                Boards::Name,
+                Boards::Revision,
+                Boards::Vendor,
+                Boards::Description,
+            ])
+            .values_panic([
+                uuid::Uuid::new_v4().into(),
+                request.name.clone().into(),
+                request.revision.clone().into(),
+                request.vendor.clone().into(),
+                request.description.clone().into(),
+            ])
+            .returning(Query::returning().columns(BOARD_COLUMNS))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Board>(&sql)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| write_error(e, &request, "Failed to perform a sql to create a board"))?;
+
+        Ok(res)
+    }
+
+    async fn update(
+        &self,
+        id: uuid::Uuid,
+        request: BoardRequest,
+        updated_by: uuid::Uuid,
+    ) -> anyhow::Result<Option<Board>> {
+        let mut conn = self.session.get_session().await?;
+
+        let mut tx = conn
+            .begin()
+            .await
+            .context("Failed to begin a transaction")
+            .map_err(AppError::UnexpectedError)?;
+
+        let sql = Query::select()
+            .column(Boards::FullName)
+            .from(Boards::Table)
+            .and_where(Expr::col(Boards::Id).eq(id))
+            .lock_exclusive()
+            .to_string(PostgresQueryBuilder);
+
+        let old_full_name = match sqlx::query(&sql)
+            .fetch_optional(&mut *tx)
+            .await
+            .context("Failed to perform a sql to lock a board")
+            .map_err(AppError::UnexpectedError)?
+        {
+            Some(row) => row.get::<String, usize>(0),
+            None => return Ok(None),
+        };
+
+        let sql = Query::update()
+            .table(Boards::Table)
+            .values([
+                (Boards::Name, request.name.clone().into()),
+                (Boards::Revision, request.revision.clone().into()),
+                (Boards::Vendor, request.vendor.clone().into()),
+                (Boards::Description, request.description.clone().into()),
+            ])
+            .and_where(Expr::col(Boards::Id).eq(id))
+            .returning(Query::returning().columns(BOARD_COLUMNS))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Board>(&sql)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| write_error(e, &request, "Failed to perform a sql to update a board"))?;
+        record_cascaded_rename(
+            &mut tx,
+            Devices::Board,
+            "board",
+            &old_full_name,
+            &res.full_name,
+            updated_by,
+        )
+        .await?;
+
+        tx.commit()
+            .await
+            .context("Failed to commit the board update")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(Some(res))
+    }
+
+    async fn delete(&self, id: uuid::Uuid) -> anyhow::Result<bool> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::delete()
+            .from_table(Boards::Table)
+            .and_where(Expr::col(Boards::Id).eq(id))
+            .to_string(PostgresQueryBuilder);
+
+        let res = match sqlx::query(&sql).execute(&mut *conn).await {
+            Ok(res) => res,
+            Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some(FOREIGN_KEY_VIOLATION) => {
+                return Err(AppError::Conflict(
+                    "the board is still used by devices".to_owned(),
+                ))?
+            }
+            Err(e) => Err(e)
+                .context("Failed to perform a sql to delete a board")
+                .map_err(AppError::UnexpectedError)?,
+        };
+
+        Ok(res.rows_affected() > 0)
+    }
+}
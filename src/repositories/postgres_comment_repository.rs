@@ -0,0 +1,377 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use sea_query::{Alias, Expr, OnConflict, Order, PostgresQueryBuilder, Query, SelectStatement};
+use sqlx::{Connection, PgConnection};
+
+use crate::{
+    errors::AppError,
+    models::{
+        comment::{Comment, Mention, MentionedUser},
+        comment_table::{DeviceCommentMentions, DeviceComments},
+        user_table::Users,
+    },
+    utils::PostgresSession,
+};
+
+use super::i_comment_repository::ICommentRepository;
+use super::postgres_device_repository::live_devices;
+use super::FOREIGN_KEY_VIOLATION;
+
+pub struct PostgresCommentRepository {
+    session: PostgresSession,
+}
+
+impl PostgresCommentRepository {
+    pub fn new(session: PostgresSession) -> Self {
+        Self { session }
+    }
+}
+
+/// Select the comments joined with the usernames of their authors
+fn select_comments() -> SelectStatement {
+    Query::select()
+        .columns([
+            (DeviceComments::Table, DeviceComments::Id),
+            (DeviceComments::Table, DeviceComments::DeviceId),
+            (DeviceComments::Table, DeviceComments::AuthorId),
+            (DeviceComments::Table, DeviceComments::Body),
+            (DeviceComments::Table, DeviceComments::CreatedAt),
+            (DeviceComments::Table, DeviceComments::UpdatedAt),
+        ])
+        .expr_as(
+            Expr::col((Users::Table, Users::Username)),
+            Alias::new("author_username"),
+        )
+        .from(DeviceComments::Table)
+        .left_join(
+            Users::Table,
+            Expr::col((Users::Table, Users::Id))
+                .equals((DeviceComments::Table, DeviceComments::AuthorId)),
+        )
+        .to_owned()
+}
+
+/// Fill the mentioned users of the comments
+async fn attach_mentions(conn: &mut PgConnection, comments: &mut [Comment]) -> anyhow::Result<()> {
+    if comments.is_empty() {
+        return Ok(());
+    }
+
+    let sql = Query::select()
+        .column((
+            DeviceCommentMentions::Table,
+            DeviceCommentMentions::CommentId,
+        ))
+        .expr_as(
+            Expr::col((DeviceCommentMentions::Table, DeviceCommentMentions::UserId)),
+            Alias::new("user_id"),
+        )
+        .column((Users::Table, Users::Username))
+        .from(DeviceCommentMentions::Table)
+        .inner_join(
+            Users::Table,
+            Expr::col((Users::Table, Users::Id))
+                .equals((DeviceCommentMentions::Table, DeviceCommentMentions::UserId)),
+        )
+        .and_where(
+            Expr::col((
+                DeviceCommentMentions::Table,
+                DeviceCommentMentions::CommentId,
+            ))
+            .is_in(comments.iter().map(|comment| comment.id)),
+        )
+        .order_by((Users::Table, Users::Username), Order::Asc)
+        .to_string(PostgresQueryBuilder);
+
+    let mentions = sqlx::query_as::<_, (uuid::Uuid, uuid::Uuid, String)>(&sql)
+        .fetch_all(&mut *conn)
+        .await
+        .context("Failed to perform a sql to list the mentions of comments")
+        .map_err(AppError::UnexpectedError)?;
+
+    for (comment_id, user_id, username) in mentions {
+        if let Some(comment) = comments.iter_mut().find(|comment| comment.id == comment_id) {
+            comment.mentions.push(MentionedUser { user_id, username });
+        }
+    }
+
+    Ok(())
+}
+
+async fn fetch_comment(
+    conn: &mut PgConnection,
+    device_id: uuid::Uuid,
+    id: uuid::Uuid,
+) -> anyhow::Result<Option<Comment>> {
+    let sql = select_comments()
+        .and_where(Expr::col((DeviceComments::Table, DeviceComments::DeviceId)).eq(device_id))
+        .and_where(Expr::col((DeviceComments::Table, DeviceComments::Id)).eq(id))
+        .to_string(PostgresQueryBuilder);
+
+    let comment = sqlx::query_as::<_, Comment>(&sql)
+        .fetch_optional(&mut *conn)
+        .await
+        .context("Failed to perform a sql to get a comment")
+        .map_err(AppError::UnexpectedError)?;
+
+    let mut comments: Vec<_> = comment.into_iter().collect();
+    attach_mentions(conn, &mut comments).await?;
+
+    Ok(comments.pop())
+}
+
+/// Mention the existing users among `usernames` in a comment, the users
+/// already mentioned keep their mention
+async fn insert_mentions(
+    conn: &mut PgConnection,
+    comment_id: uuid::Uuid,
+    usernames: &[String],
+) -> anyhow::Result<()> {
+    if usernames.is_empty() {
+        return Ok(());
+    }
+
+    let sql = Query::insert()
+        .into_table(DeviceCommentMentions::Table)
+        .columns([
+            DeviceCommentMentions::CommentId,
+            DeviceCommentMentions::UserId,
+        ])
+        .select_from(
+            Query::select()
+                .expr(Expr::val(comment_id))
+                .column(Users::Id)
+                .from(Users::Table)
+                .and_where(Expr::col(Users::Username).is_in(usernames.iter().cloned()))
+                .to_owned(),
+        )
+        .context("Failed to build a sql to mention users")?
+        .on_conflict(
+            OnConflict::columns([
+                DeviceCommentMentions::CommentId,
+                DeviceCommentMentions::UserId,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .to_string(PostgresQueryBuilder);
+
+    sqlx::query(&sql)
+        .execute(&mut *conn)
+        .await
+        .context("Failed to perform a sql to mention users")
+        .map_err(AppError::UnexpectedError)?;
+
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl ICommentRepository for PostgresCommentRepository {
+    async fn list(&self, device_id: uuid::Uuid) -> anyhow::Result<Vec<Comment>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = select_comments()
+            .and_where(Expr::col((DeviceComments::Table, DeviceComments::DeviceId)).eq(device_id))
+            .order_by(
+                (DeviceComments::Table, DeviceComments::CreatedAt),
+                Order::Asc,
+            )
+            .order_by((DeviceComments::Table, DeviceComments::Id), Order::Asc)
+            .to_string(PostgresQueryBuilder);
+
+        let mut res = sqlx::query_as::<_, Comment>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to list the comments of a device")
+            .map_err(AppError::UnexpectedError)?;
+        attach_mentions(&mut conn, &mut res).await?;
+
+        Ok(res)
+    }
+
+    async fn get(&self, device_id: uuid::Uuid, id: uuid::Uuid) -> anyhow::Result<Option<Comment>> {
+        let mut conn = self.session.get_session().await?;
+
+        fetch_comment(&mut conn, device_id, id).await
+    }
+
+    async fn create(
+        &self,
+        device_id: uuid::Uuid,
+        author_id: uuid::Uuid,
+        body: String,
+        mentions: &[String],
+    ) -> anyhow::Result<Comment> {
+        let mut conn = self.session.get_session().await?;
+
+        let mut tx = conn
+            .begin()
+            .await
+            .context("Failed to begin a transaction")
+            .map_err(AppError::UnexpectedError)?;
+
+        let id = uuid::Uuid::new_v4();
+        let sql = Query::insert()
+            .into_table(DeviceComments::Table)
+            .columns([
+                DeviceComments::Id,
+                DeviceComments::DeviceId,
+                DeviceComments::AuthorId,
+                DeviceComments::Body,
+            ])
+            .values_panic([id.into(), device_id.into(), author_id.into(), body.into()])
+            .to_string(PostgresQueryBuilder);
+
+        match sqlx::query(&sql).execute(&mut *tx).await {
+            Ok(_) => {}
+            Err(sqlx::Error::Database(e))
+                if e.code().as_deref() == Some(FOREIGN_KEY_VIOLATION)
+                    && e.constraint() == Some("device_comments_device_id_fkey") =>
+            {
+                return Err(AppError::NotFound)?
+            }
+            Err(e) => Err(e)
+                .context("Failed to perform a sql to create a comment")
+                .map_err(AppError::UnexpectedError)?,
+        }
+
+        insert_mentions(&mut tx, id, mentions).await?;
+        let res = fetch_comment(&mut tx, device_id, id)
+            .await?
+            .context("The created comment is missing")?;
+
+        tx.commit()
+            .await
+            .context("Failed to commit the comment")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn update(
+        &self,
+        device_id: uuid::Uuid,
+        id: uuid::Uuid,
+        body: String,
+        mentions: &[String],
+    ) -> anyhow::Result<Option<Comment>> {
+        let mut conn = self.session.get_session().await?;
+
+        let mut tx = conn
+            .begin()
+            .await
+            .context("Failed to begin a transaction")
+            .map_err(AppError::UnexpectedError)?;
+
+        let sql = Query::update()
+            .table(DeviceComments::Table)
+            .values([
+                (DeviceComments::Body, body.into()),
+                (DeviceComments::UpdatedAt, Expr::current_timestamp().into()),
+            ])
+            .and_where(Expr::col(DeviceComments::DeviceId).eq(device_id))
+            .and_where(Expr::col(DeviceComments::Id).eq(id))
+            .to_string(PostgresQueryBuilder);
+
+        let updated = sqlx::query(&sql)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to perform a sql to update a comment")
+            .map_err(AppError::UnexpectedError)?;
+        if updated.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        // Drop the mentions which were edited out
+        let sql = Query::delete()
+            .from_table(DeviceCommentMentions::Table)
+            .and_where(Expr::col(DeviceCommentMentions::CommentId).eq(id))
+            .and_where(
+                Expr::col(DeviceCommentMentions::UserId).not_in_subquery(
+                    Query::select()
+                        .column(Users::Id)
+                        .from(Users::Table)
+                        .and_where(Expr::col(Users::Username).is_in(mentions.iter().cloned()))
+                        .to_owned(),
+                ),
+            )
+            .to_string(PostgresQueryBuilder);
+
+        sqlx::query(&sql)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to perform a sql to remove the mentions of a comment")
+            .map_err(AppError::UnexpectedError)?;
+
+        insert_mentions(&mut tx, id, mentions).await?;
+        let res = fetch_comment(&mut tx, device_id, id).await?;
+
+        tx.commit()
+            .await
+            .context("Failed to commit the comment")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn delete(&self, device_id: uuid::Uuid, id: uuid::Uuid) -> anyhow::Result<bool> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::delete()
+            .from_table(DeviceComments::Table)
+            .and_where(Expr::col(DeviceComments::DeviceId).eq(device_id))
+            .and_where(Expr::col(DeviceComments::Id).eq(id))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query(&sql)
+            .execute(&mut *conn)
+            .await
+            .context("Failed to perform a sql to delete a comment")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res.rows_affected() > 0)
+    }
+
+    async fn mentions(
+        &self,
+        user_id: uuid::Uuid,
+        since: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<Vec<Mention>> {
+        let mut conn = self.session.get_session().await?;
+
+        let mentioned_at = (
+            DeviceCommentMentions::Table,
+            DeviceCommentMentions::CreatedAt,
+        );
+        let sql = select_comments()
+            .expr_as(Expr::col(mentioned_at), Alias::new("mentioned_at"))
+            .inner_join(
+                DeviceCommentMentions::Table,
+                Expr::col((
+                    DeviceCommentMentions::Table,
+                    DeviceCommentMentions::CommentId,
+                ))
+                .equals((DeviceComments::Table, DeviceComments::Id)),
+            )
+            .and_where(
+                Expr::col((DeviceCommentMentions::Table, DeviceCommentMentions::UserId))
+                    .eq(user_id),
+            )
+            .and_where(
+                Expr::col((DeviceComments::Table, DeviceComments::DeviceId))
+                    .in_subquery(live_devices()),
+            )
+            .and_where_option(since.map(|since| Expr::col(mentioned_at).gt(since)))
+            .order_by(mentioned_at, Order::Desc)
+            .order_by((DeviceComments::Table, DeviceComments::Id), Order::Desc)
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Mention>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to list the mentions of a user")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+}
@@ -0,0 +1,1620 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use futures_util::stream::{self, BoxStream};
+use futures_util::StreamExt;
+use sea_query::{
+    Alias, Asterisk, BinOper, Cond, Condition, Expr, Func, LockType, Order, PostgresQueryBuilder,
+    Query, SelectStatement, SimpleExpr, SubQueryStatement, Value,
+};
+use sqlx::{Connection, PgConnection, Row};
+
+use crate::{
+    attributes,
+    errors::{AppError, AuthError},
+    models::{
+        device::{
+            CreateDeviceRequest, Device, DeviceHighlights, DeviceSearchHit, DeviceSortColumn,
+            IfMatch, ListDevicesQuery, SortOrder, TrashedDevice, UpdateDeviceRequest,
+        },
+        device_history::{diff, DeviceHistoryAction, DeviceHistoryEntry},
+        device_history_table::DeviceHistory,
+        device_status::{DeviceStatus, DeviceStatusChange},
+        device_status_table::DeviceStatusChanges,
+        device_table::Devices,
+        device_type_table::DeviceTypes,
+        hw_phase_table::HwPhases,
+        reservation_table::DeviceReservations,
+        tag::TagMatch,
+        tag_table::{DeviceTags, Tags},
+    },
+    utils::PostgresSession,
+};
+
+use super::i_device_repository::IDeviceRepository;
+use super::postgres_location_repository::subtree;
+use super::{FOREIGN_KEY_VIOLATION, UNIQUE_VIOLATION};
+
+pub struct PostgresDeviceRepository {
+    session: PostgresSession,
+}
+
+impl PostgresDeviceRepository {
+    pub fn new(session: PostgresSession) -> Self {
+        Self { session }
+    }
+}
+
+const DEVICE_COLUMNS: [Devices; 15] = [
+    Devices::Id,
+    Devices::Name,
+    Devices::OwnerId,
+    Devices::Board,
+    Devices::Sn,
+    Devices::Barcode,
+    Devices::ReceivedDate,
+    Devices::HwPhase,
+    Devices::Note,
+    Devices::DeviceTypeId,
+    Devices::Attributes,
+    Devices::Status,
+    Devices::StatusChangedAt,
+    Devices::LocationId,
+    Devices::Version,
+];
+
+fn sort_column(column: DeviceSortColumn) -> Devices {
+    match column {
+        DeviceSortColumn::Id => Devices::Id,
+        DeviceSortColumn::Name => Devices::Name,
+        DeviceSortColumn::OwnerId => Devices::OwnerId,
+        DeviceSortColumn::Board => Devices::Board,
+        DeviceSortColumn::Sn => Devices::Sn,
+        DeviceSortColumn::Barcode => Devices::Barcode,
+        DeviceSortColumn::ReceivedDate => Devices::ReceivedDate,
+        DeviceSortColumn::HwPhase => Devices::HwPhase,
+        DeviceSortColumn::Note => Devices::Note,
+        DeviceSortColumn::DeviceTypeId => Devices::DeviceTypeId,
+        DeviceSortColumn::Status => Devices::Status,
+    }
+}
+
+/// Get the value of the sorting column from a device, `None` if it is null
+fn sort_value(column: DeviceSortColumn, device: &Device) -> Option<Value> {
+    match column {
+        DeviceSortColumn::Id => Some(device.id.into()),
+        DeviceSortColumn::Name => Some(device.name.clone().into()),
+        DeviceSortColumn::OwnerId => Some(device.owner_id.into()),
+        DeviceSortColumn::Board => device.board.clone().map(Value::from),
+        DeviceSortColumn::Sn => device.sn.clone().map(Value::from),
+        DeviceSortColumn::Barcode => device.barcode.clone().map(Value::from),
+        DeviceSortColumn::ReceivedDate => device.received_date.map(Value::from),
+        DeviceSortColumn::HwPhase => device.hw_phase.clone().map(Value::from),
+        DeviceSortColumn::Note => device.note.clone().map(Value::from),
+        DeviceSortColumn::DeviceTypeId => device.device_type_id.map(Value::from),
+        DeviceSortColumn::Status => Some(device.status.as_str().into()),
+    }
+}
+
+/// Build the `WHERE` condition from the filters of the query string, the
+/// deleted devices are left out
+fn filter_condition(query: &ListDevicesQuery) -> Condition {
+    Cond::all()
+        .add(Expr::col(Devices::DeletedAt).is_null())
+        .add_option(query.owner_id.map(|id| Expr::col(Devices::OwnerId).eq(id)))
+        .add_option(
+            query
+                .board
+                .as_ref()
+                .map(|board| Expr::col(Devices::Board).eq(board)),
+        )
+        .add_option(
+            query
+                .hw_phase
+                .as_ref()
+                .map(|hw_phase| Expr::col(Devices::HwPhase).eq(hw_phase)),
+        )
+        .add_option(
+            query
+                .device_type_id
+                .map(|id| Expr::col(Devices::DeviceTypeId).eq(id)),
+        )
+        .add_option(
+            query
+                .status
+                .map(|status| Expr::col(Devices::Status).eq(status.as_str())),
+        )
+        .add_option(query.location_id.map(|id| {
+            Expr::col(Devices::LocationId).binary(
+                BinOper::In,
+                SimpleExpr::SubQuery(
+                    None,
+                    Box::new(SubQueryStatement::WithStatement(subtree(id))),
+                ),
+            )
+        }))
+        .add_option(query.attributes.as_ref().map(|attributes| {
+            Expr::cust_with_exprs(
+                "$1 @> $2",
+                [
+                    Expr::col(Devices::Attributes).into(),
+                    Expr::val(serde_json::Value::Object(attributes.clone())).into(),
+                ],
+            )
+        }))
+        .add_option(
+            query
+                .tags
+                .as_ref()
+                .map(|tags| tagged_condition(tags, query.tag_match)),
+        )
+        .add_option(
+            query
+                .received_from
+                .map(|from| Expr::col(Devices::ReceivedDate).gte(from)),
+        )
+        .add_option(
+            query
+                .received_to
+                .map(|to| Expr::col(Devices::ReceivedDate).lte(to)),
+        )
+}
+
+/// Select the devices tagged with any or every tag of `tags`
+fn tagged_condition(tags: &[String], tag_match: TagMatch) -> SimpleExpr {
+    let mut tagged = Query::select()
+        .column((DeviceTags::Table, DeviceTags::DeviceId))
+        .from(DeviceTags::Table)
+        .inner_join(
+            Tags::Table,
+            Expr::col((Tags::Table, Tags::Id)).equals((DeviceTags::Table, DeviceTags::TagId)),
+        )
+        .and_where(Expr::col((Tags::Table, Tags::Name)).is_in(tags.iter().cloned()))
+        .to_owned();
+
+    if tag_match == TagMatch::All {
+        let mut names = tags.to_vec();
+        names.sort();
+        names.dedup();
+        tagged
+            .group_by_col((DeviceTags::Table, DeviceTags::DeviceId))
+            .and_having(
+                Expr::expr(Func::count(Expr::col((
+                    DeviceTags::Table,
+                    DeviceTags::TagId,
+                ))))
+                .eq(names.len() as i64),
+            );
+    }
+
+    Expr::col((Devices::Table, Devices::Id)).in_subquery(tagged)
+}
+
+/// Build the keyset condition which selects the devices after `cursor`.
+///
+/// Rows are ordered by the sorting column and then by id, Postgres puts
+/// nulls last in ascending order and first in descending order.
+fn cursor_condition(column: DeviceSortColumn, order: SortOrder, cursor: &Device) -> Condition {
+    let col = sort_column(column);
+    let after = |col: Devices, value: Value| -> SimpleExpr {
+        match order {
+            SortOrder::Asc => Expr::col(col).gt(value),
+            SortOrder::Desc => Expr::col(col).lt(value),
+        }
+    };
+
+    match (order, sort_value(column, cursor)) {
+        (SortOrder::Asc, None) => Cond::all()
+            .add(Expr::col(col).is_null())
+            .add(after(Devices::Id, cursor.id.into())),
+        (SortOrder::Asc, Some(value)) => Cond::any()
+            .add(Expr::col(col).is_null())
+            .add(after(col, value.clone()))
+            .add(
+                Cond::all()
+                    .add(Expr::col(col).eq(value))
+                    .add(after(Devices::Id, cursor.id.into())),
+            ),
+        (SortOrder::Desc, None) => Cond::any().add(Expr::col(col).is_not_null()).add(
+            Cond::all()
+                .add(Expr::col(col).is_null())
+                .add(after(Devices::Id, cursor.id.into())),
+        ),
+        (SortOrder::Desc, Some(value)) => Cond::all().add(Expr::col(col).is_not_null()).add(
+            Cond::any().add(after(col, value.clone())).add(
+                Cond::all()
+                    .add(Expr::col(col).eq(value))
+                    .add(after(Devices::Id, cursor.id.into())),
+            ),
+        ),
+    }
+}
+
+const HISTORY_COLUMNS: [DeviceHistory; 6] = [
+    DeviceHistory::Id,
+    DeviceHistory::DeviceId,
+    DeviceHistory::Action,
+    DeviceHistory::ChangedBy,
+    DeviceHistory::ChangedAt,
+    DeviceHistory::Changes,
+];
+
+/// The written values of a device which are checked by constraints, they are
+/// kept to explain a violation
+struct ConstrainedValues {
+    owner_id: uuid::Uuid,
+    board: Option<String>,
+    sn: Option<String>,
+    barcode: Option<String>,
+    hw_phase: Option<String>,
+    device_type_id: Option<uuid::Uuid>,
+}
+
+impl From<&CreateDeviceRequest> for ConstrainedValues {
+    fn from(request: &CreateDeviceRequest) -> Self {
+        Self {
+            owner_id: request.owner_id,
+            board: request.board.clone(),
+            sn: request.sn.clone(),
+            barcode: request.barcode.clone(),
+            hw_phase: request.hw_phase.clone(),
+            device_type_id: request.device_type_id,
+        }
+    }
+}
+
+impl From<&UpdateDeviceRequest> for ConstrainedValues {
+    fn from(request: &UpdateDeviceRequest) -> Self {
+        Self {
+            owner_id: request.owner_id,
+            board: request.board.clone(),
+            sn: request.sn.clone(),
+            barcode: request.barcode.clone(),
+            hw_phase: request.hw_phase.clone(),
+            device_type_id: request.device_type_id,
+        }
+    }
+}
+
+impl From<&Device> for ConstrainedValues {
+    fn from(device: &Device) -> Self {
+        Self {
+            owner_id: device.owner_id,
+            board: device.board.clone(),
+            sn: device.sn.clone(),
+            barcode: device.barcode.clone(),
+            hw_phase: device.hw_phase.clone(),
+            device_type_id: device.device_type_id,
+        }
+    }
+}
+
+/// Turn a duplicated serial number or barcode into a conflict and an unknown
+/// owner, board, hardware phase or device type into an invalid request, any
+/// other error is unexpected
+fn write_error(e: sqlx::Error, values: &ConstrainedValues, context: &'static str) -> anyhow::Error {
+    let value = |value: &Option<String>| value.clone().unwrap_or_default();
+
+    if let sqlx::Error::Database(db) = &e {
+        match (db.code().as_deref(), db.constraint()) {
+            (Some(UNIQUE_VIOLATION), Some("devices_sn_unique_idx")) => {
+                return AppError::Conflict(format!(
+                    "a device with the serial number `{}` already exists",
+                    value(&values.sn)
+                ))
+                .into()
+            }
+            (Some(UNIQUE_VIOLATION), Some("devices_barcode_unique_idx")) => {
+                return AppError::Conflict(format!(
+                    "a device with the barcode `{}` already exists",
+                    value(&values.barcode)
+                ))
+                .into()
+            }
+            (Some(FOREIGN_KEY_VIOLATION), Some("devices_owner_id_fkey")) => {
+                return AppError::InvalidRequest(format!("unknown owner `{}`", values.owner_id))
+                    .into()
+            }
+            (Some(FOREIGN_KEY_VIOLATION), Some("devices_board_fkey")) => {
+                return AppError::InvalidRequest(format!(
+                    "unknown board `{}`",
+                    value(&values.board)
+                ))
+                .into()
+            }
+            (Some(FOREIGN_KEY_VIOLATION), Some("devices_hw_phase_fkey")) => {
+                return AppError::InvalidRequest(format!(
+                    "unknown hardware phase `{}`",
+                    value(&values.hw_phase)
+                ))
+                .into()
+            }
+            (Some(FOREIGN_KEY_VIOLATION), Some("devices_device_type_id_fkey")) => {
+                return AppError::InvalidRequest(format!(
+                    "unknown device type `{}`",
+                    values.device_type_id.unwrap_or_default()
+                ))
+                .into()
+            }
+            _ => {}
+        }
+    }
+
+    AppError::UnexpectedError(anyhow::Error::new(e).context(context)).into()
+}
+
+/// The number of exported rows read ahead of the client
+const EXPORT_BUFFER_SIZE: usize = 64;
+
+/// The default threshold of the `<%` operator of `pg_trgm`
+const WORD_SIMILARITY_THRESHOLD: f32 = 0.6;
+
+const HEADLINE_OPTIONS: &str = "StartSel=<mark>, StopSel=</mark>, HighlightAll=true";
+
+/// Turn the search text into a prefix `tsquery`, e.g. `dev boa` becomes
+/// `dev:* & boa:*`. Punctuation is dropped so the user input can never
+/// break the `tsquery` syntax.
+fn prefix_tsquery(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(|term| format!("{}:*", term.to_lowercase()))
+        .collect::<Vec<_>>()
+        .join(" & ")
+}
+
+#[derive(sqlx::FromRow)]
+struct DeviceSearchRow {
+    #[sqlx(flatten)]
+    device: Device,
+    rank: f32,
+    name_headline: Option<String>,
+    board_headline: Option<String>,
+    sn_headline: Option<String>,
+    barcode_headline: Option<String>,
+    note_headline: Option<String>,
+    sn_similarity: Option<f32>,
+    barcode_similarity: Option<f32>,
+}
+
+impl From<DeviceSearchRow> for DeviceSearchHit {
+    fn from(row: DeviceSearchRow) -> Self {
+        let marked = |headline: Option<String>| headline.filter(|h| h.contains("<mark>"));
+        // A serial number or barcode matched by similarity has no matched word
+        // to highlight, so the whole value is marked instead
+        let similar =
+            |headline: Option<String>, value: &Option<String>, similarity: Option<f32>| {
+                marked(headline).or_else(|| {
+                    value
+                        .as_ref()
+                        .filter(|_| similarity.unwrap_or(0.0) >= WORD_SIMILARITY_THRESHOLD)
+                        .map(|value| format!("<mark>{value}</mark>"))
+                })
+            };
+
+        let highlights = DeviceHighlights {
+            name: marked(row.name_headline),
+            board: marked(row.board_headline),
+            sn: similar(row.sn_headline, &row.device.sn, row.sn_similarity),
+            barcode: similar(
+                row.barcode_headline,
+                &row.device.barcode,
+                row.barcode_similarity,
+            ),
+            note: marked(row.note_headline),
+        };
+
+        Self {
+            device: row.device,
+            rank: row.rank,
+            highlights,
+        }
+    }
+}
+
+/// Lock a device until the end of the transaction and return its current version
+async fn select_for_update(
+    conn: &mut PgConnection,
+    id: uuid::Uuid,
+) -> anyhow::Result<Option<Device>> {
+    let sql = Query::select()
+        .columns(DEVICE_COLUMNS)
+        .from(Devices::Table)
+        .and_where(Expr::col(Devices::Id).eq(id))
+        .and_where(Expr::col(Devices::DeletedAt).is_null())
+        .lock(LockType::Update)
+        .to_string(PostgresQueryBuilder);
+
+    let res = sqlx::query_as::<_, Device>(&sql)
+        .fetch_optional(conn)
+        .await
+        .context("Failed to perform a sql to lock a device")
+        .map_err(AppError::UnexpectedError)?;
+
+    Ok(res)
+}
+
+/// Reject a change based on another version of a locked device
+fn check_version(device: &Device, if_match: &IfMatch) -> Result<(), AppError> {
+    if !if_match.matches(device) {
+        return Err(AppError::PreconditionFailed(format!(
+            "the device has been changed, its current version is {}",
+            device.etag()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Check the attributes of a device against the schema of its type, a device
+/// without a type has no attributes. The type is locked so its schema can't
+/// change before the device is written.
+async fn check_attributes(
+    conn: &mut PgConnection,
+    device_type_id: Option<uuid::Uuid>,
+    attributes: &serde_json::Map<String, serde_json::Value>,
+) -> anyhow::Result<()> {
+    let device_type_id = match device_type_id {
+        Some(id) => id,
+        None if attributes.is_empty() => return Ok(()),
+        None => {
+            return Err(AppError::InvalidRequest(
+                "attributes require a device type".to_owned(),
+            ))?
+        }
+    };
+
+    let sql = Query::select()
+        .column(DeviceTypes::AttributeSchema)
+        .from(DeviceTypes::Table)
+        .and_where(Expr::col(DeviceTypes::Id).eq(device_type_id))
+        .lock(LockType::Share)
+        .to_string(PostgresQueryBuilder);
+
+    let schema = sqlx::query(&sql)
+        .fetch_optional(conn)
+        .await
+        .context("Failed to perform a sql to get the attribute schema of a device type")
+        .map_err(AppError::UnexpectedError)?
+        .map(|row| row.get::<serde_json::Value, usize>(0))
+        .ok_or_else(|| {
+            AppError::InvalidRequest(format!("unknown device type `{device_type_id}`"))
+        })?;
+
+    let schema =
+        attributes::compile(&schema).map_err(|e| AppError::UnexpectedError(anyhow::anyhow!(e)))?;
+
+    attributes::validate(&schema, &serde_json::Value::Object(attributes.clone()))
+        .map_err(AppError::InvalidRequest)?;
+
+    Ok(())
+}
+
+/// Insert a new device in the `received` status
+async fn insert_device(
+    conn: &mut PgConnection,
+    request: CreateDeviceRequest,
+) -> anyhow::Result<Device> {
+    check_attributes(conn, request.device_type_id, &request.attributes).await?;
+
+    let values = ConstrainedValues::from(&request);
+    let sql = Query::insert()
+        .into_table(Devices::Table)
+        .columns(DEVICE_COLUMNS)
+        .values_panic([
+            uuid::Uuid::new_v4().into(),
+            request.name.into(),
+            request.owner_id.into(),
+            request.board.into(),
+            request.sn.into(),
+            request.barcode.into(),
+            request.received_date.into(),
+            request.hw_phase.into(),
+            request.note.into(),
+            request.device_type_id.into(),
+            serde_json::Value::Object(request.attributes).into(),
+            DeviceStatus::Received.as_str().into(),
+            None::<DateTime<Utc>>.into(),
+            None::<uuid::Uuid>.into(),
+            1.into(),
+        ])
+        .returning(Query::returning().columns(DEVICE_COLUMNS))
+        .to_string(PostgresQueryBuilder);
+
+    let res = sqlx::query_as::<_, Device>(&sql)
+        .fetch_one(conn)
+        .await
+        .map_err(|e| write_error(e, &values, "Failed to perform a sql to create a device"))?;
+
+    Ok(res)
+}
+
+/// Append an entry to the history of a device, the database fills
+/// `changed_at` so it keeps its sub-seconds
+async fn insert_history(
+    conn: &mut PgConnection,
+    device_id: uuid::Uuid,
+    action: DeviceHistoryAction,
+    changed_by: uuid::Uuid,
+    changes: serde_json::Value,
+) -> anyhow::Result<()> {
+    let sql = Query::insert()
+        .into_table(DeviceHistory::Table)
+        .columns([
+            DeviceHistory::Id,
+            DeviceHistory::DeviceId,
+            DeviceHistory::Action,
+            DeviceHistory::ChangedBy,
+            DeviceHistory::Changes,
+        ])
+        .values_panic([
+            uuid::Uuid::new_v4().into(),
+            device_id.into(),
+            action.as_str().into(),
+            changed_by.into(),
+            changes.into(),
+        ])
+        .to_string(PostgresQueryBuilder);
+
+    sqlx::query(&sql)
+        .execute(conn)
+        .await
+        .context("Failed to perform a sql to record the device history")
+        .map_err(AppError::UnexpectedError)?;
+
+    Ok(())
+}
+
+/// Select the ids of the devices which aren't in the trash, for the queries
+/// of the other repositories which span many devices
+pub(crate) fn live_devices() -> SelectStatement {
+    Query::select()
+        .column(Devices::Id)
+        .from(Devices::Table)
+        .and_where(Expr::col(Devices::DeletedAt).is_null())
+        .to_owned()
+}
+
+/// Record the rename of a catalog entry which `ON UPDATE CASCADE` carried over
+/// to the `column` of the devices, `field` is the name of the column in the
+/// history. Call it in the transaction of the rename, after the rename.
+pub(crate) async fn record_cascaded_rename(
+    conn: &mut PgConnection,
+    column: Devices,
+    field: &str,
+    old: &str,
+    new: &str,
+    changed_by: uuid::Uuid,
+) -> anyhow::Result<()> {
+    if old == new {
+        return Ok(());
+    }
+
+    // No device can have referenced the new name before the rename
+    let sql = Query::select()
+        .column(Devices::Id)
+        .from(Devices::Table)
+        .and_where(Expr::col(column).eq(new))
+        .to_string(PostgresQueryBuilder);
+
+    let ids = sqlx::query(&sql)
+        .fetch_all(&mut *conn)
+        .await
+        .context("Failed to perform a sql to get the renamed devices")
+        .map_err(AppError::UnexpectedError)?;
+
+    for row in ids {
+        insert_history(
+            conn,
+            row.get::<uuid::Uuid, usize>(0),
+            DeviceHistoryAction::Update,
+            changed_by,
+            serde_json::json!({ field: { "old": old, "new": new } }),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl IDeviceRepository for PostgresDeviceRepository {
+    async fn list(
+        &self,
+        query: &ListDevicesQuery,
+        limit: u64,
+        cursor: Option<Device>,
+    ) -> anyhow::Result<(Vec<Device>, i64)> {
+        let mut conn = self.session.get_session().await?;
+
+        let order = match query.order {
+            SortOrder::Asc => Order::Asc,
+            SortOrder::Desc => Order::Desc,
+        };
+
+        let sql = {
+            let mut select = Query::select();
+            select
+                .columns(DEVICE_COLUMNS)
+                .from(Devices::Table)
+                .cond_where(filter_condition(query))
+                .order_by(sort_column(query.sort_by), order.clone())
+                .order_by(Devices::Id, order)
+                .limit(limit)
+                .offset(query.offset.unwrap_or(0));
+
+            if let Some(cursor) = cursor {
+                select.cond_where(cursor_condition(query.sort_by, query.order, &cursor));
+            }
+
+            select.to_string(PostgresQueryBuilder)
+        };
+
+        let devices = sqlx::query_as::<_, Device>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to list devices")
+            .map_err(AppError::UnexpectedError)?;
+
+        let sql = Query::select()
+            .expr(Func::count(Expr::col(Asterisk)))
+            .from(Devices::Table)
+            .cond_where(filter_condition(query))
+            .to_string(PostgresQueryBuilder);
+
+        let total = sqlx::query(&sql)
+            .fetch_one(&mut *conn)
+            .await
+            .context("Failed to perform a sql to count devices")
+            .map_err(AppError::UnexpectedError)?
+            .get::<i64, usize>(0);
+
+        Ok((devices, total))
+    }
+
+    async fn export(
+        &self,
+        query: &ListDevicesQuery,
+    ) -> anyhow::Result<BoxStream<'static, anyhow::Result<Device>>> {
+        let order = match query.order {
+            SortOrder::Asc => Order::Asc,
+            SortOrder::Desc => Order::Desc,
+        };
+
+        let sql = Query::select()
+            .columns(DEVICE_COLUMNS)
+            .from(Devices::Table)
+            .cond_where(filter_condition(query))
+            .order_by(sort_column(query.sort_by), order.clone())
+            .order_by(Devices::Id, order)
+            .to_string(PostgresQueryBuilder);
+
+        // The connection moves to the task streaming the rows, it goes back to
+        // the pool once the client has every row or goes away
+        let mut conn = self.session.get_session().await?;
+
+        let (sender, receiver) = tokio::sync::mpsc::channel(EXPORT_BUFFER_SIZE);
+        tokio::spawn(async move {
+            let mut rows = sqlx::query_as::<_, Device>(&sql).fetch(&mut *conn);
+            while let Some(row) = rows.next().await {
+                let row = row.context("Failed to perform a sql to export devices");
+                let failed = row.is_err();
+                // The receiver is dropped once the client goes away
+                if sender.send(row).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        let devices = stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|row| (row, receiver))
+        });
+
+        Ok(devices.boxed())
+    }
+
+    async fn search(&self, text: &str, limit: u64) -> anyhow::Result<Vec<DeviceSearchHit>> {
+        let mut conn = self.session.get_session().await?;
+
+        let tsquery = prefix_tsquery(text);
+        let headline = |column: Devices| {
+            Expr::cust_with_exprs(
+                "ts_headline('simple', $1, to_tsquery('simple', $2), $3)",
+                [
+                    Expr::col(column).into(),
+                    tsquery.as_str().into(),
+                    HEADLINE_OPTIONS.into(),
+                ],
+            )
+        };
+        let similarity = |column: Devices| {
+            Expr::cust_with_exprs(
+                "word_similarity($1, $2)",
+                [text.into(), Expr::col(column).into()],
+            )
+        };
+
+        let sql = Query::select()
+            .columns(DEVICE_COLUMNS)
+            .expr_as(
+                Expr::cust_with_exprs(
+                    "ts_rank(search_vector, to_tsquery('simple', $1)) \
+                     + greatest(coalesce($2, 0), coalesce($3, 0))",
+                    [
+                        tsquery.as_str().into(),
+                        similarity(Devices::Sn),
+                        similarity(Devices::Barcode),
+                    ],
+                ),
+                Alias::new("rank"),
+            )
+            .expr_as(headline(Devices::Name), Alias::new("name_headline"))
+            .expr_as(headline(Devices::Board), Alias::new("board_headline"))
+            .expr_as(headline(Devices::Sn), Alias::new("sn_headline"))
+            .expr_as(headline(Devices::Barcode), Alias::new("barcode_headline"))
+            .expr_as(headline(Devices::Note), Alias::new("note_headline"))
+            .expr_as(similarity(Devices::Sn), Alias::new("sn_similarity"))
+            .expr_as(
+                similarity(Devices::Barcode),
+                Alias::new("barcode_similarity"),
+            )
+            .from(Devices::Table)
+            .cond_where(
+                Cond::any()
+                    .add(Expr::cust_with_values(
+                        "search_vector @@ to_tsquery('simple', $1)",
+                        [tsquery.as_str()],
+                    ))
+                    .add(Expr::cust_with_exprs(
+                        "$1 <% $2",
+                        [text.into(), Expr::col(Devices::Sn).into()],
+                    ))
+                    .add(Expr::cust_with_exprs(
+                        "$1 <% $2",
+                        [text.into(), Expr::col(Devices::Barcode).into()],
+                    )),
+            )
+            .and_where(Expr::col(Devices::DeletedAt).is_null())
+            .order_by(Alias::new("rank"), Order::Desc)
+            .order_by(Devices::Id, Order::Asc)
+            .limit(limit)
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, DeviceSearchRow>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to search devices")
+            .map_err(AppError::UnexpectedError)?
+            .into_iter()
+            .map(DeviceSearchHit::from)
+            .collect();
+
+        Ok(res)
+    }
+
+    async fn available(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: u64,
+        cursor: Option<Device>,
+    ) -> anyhow::Result<Vec<Device>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = {
+            let mut select = Query::select();
+            select
+                .columns(DEVICE_COLUMNS)
+                .from(Devices::Table)
+                .cond_where(
+                    Cond::all()
+                        .add(
+                            Expr::exists(
+                                Query::select()
+                                    .expr(Expr::val(1))
+                                    .from(DeviceReservations::Table)
+                                    .and_where(
+                                        Expr::col((
+                                            DeviceReservations::Table,
+                                            DeviceReservations::DeviceId,
+                                        ))
+                                        .equals((Devices::Table, Devices::Id)),
+                                    )
+                                    .and_where(Expr::col(DeviceReservations::StartsAt).lt(to))
+                                    .and_where(Expr::col(DeviceReservations::EndsAt).gt(from))
+                                    .to_owned(),
+                            )
+                            .not(),
+                        )
+                        .add(Expr::col(Devices::DeletedAt).is_null()),
+                )
+                .order_by(Devices::Name, Order::Asc)
+                .order_by(Devices::Id, Order::Asc)
+                .limit(limit);
+
+            if let Some(cursor) = cursor {
+                select.cond_where(cursor_condition(
+                    DeviceSortColumn::Name,
+                    SortOrder::Asc,
+                    &cursor,
+                ));
+            }
+
+            select.to_string(PostgresQueryBuilder)
+        };
+
+        let res = sqlx::query_as::<_, Device>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to list the available devices")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn get(&self, id: uuid::Uuid) -> anyhow::Result<Option<Device>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(DEVICE_COLUMNS)
+            .from(Devices::Table)
+            .and_where(Expr::col(Devices::Id).eq(id))
+            .and_where(Expr::col(Devices::DeletedAt).is_null())
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Device>(&sql)
+            .fetch_optional(&mut *conn)
+            .await
+            .context("Failed to perform a sql to get a device")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn get_many(&self, ids: &[uuid::Uuid]) -> anyhow::Result<Vec<Device>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(DEVICE_COLUMNS)
+            .from(Devices::Table)
+            .and_where(Expr::col(Devices::Id).is_in(ids.iter().copied()))
+            .and_where(Expr::col(Devices::DeletedAt).is_null())
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Device>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to get devices")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn get_by_barcode(&self, barcode: &str) -> anyhow::Result<Option<Device>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(DEVICE_COLUMNS)
+            .from(Devices::Table)
+            .and_where(Expr::col(Devices::Barcode).eq(barcode))
+            .and_where(Expr::col(Devices::DeletedAt).is_null())
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Device>(&sql)
+            .fetch_optional(&mut *conn)
+            .await
+            .context("Failed to perform a sql to get a device by barcode")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn get_by_sn(&self, sn: &str) -> anyhow::Result<Option<Device>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(DEVICE_COLUMNS)
+            .from(Devices::Table)
+            .and_where(Expr::col(Devices::Sn).eq(sn))
+            .and_where(Expr::col(Devices::DeletedAt).is_null())
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Device>(&sql)
+            .fetch_optional(&mut *conn)
+            .await
+            .context("Failed to perform a sql to get a device by serial number")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn create(
+        &self,
+        request: CreateDeviceRequest,
+        created_by: uuid::Uuid,
+    ) -> anyhow::Result<Device> {
+        let mut conn = self.session.get_session().await?;
+
+        let mut tx = conn
+            .begin()
+            .await
+            .context("Failed to begin a transaction")
+            .map_err(AppError::UnexpectedError)?;
+
+        let res = insert_device(&mut tx, request).await?;
+
+        insert_history(
+            &mut tx,
+            res.id,
+            DeviceHistoryAction::Create,
+            created_by,
+            diff(None, Some(&res)),
+        )
+        .await?;
+
+        tx.commit()
+            .await
+            .context("Failed to commit the new device")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn create_many(
+        &self,
+        requests: Vec<CreateDeviceRequest>,
+        dry_run: bool,
+        created_by: uuid::Uuid,
+    ) -> anyhow::Result<Vec<Result<Device, AppError>>> {
+        let mut conn = self.session.get_session().await?;
+
+        let mut tx = conn
+            .begin()
+            .await
+            .context("Failed to begin a transaction")
+            .map_err(AppError::UnexpectedError)?;
+
+        // Every device is created in a savepoint, so a failed one doesn't
+        // abort the transaction and the next ones are still checked
+        let mut results = Vec::with_capacity(requests.len());
+        for request in requests {
+            let mut savepoint = tx
+                .begin()
+                .await
+                .context("Failed to create a savepoint")
+                .map_err(AppError::UnexpectedError)?;
+
+            match insert_device(&mut savepoint, request).await {
+                Ok(device) => {
+                    insert_history(
+                        &mut savepoint,
+                        device.id,
+                        DeviceHistoryAction::Create,
+                        created_by,
+                        diff(None, Some(&device)),
+                    )
+                    .await?;
+
+                    savepoint
+                        .commit()
+                        .await
+                        .context("Failed to release a savepoint")
+                        .map_err(AppError::UnexpectedError)?;
+                    results.push(Ok(device));
+                }
+                Err(e) => {
+                    let e = match AppError::from(e) {
+                        AppError::UnexpectedError(e) => return Err(e),
+                        e => e,
+                    };
+
+                    savepoint
+                        .rollback()
+                        .await
+                        .context("Failed to roll back to a savepoint")
+                        .map_err(AppError::UnexpectedError)?;
+                    results.push(Err(e));
+                }
+            }
+        }
+
+        if dry_run || results.iter().any(Result::is_err) {
+            tx.rollback()
+                .await
+                .context("Failed to roll back the imported devices")
+                .map_err(AppError::UnexpectedError)?;
+        } else {
+            tx.commit()
+                .await
+                .context("Failed to commit the imported devices")
+                .map_err(AppError::UnexpectedError)?;
+        }
+
+        Ok(results)
+    }
+
+    async fn update(
+        &self,
+        id: uuid::Uuid,
+        request: UpdateDeviceRequest,
+        if_match: &IfMatch,
+        updated_by: uuid::Uuid,
+    ) -> anyhow::Result<Option<Device>> {
+        let mut conn = self.session.get_session().await?;
+
+        let mut tx = conn
+            .begin()
+            .await
+            .context("Failed to begin a transaction")
+            .map_err(AppError::UnexpectedError)?;
+
+        let old = match select_for_update(&mut tx, id).await? {
+            Some(old) => old,
+            None => return Ok(None),
+        };
+        check_version(&old, if_match)?;
+
+        check_attributes(&mut tx, request.device_type_id, &request.attributes).await?;
+
+        let values = ConstrainedValues::from(&request);
+        let sql = Query::update()
+            .table(Devices::Table)
+            .values([
+                (Devices::Name, request.name.into()),
+                (Devices::OwnerId, request.owner_id.into()),
+                (Devices::Board, request.board.into()),
+                (Devices::Sn, request.sn.into()),
+                (Devices::Barcode, request.barcode.into()),
+                (Devices::ReceivedDate, request.received_date.into()),
+                (Devices::HwPhase, request.hw_phase.into()),
+                (Devices::Note, request.note.into()),
+                (Devices::DeviceTypeId, request.device_type_id.into()),
+                (
+                    Devices::Attributes,
+                    serde_json::Value::Object(request.attributes).into(),
+                ),
+            ])
+            .and_where(Expr::col(Devices::Id).eq(id))
+            .returning(Query::returning().columns(DEVICE_COLUMNS))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Device>(&sql)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| write_error(e, &values, "Failed to perform a sql to update a device"))?;
+
+        let changes = diff(Some(&old), Some(&res));
+        if changes.as_object().is_some_and(|c| !c.is_empty()) {
+            insert_history(
+                &mut tx,
+                id,
+                DeviceHistoryAction::Update,
+                updated_by,
+                changes,
+            )
+            .await?;
+        }
+
+        tx.commit()
+            .await
+            .context("Failed to commit the device update")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(Some(res))
+    }
+
+    async fn delete(
+        &self,
+        id: uuid::Uuid,
+        if_match: &IfMatch,
+        deleted_by: uuid::Uuid,
+    ) -> anyhow::Result<bool> {
+        let mut conn = self.session.get_session().await?;
+
+        let mut tx = conn
+            .begin()
+            .await
+            .context("Failed to begin a transaction")
+            .map_err(AppError::UnexpectedError)?;
+
+        let old = match select_for_update(&mut tx, id).await? {
+            Some(old) => old,
+            None => return Ok(false),
+        };
+        check_version(&old, if_match)?;
+
+        let sql = Query::update()
+            .table(Devices::Table)
+            .values([
+                (Devices::DeletedAt, Utc::now().into()),
+                (Devices::DeletedBy, deleted_by.into()),
+            ])
+            .and_where(Expr::col(Devices::Id).eq(id))
+            .to_string(PostgresQueryBuilder);
+
+        sqlx::query(&sql)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to perform a sql to move a device to the trash")
+            .map_err(AppError::UnexpectedError)?;
+
+        insert_history(
+            &mut tx,
+            id,
+            DeviceHistoryAction::Delete,
+            deleted_by,
+            diff(Some(&old), None),
+        )
+        .await?;
+
+        tx.commit()
+            .await
+            .context("Failed to commit the device deletion")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(true)
+    }
+
+    async fn trash(&self) -> anyhow::Result<Vec<TrashedDevice>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(DEVICE_COLUMNS)
+            .columns([Devices::DeletedAt, Devices::DeletedBy])
+            .from(Devices::Table)
+            .and_where(Expr::col(Devices::DeletedAt).is_not_null())
+            .order_by(Devices::DeletedAt, Order::Desc)
+            .order_by(Devices::Id, Order::Asc)
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, TrashedDevice>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to list the trash")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn restore(
+        &self,
+        id: uuid::Uuid,
+        restored_by: uuid::Uuid,
+    ) -> anyhow::Result<Option<Device>> {
+        let mut conn = self.session.get_session().await?;
+
+        let mut tx = conn
+            .begin()
+            .await
+            .context("Failed to begin a transaction")
+            .map_err(AppError::UnexpectedError)?;
+
+        let sql = Query::select()
+            .columns(DEVICE_COLUMNS)
+            .from(Devices::Table)
+            .and_where(Expr::col(Devices::Id).eq(id))
+            .and_where(Expr::col(Devices::DeletedAt).is_not_null())
+            .lock(LockType::Update)
+            .to_string(PostgresQueryBuilder);
+
+        let trashed = match sqlx::query_as::<_, Device>(&sql)
+            .fetch_optional(&mut *tx)
+            .await
+            .context("Failed to perform a sql to lock a deleted device")
+            .map_err(AppError::UnexpectedError)?
+        {
+            Some(device) => device,
+            None => return Ok(None),
+        };
+
+        // A live device may have taken the serial number or the barcode since
+        let sql = Query::update()
+            .table(Devices::Table)
+            .values([
+                (Devices::DeletedAt, None::<DateTime<Utc>>.into()),
+                (Devices::DeletedBy, None::<uuid::Uuid>.into()),
+            ])
+            .and_where(Expr::col(Devices::Id).eq(id))
+            .returning(Query::returning().columns(DEVICE_COLUMNS))
+            .to_string(PostgresQueryBuilder);
+
+        let device = sqlx::query_as::<_, Device>(&sql)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| {
+                write_error(
+                    e,
+                    &ConstrainedValues::from(&trashed),
+                    "Failed to perform a sql to restore a device",
+                )
+            })?;
+
+        insert_history(
+            &mut tx,
+            id,
+            DeviceHistoryAction::Restore,
+            restored_by,
+            diff(None, Some(&device)),
+        )
+        .await?;
+
+        tx.commit()
+            .await
+            .context("Failed to commit the device restoration")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(Some(device))
+    }
+
+    async fn purge(
+        &self,
+        deleted_before: DateTime<Utc>,
+        purged_by: uuid::Uuid,
+    ) -> anyhow::Result<Vec<uuid::Uuid>> {
+        let mut conn = self.session.get_session().await?;
+
+        let mut tx = conn
+            .begin()
+            .await
+            .context("Failed to begin a transaction")
+            .map_err(AppError::UnexpectedError)?;
+
+        let sql = Query::delete()
+            .from_table(Devices::Table)
+            .and_where(Expr::col(Devices::DeletedAt).lt(deleted_before))
+            .returning(Query::returning().column(Devices::Id))
+            .to_string(PostgresQueryBuilder);
+
+        let ids: Vec<uuid::Uuid> = sqlx::query(&sql)
+            .fetch_all(&mut *tx)
+            .await
+            .context("Failed to perform a sql to purge devices")
+            .map_err(AppError::UnexpectedError)?
+            .iter()
+            .map(|row| row.get(0))
+            .collect();
+
+        for id in &ids {
+            insert_history(
+                &mut tx,
+                *id,
+                DeviceHistoryAction::Purge,
+                purged_by,
+                diff(None, None),
+            )
+            .await?;
+        }
+
+        tx.commit()
+            .await
+            .context("Failed to commit the purge")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(ids)
+    }
+
+    async fn relocate(
+        &self,
+        id: uuid::Uuid,
+        location_id: Option<uuid::Uuid>,
+        moved_by: uuid::Uuid,
+    ) -> anyhow::Result<Option<Device>> {
+        let mut conn = self.session.get_session().await?;
+
+        let mut tx = conn
+            .begin()
+            .await
+            .context("Failed to begin a transaction")
+            .map_err(AppError::UnexpectedError)?;
+
+        let old = match select_for_update(&mut tx, id).await? {
+            Some(old) if old.location_id == location_id => return Ok(Some(old)),
+            Some(old) => old,
+            None => return Ok(None),
+        };
+
+        let sql = Query::update()
+            .table(Devices::Table)
+            .value(Devices::LocationId, location_id)
+            .and_where(Expr::col(Devices::Id).eq(id))
+            .returning(Query::returning().columns(DEVICE_COLUMNS))
+            .to_string(PostgresQueryBuilder);
+
+        let device = match sqlx::query_as::<_, Device>(&sql).fetch_one(&mut *tx).await {
+            Ok(device) => device,
+            Err(sqlx::Error::Database(e))
+                if e.code().as_deref() == Some(FOREIGN_KEY_VIOLATION)
+                    && e.constraint() == Some("devices_location_id_fkey") =>
+            {
+                return Err(AppError::InvalidRequest(format!(
+                    "unknown location `{}`",
+                    location_id.unwrap_or_default()
+                )))?
+            }
+            Err(e) => Err(e)
+                .context("Failed to perform a sql to move a device")
+                .map_err(AppError::UnexpectedError)?,
+        };
+
+        insert_history(
+            &mut tx,
+            id,
+            DeviceHistoryAction::Move,
+            moved_by,
+            diff(Some(&old), Some(&device)),
+        )
+        .await?;
+
+        tx.commit()
+            .await
+            .context("Failed to commit the move")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(Some(device))
+    }
+
+    async fn transition(
+        &self,
+        id: uuid::Uuid,
+        from: DeviceStatus,
+        to: DeviceStatus,
+        changed_by: uuid::Uuid,
+    ) -> anyhow::Result<Option<Device>> {
+        let mut conn = self.session.get_session().await?;
+
+        let mut tx = conn
+            .begin()
+            .await
+            .context("Failed to begin a transaction")
+            .map_err(AppError::UnexpectedError)?;
+
+        // Only move the device if nobody changed its status in the meantime
+        let old = match select_for_update(&mut tx, id).await? {
+            Some(old) if old.status == from => old,
+            _ => return Ok(None),
+        };
+
+        let sql = Query::update()
+            .table(Devices::Table)
+            .values([
+                (Devices::Status, to.as_str().into()),
+                (Devices::StatusChangedAt, Expr::cust("clock_timestamp()")),
+            ])
+            .and_where(Expr::col(Devices::Id).eq(id))
+            .returning(Query::returning().columns(DEVICE_COLUMNS))
+            .to_string(PostgresQueryBuilder);
+
+        let device = sqlx::query_as::<_, Device>(&sql)
+            .fetch_one(&mut *tx)
+            .await
+            .context("Failed to perform a sql to change the device status")
+            .map_err(AppError::UnexpectedError)?;
+
+        let sql = Query::insert()
+            .into_table(DeviceStatusChanges::Table)
+            .columns([
+                DeviceStatusChanges::Id,
+                DeviceStatusChanges::DeviceId,
+                DeviceStatusChanges::FromStatus,
+                DeviceStatusChanges::ToStatus,
+                DeviceStatusChanges::ChangedBy,
+                DeviceStatusChanges::ChangedAt,
+            ])
+            .values_panic([
+                uuid::Uuid::new_v4().into(),
+                id.into(),
+                from.as_str().into(),
+                to.as_str().into(),
+                changed_by.into(),
+                // The time the database just gave the device
+                SimpleExpr::SubQuery(
+                    None,
+                    Box::new(
+                        Query::select()
+                            .column(Devices::StatusChangedAt)
+                            .from(Devices::Table)
+                            .and_where(Expr::col(Devices::Id).eq(id))
+                            .to_owned()
+                            .into_sub_query_statement(),
+                    ),
+                ),
+            ])
+            .to_string(PostgresQueryBuilder);
+
+        sqlx::query(&sql)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to perform a sql to record the status change")
+            .map_err(AppError::UnexpectedError)?;
+
+        insert_history(
+            &mut tx,
+            id,
+            DeviceHistoryAction::StatusChange,
+            changed_by,
+            diff(Some(&old), Some(&device)),
+        )
+        .await?;
+
+        tx.commit()
+            .await
+            .context("Failed to commit the status change")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(Some(device))
+    }
+
+    async fn promote(
+        &self,
+        ids: &[uuid::Uuid],
+        to_phase: Option<&str>,
+        allow_skip: bool,
+        promoted_by: uuid::Uuid,
+    ) -> anyhow::Result<Vec<Device>> {
+        let mut conn = self.session.get_session().await?;
+
+        let mut tx = conn
+            .begin()
+            .await
+            .context("Failed to begin a transaction")
+            .map_err(AppError::UnexpectedError)?;
+
+        // The order can't change while the devices move
+        let sql = Query::select()
+            .columns([HwPhases::Name, HwPhases::Position])
+            .from(HwPhases::Table)
+            .order_by(HwPhases::Position, Order::Asc)
+            .lock_shared()
+            .to_string(PostgresQueryBuilder);
+
+        let phases: Vec<(String, i32)> = sqlx::query_as(&sql)
+            .fetch_all(&mut *tx)
+            .await
+            .context("Failed to perform a sql to list hardware phases")
+            .map_err(AppError::UnexpectedError)?;
+
+        let target = match to_phase {
+            Some(to_phase) => Some(
+                phases
+                    .iter()
+                    .find(|(name, _)| name == to_phase)
+                    .ok_or_else(|| {
+                        AppError::InvalidRequest(format!("unknown hardware phase `{to_phase}`"))
+                    })?,
+            ),
+            None => None,
+        };
+
+        // Lock in a stable order so concurrent promotions can't deadlock
+        let sql = Query::select()
+            .columns(DEVICE_COLUMNS)
+            .from(Devices::Table)
+            .and_where(Expr::col(Devices::Id).is_in(ids.iter().copied()))
+            .and_where(Expr::col(Devices::DeletedAt).is_null())
+            .order_by(Devices::Id, Order::Asc)
+            .lock(LockType::Update)
+            .to_string(PostgresQueryBuilder);
+
+        let devices = sqlx::query_as::<_, Device>(&sql)
+            .fetch_all(&mut *tx)
+            .await
+            .context("Failed to perform a sql to lock devices")
+            .map_err(AppError::UnexpectedError)?;
+
+        let mut promoted: Vec<Device> = Vec::with_capacity(ids.len());
+        for id in ids {
+            if promoted.iter().any(|device| device.id == *id) {
+                continue;
+            }
+            let old = devices
+                .iter()
+                .find(|device| device.id == *id)
+                .ok_or_else(|| AppError::InvalidRequest(format!("unknown device {id}")))?;
+
+            // A device without a phase is before the first phase
+            let current = phases
+                .iter()
+                .find(|(name, _)| Some(name) == old.hw_phase.as_ref())
+                .map(|(_, position)| *position)
+                .unwrap_or(0);
+            let next = phases.iter().find(|(_, position)| *position > current);
+            let (phase, position) = match (target, next) {
+                (Some(target), _) => target,
+                (None, Some(next)) => next,
+                (None, None) => {
+                    return Err(AppError::Conflict(format!(
+                        "`{}` is already in the last hardware phase",
+                        old.name
+                    )))?
+                }
+            };
+
+            if *position <= current {
+                return Err(AppError::Conflict(format!(
+                    "`{}` is already in {}, a promotion can't go backwards",
+                    old.name,
+                    old.hw_phase.as_deref().unwrap_or_default()
+                )))?;
+            }
+            if next.is_some_and(|(_, next)| next < position) && !allow_skip {
+                return Err(AppError::Auth(AuthError::Forbidden))?;
+            }
+
+            let sql = Query::update()
+                .table(Devices::Table)
+                .value(Devices::HwPhase, phase.as_str())
+                .and_where(Expr::col(Devices::Id).eq(*id))
+                .returning(Query::returning().columns(DEVICE_COLUMNS))
+                .to_string(PostgresQueryBuilder);
+
+            let device = sqlx::query_as::<_, Device>(&sql)
+                .fetch_one(&mut *tx)
+                .await
+                .context("Failed to perform a sql to promote a device")
+                .map_err(AppError::UnexpectedError)?;
+
+            insert_history(
+                &mut tx,
+                *id,
+                DeviceHistoryAction::Update,
+                promoted_by,
+                diff(Some(old), Some(&device)),
+            )
+            .await?;
+
+            promoted.push(device);
+        }
+
+        tx.commit()
+            .await
+            .context("Failed to commit the promotion")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(promoted)
+    }
+
+    async fn status_history(&self, id: uuid::Uuid) -> anyhow::Result<Vec<DeviceStatusChange>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns([
+                DeviceStatusChanges::Id,
+                DeviceStatusChanges::DeviceId,
+                DeviceStatusChanges::FromStatus,
+                DeviceStatusChanges::ToStatus,
+                DeviceStatusChanges::ChangedBy,
+                DeviceStatusChanges::ChangedAt,
+            ])
+            .from(DeviceStatusChanges::Table)
+            .and_where(Expr::col(DeviceStatusChanges::DeviceId).eq(id))
+            .order_by(DeviceStatusChanges::ChangedAt, Order::Asc)
+            .order_by(DeviceStatusChanges::Seq, Order::Asc)
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, DeviceStatusChange>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to get the status history")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn history(&self, id: uuid::Uuid) -> anyhow::Result<Vec<DeviceHistoryEntry>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(HISTORY_COLUMNS)
+            .from(DeviceHistory::Table)
+            .and_where(Expr::col(DeviceHistory::DeviceId).eq(id))
+            .order_by(DeviceHistory::ChangedAt, Order::Asc)
+            .order_by(DeviceHistory::Seq, Order::Asc)
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, DeviceHistoryEntry>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to get the device history")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+}
@@ -0,0 +1,229 @@
+use anyhow::Context;
+use sea_query::{Expr, Func, Order, PostgresQueryBuilder, Query};
+use sqlx::{Connection, Row};
+
+use crate::{
+    attributes,
+    errors::AppError,
+    models::{
+        device_table::Devices,
+        device_type::{DeviceType, DeviceTypeRequest},
+        device_type_table::DeviceTypes,
+    },
+    utils::PostgresSession,
+};
+
+use super::i_device_type_repository::IDeviceTypeRepository;
+use super::{FOREIGN_KEY_VIOLATION, UNIQUE_VIOLATION};
+
+pub struct PostgresDeviceTypeRepository {
+    session: PostgresSession,
+}
+
+impl PostgresDeviceTypeRepository {
+    pub fn new(session: PostgresSession) -> Self {
+        Self { session }
+    }
+}
+
+const DEVICE_TYPE_COLUMNS: [DeviceTypes; 4] = [
+    DeviceTypes::Id,
+    DeviceTypes::Name,
+    DeviceTypes::AttributeSchema,
+    DeviceTypes::CreatedAt,
+];
+
+/// Turn a duplicated name into a conflict, any other error is unexpected
+fn write_error(e: sqlx::Error, name: &str, context: &'static str) -> anyhow::Error {
+    match &e {
+        sqlx::Error::Database(db)
+            if db.code().as_deref() == Some(UNIQUE_VIOLATION)
+                && db.constraint() == Some("device_types_name_key") =>
+        {
+            AppError::Conflict(format!("a device type named `{name}` already exists")).into()
+        }
+        _ => AppError::UnexpectedError(anyhow::Error::new(e).context(context)).into(),
+    }
+}
+
+#[async_trait::async_trait]
+impl IDeviceTypeRepository for PostgresDeviceTypeRepository {
+    async fn list(&self) -> anyhow::Result<Vec<DeviceType>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(DEVICE_TYPE_COLUMNS)
+            .from(DeviceTypes::Table)
+            .order_by(DeviceTypes::Name, Order::Asc)
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, DeviceType>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to list device types")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn get(&self, id: uuid::Uuid) -> anyhow::Result<Option<DeviceType>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(DEVICE_TYPE_COLUMNS)
+            .from(DeviceTypes::Table)
+            .and_where(Expr::col(DeviceTypes::Id).eq(id))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, DeviceType>(&sql)
+            .fetch_optional(&mut *conn)
+            .await
+            .context("Failed to perform a sql to get a device type")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn create(&self, request: DeviceTypeRequest) -> anyhow::Result<DeviceType> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::insert()
+            .into_table(DeviceTypes::Table)
+            .columns([
+                DeviceTypes::Id,
+                DeviceTypes::Name,
+                DeviceTypes::AttributeSchema,
+            ])
+            .values_panic([
+                uuid::Uuid::new_v4().into(),
+                request.name.clone().into(),
+                request.attribute_schema.clone().into(),
+            ])
+            .returning(Query::returning().columns(DEVICE_TYPE_COLUMNS))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, DeviceType>(&sql)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| {
+                write_error(
+                    e,
+                    &request.name,
+                    "Failed to perform a sql to create a device type",
+                )
+            })?;
+
+        Ok(res)
+    }
+
+    async fn update(
+        &self,
+        id: uuid::Uuid,
+        request: DeviceTypeRequest,
+    ) -> anyhow::Result<Option<DeviceType>> {
+        let mut conn = self.session.get_session().await?;
+
+        let mut tx = conn
+            .begin()
+            .await
+            .context("Failed to begin a transaction")
+            .map_err(AppError::UnexpectedError)?;
+
+        let sql = Query::update()
+            .table(DeviceTypes::Table)
+            .values([
+                (DeviceTypes::Name, request.name.clone().into()),
+                (
+                    DeviceTypes::AttributeSchema,
+                    request.attribute_schema.clone().into(),
+                ),
+            ])
+            .and_where(Expr::col(DeviceTypes::Id).eq(id))
+            .returning(Query::returning().columns(DEVICE_TYPE_COLUMNS))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, DeviceType>(&sql)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| {
+                write_error(
+                    e,
+                    &request.name,
+                    "Failed to perform a sql to update a device type",
+                )
+            })?;
+        let res = match res {
+            Some(res) => res,
+            None => return Ok(None),
+        };
+
+        // The devices of the type must still be valid under the new schema,
+        // the locked type keeps new devices out until the commit
+        let schema =
+            attributes::compile(&res.attribute_schema).map_err(AppError::InvalidRequest)?;
+        let sql = Query::select()
+            .column(Devices::Attributes)
+            .from(Devices::Table)
+            .and_where(Expr::col(Devices::DeviceTypeId).eq(id))
+            .to_string(PostgresQueryBuilder);
+
+        let invalid = sqlx::query(&sql)
+            .fetch_all(&mut *tx)
+            .await
+            .context("Failed to perform a sql to get the attributes of the devices of a type")
+            .map_err(AppError::UnexpectedError)?
+            .iter()
+            .filter(|row| !schema.is_valid(&row.get::<serde_json::Value, usize>(0)))
+            .count();
+        if invalid > 0 {
+            return Err(AppError::Conflict(format!(
+                "the attributes of {invalid} device(s) of the type don't match the new schema"
+            )))?;
+        }
+
+        tx.commit()
+            .await
+            .context("Failed to commit the device type update")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(Some(res))
+    }
+
+    async fn delete(&self, id: uuid::Uuid) -> anyhow::Result<bool> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::delete()
+            .from_table(DeviceTypes::Table)
+            .and_where(Expr::col(DeviceTypes::Id).eq(id))
+            .to_string(PostgresQueryBuilder);
+
+        let res = match sqlx::query(&sql).execute(&mut *conn).await {
+            Ok(res) => res,
+            Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some(FOREIGN_KEY_VIOLATION) => {
+                // Tell how many devices are in the way
+                let sql = Query::select()
+                    .expr(Func::count(Expr::col(Devices::Id)))
+                    .from(Devices::Table)
+                    .and_where(Expr::col(Devices::DeviceTypeId).eq(id))
+                    .to_string(PostgresQueryBuilder);
+
+                let used_by = sqlx::query(&sql)
+                    .fetch_one(&mut *conn)
+                    .await
+                    .context("Failed to perform a sql to count the devices of a type")
+                    .map_err(AppError::UnexpectedError)?
+                    .get::<i64, usize>(0);
+
+                return Err(AppError::Conflict(format!(
+                    "the device type is still used by {used_by} device(s), \
+                     change their type before deleting it"
+                )))?;
+            }
+            Err(e) => Err(e)
+                .context("Failed to perform a sql to delete a device type")
+                .map_err(AppError::UnexpectedError)?,
+        };
+
+        Ok(res.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,297 @@
+use anyhow::Context;
+use sea_query::{Expr, Func, Order, PostgresQueryBuilder, Query};
+use sqlx::{Connection, PgConnection, Row};
+
+use crate::{
+    errors::AppError,
+    models::{
+        device_table::Devices,
+        hw_phase::{HwPhase, HwPhaseRequest},
+        hw_phase_table::HwPhases,
+    },
+    utils::PostgresSession,
+};
+
+use super::i_hw_phase_repository::IHwPhaseRepository;
+use super::postgres_device_repository::record_cascaded_rename;
+use super::{FOREIGN_KEY_VIOLATION, UNIQUE_VIOLATION};
+
+pub struct PostgresHwPhaseRepository {
+    session: PostgresSession,
+}
+
+impl PostgresHwPhaseRepository {
+    pub fn new(session: PostgresSession) -> Self {
+        Self { session }
+    }
+}
+
+const PHASE_COLUMNS: [HwPhases; 4] = [
+    HwPhases::Id,
+    HwPhases::Name,
+    HwPhases::Position,
+    HwPhases::CreatedAt,
+];
+
+/// Turn a duplicated name into a conflict, any other error is unexpected
+fn write_error(e: sqlx::Error, name: &str, context: &'static str) -> anyhow::Error {
+    match &e {
+        sqlx::Error::Database(db)
+            if db.code().as_deref() == Some(UNIQUE_VIOLATION)
+                && matches!(
+                    db.constraint(),
+                    Some("hw_phases_name_key" | "hw_phases_name_lower_key")
+                ) =>
+        {
+            AppError::Conflict(format!("a hardware phase named `{name}` already exists")).into()
+        }
+        _ => AppError::UnexpectedError(anyhow::Error::new(e).context(context)).into(),
+    }
+}
+
+/// Keep the other changes of the order out until the transaction ends, the
+/// positions are only checked to be unique at its commit
+async fn lock_order(conn: &mut PgConnection) -> anyhow::Result<()> {
+    sqlx::query("LOCK TABLE hw_phases IN SHARE ROW EXCLUSIVE MODE")
+        .execute(conn)
+        .await
+        .context("Failed to perform a sql to lock the hardware phases")
+        .map_err(AppError::UnexpectedError)?;
+
+    Ok(())
+}
+
+/// The position after the last phase
+async fn next_position(conn: &mut PgConnection) -> anyhow::Result<i32> {
+    let sql = Query::select()
+        .expr(Func::coalesce([
+            Func::max(Expr::col(HwPhases::Position)).into(),
+            Expr::val(0).into(),
+        ]))
+        .from(HwPhases::Table)
+        .to_string(PostgresQueryBuilder);
+
+    let last = sqlx::query(&sql)
+        .fetch_one(conn)
+        .await
+        .context("Failed to perform a sql to get the last hardware phase")
+        .map_err(AppError::UnexpectedError)?
+        .get::<i32, usize>(0);
+
+    Ok(last + 1)
+}
+
+/// Move the phases from `position` on by `offset` places
+async fn shift(conn: &mut PgConnection, position: i32, offset: i32) -> anyhow::Result<()> {
+    let sql = Query::update()
+        .table(HwPhases::Table)
+        .value(
+            HwPhases::Position,
+            Expr::col(HwPhases::Position).add(offset),
+        )
+        .and_where(Expr::col(HwPhases::Position).gte(position))
+        .to_string(PostgresQueryBuilder);
+
+    sqlx::query(&sql)
+        .execute(conn)
+        .await
+        .context("Failed to perform a sql to reorder hardware phases")
+        .map_err(AppError::UnexpectedError)?;
+
+    Ok(())
+}
+
+/// Clamp the requested position to the existing phases, `last` is the
+/// position a phase takes at the end
+fn clamp_position(position: Option<i32>, last: i32) -> Result<i32, AppError> {
+    match position {
+        Some(position) if position < 1 => Err(AppError::InvalidRequest(
+            "position must be at least 1".to_owned(),
+        )),
+        Some(position) => Ok(position.min(last)),
+        None => Ok(last),
+    }
+}
+
+#[async_trait::async_trait]
+impl IHwPhaseRepository for PostgresHwPhaseRepository {
+    async fn list(&self) -> anyhow::Result<Vec<HwPhase>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(PHASE_COLUMNS)
+            .from(HwPhases::Table)
+            .order_by(HwPhases::Position, Order::Asc)
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, HwPhase>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to list hardware phases")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn create(&self, request: HwPhaseRequest) -> anyhow::Result<HwPhase> {
+        let mut conn = self.session.get_session().await?;
+
+        let mut tx = conn
+            .begin()
+            .await
+            .context("Failed to begin a transaction")
+            .map_err(AppError::UnexpectedError)?;
+        lock_order(&mut tx).await?;
+
+        let position = clamp_position(request.position, next_position(&mut tx).await?)?;
+        shift(&mut tx, position, 1).await?;
+
+        let sql = Query::insert()
+            .into_table(HwPhases::Table)
+            .columns([HwPhases::Id, HwPhases::Name, HwPhases::Position])
+            .values_panic([
+                uuid::Uuid::new_v4().into(),
+                request.name.clone().into(),
+                position.into(),
+            ])
+            .returning(Query::returning().columns(PHASE_COLUMNS))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, HwPhase>(&sql)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| {
+                write_error(
+                    e,
+                    &request.name,
+                    "Failed to perform a sql to create a hardware phase",
+                )
+            })?;
+
+        tx.commit()
+            .await
+            .context("Failed to commit the new hardware phase")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn update(
+        &self,
+        id: uuid::Uuid,
+        request: HwPhaseRequest,
+        updated_by: uuid::Uuid,
+    ) -> anyhow::Result<Option<HwPhase>> {
+        let mut conn = self.session.get_session().await?;
+
+        let mut tx = conn
+            .begin()
+            .await
+            .context("Failed to begin a transaction")
+            .map_err(AppError::UnexpectedError)?;
+        lock_order(&mut tx).await?;
+
+        let sql = Query::select()
+            .columns([HwPhases::Name, HwPhases::Position])
+            .from(HwPhases::Table)
+            .and_where(Expr::col(HwPhases::Id).eq(id))
+            .lock_exclusive()
+            .to_string(PostgresQueryBuilder);
+
+        let current = sqlx::query(&sql)
+            .fetch_optional(&mut *tx)
+            .await
+            .context("Failed to perform a sql to lock a hardware phase")
+            .map_err(AppError::UnexpectedError)?
+            .map(|row| (row.get::<String, usize>(0), row.get::<i32, usize>(1)));
+        let (old_name, current) = match current {
+            Some(current) => current,
+            None => return Ok(None),
+        };
+
+        // Take the phase out of the order and put it back at the new position,
+        // the last position is the current number of phases
+        let position = clamp_position(request.position, next_position(&mut tx).await? - 1)?;
+        shift(&mut tx, current + 1, -1).await?;
+        shift(&mut tx, position, 1).await?;
+
+        let sql = Query::update()
+            .table(HwPhases::Table)
+            .values([
+                (HwPhases::Name, request.name.clone().into()),
+                (HwPhases::Position, position.into()),
+            ])
+            .and_where(Expr::col(HwPhases::Id).eq(id))
+            .returning(Query::returning().columns(PHASE_COLUMNS))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, HwPhase>(&sql)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| {
+                write_error(
+                    e,
+                    &request.name,
+                    "Failed to perform a sql to update a hardware phase",
+                )
+            })?;
+        record_cascaded_rename(
+            &mut tx,
+            Devices::HwPhase,
+            "hwPhase",
+            &old_name,
+            &res.name,
+            updated_by,
+        )
+        .await?;
+
+        tx.commit()
+            .await
+            .context("Failed to commit the hardware phase update")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(Some(res))
+    }
+
+    async fn delete(&self, id: uuid::Uuid) -> anyhow::Result<bool> {
+        let mut conn = self.session.get_session().await?;
+
+        let mut tx = conn
+            .begin()
+            .await
+            .context("Failed to begin a transaction")
+            .map_err(AppError::UnexpectedError)?;
+        lock_order(&mut tx).await?;
+
+        let sql = Query::delete()
+            .from_table(HwPhases::Table)
+            .and_where(Expr::col(HwPhases::Id).eq(id))
+            .returning(Query::returning().columns([HwPhases::Name, HwPhases::Position]))
+            .to_string(PostgresQueryBuilder);
+
+        let deleted = match sqlx::query(&sql).fetch_optional(&mut *tx).await {
+            Ok(deleted) => deleted,
+            Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some(FOREIGN_KEY_VIOLATION) => {
+                return Err(AppError::Conflict(
+                    "the hardware phase is still used by devices".to_owned(),
+                ))?
+            }
+            Err(e) => Err(e)
+                .context("Failed to perform a sql to delete a hardware phase")
+                .map_err(AppError::UnexpectedError)?,
+        };
+        let position = match deleted {
+            Some(row) => row.get::<i32, &str>("position"),
+            None => return Ok(false),
+        };
+
+        shift(&mut tx, position + 1, -1).await?;
+
+        tx.commit()
+            .await
+            .context("Failed to commit the hardware phase deletion")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(true)
+    }
+}
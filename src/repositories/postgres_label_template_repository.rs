@@ -0,0 +1,232 @@
+use anyhow::Context;
+use sea_query::{Expr, Order, PostgresQueryBuilder, Query};
+use sqlx::{Connection, PgConnection};
+
+use crate::{
+    errors::AppError,
+    models::{
+        label_template::{LabelTemplate, LabelTemplateRequest},
+        label_template_table::LabelTemplates,
+    },
+    utils::PostgresSession,
+};
+
+use super::i_label_template_repository::ILabelTemplateRepository;
+use super::UNIQUE_VIOLATION;
+
+pub struct PostgresLabelTemplateRepository {
+    session: PostgresSession,
+}
+
+impl PostgresLabelTemplateRepository {
+    pub fn new(session: PostgresSession) -> Self {
+        Self { session }
+    }
+}
+
+const TEMPLATE_COLUMNS: [LabelTemplates; 5] = [
+    LabelTemplates::Id,
+    LabelTemplates::Name,
+    LabelTemplates::Zpl,
+    LabelTemplates::IsDefault,
+    LabelTemplates::CreatedAt,
+];
+
+/// Turn a duplicated name into a conflict, any other error is unexpected
+fn write_error(e: sqlx::Error, name: &str, context: &'static str) -> anyhow::Error {
+    match &e {
+        sqlx::Error::Database(db) if db.code().as_deref() == Some(UNIQUE_VIOLATION) => {
+            AppError::Conflict(format!("a label template named `{name}` already exists")).into()
+        }
+        _ => AppError::UnexpectedError(anyhow::Error::new(e).context(context)).into(),
+    }
+}
+
+/// Unset the current default template unless it is `id`
+async fn clear_default(conn: &mut PgConnection, id: uuid::Uuid) -> anyhow::Result<()> {
+    let sql = Query::update()
+        .table(LabelTemplates::Table)
+        .value(LabelTemplates::IsDefault, false)
+        .and_where(Expr::col(LabelTemplates::IsDefault).eq(true))
+        .and_where(Expr::col(LabelTemplates::Id).ne(id))
+        .to_string(PostgresQueryBuilder);
+
+    sqlx::query(&sql)
+        .execute(conn)
+        .await
+        .context("Failed to perform a sql to unset the default label template")
+        .map_err(AppError::UnexpectedError)?;
+
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl ILabelTemplateRepository for PostgresLabelTemplateRepository {
+    async fn list(&self) -> anyhow::Result<Vec<LabelTemplate>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(TEMPLATE_COLUMNS)
+            .from(LabelTemplates::Table)
+            .order_by(LabelTemplates::Name, Order::Asc)
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, LabelTemplate>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to list label templates")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn get(&self, id: uuid::Uuid) -> anyhow::Result<Option<LabelTemplate>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(TEMPLATE_COLUMNS)
+            .from(LabelTemplates::Table)
+            .and_where(Expr::col(LabelTemplates::Id).eq(id))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, LabelTemplate>(&sql)
+            .fetch_optional(&mut *conn)
+            .await
+            .context("Failed to perform a sql to get a label template")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn get_default(&self) -> anyhow::Result<Option<LabelTemplate>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(TEMPLATE_COLUMNS)
+            .from(LabelTemplates::Table)
+            .and_where(Expr::col(LabelTemplates::IsDefault).eq(true))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, LabelTemplate>(&sql)
+            .fetch_optional(&mut *conn)
+            .await
+            .context("Failed to perform a sql to get the default label template")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn create(&self, request: LabelTemplateRequest) -> anyhow::Result<LabelTemplate> {
+        let mut conn = self.session.get_session().await?;
+
+        let mut tx = conn
+            .begin()
+            .await
+            .context("Failed to begin a transaction")
+            .map_err(AppError::UnexpectedError)?;
+
+        let id = uuid::Uuid::new_v4();
+        if request.is_default {
+            clear_default(&mut tx, id).await?;
+        }
+
+        let sql = Query::insert()
+            .into_table(LabelTemplates::Table)
+            .columns([
+                LabelTemplates::Id,
+                LabelTemplates::Name,
+                LabelTemplates::Zpl,
+                LabelTemplates::IsDefault,
+            ])
+            .values_panic([
+                id.into(),
+                request.name.clone().into(),
+                request.zpl.into(),
+                request.is_default.into(),
+            ])
+            .returning(Query::returning().columns(TEMPLATE_COLUMNS))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, LabelTemplate>(&sql)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| {
+                write_error(
+                    e,
+                    &request.name,
+                    "Failed to perform a sql to create a label template",
+                )
+            })?;
+
+        tx.commit()
+            .await
+            .context("Failed to commit the new label template")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn update(
+        &self,
+        id: uuid::Uuid,
+        request: LabelTemplateRequest,
+    ) -> anyhow::Result<Option<LabelTemplate>> {
+        let mut conn = self.session.get_session().await?;
+
+        let mut tx = conn
+            .begin()
+            .await
+            .context("Failed to begin a transaction")
+            .map_err(AppError::UnexpectedError)?;
+
+        if request.is_default {
+            clear_default(&mut tx, id).await?;
+        }
+
+        let sql = Query::update()
+            .table(LabelTemplates::Table)
+            .values([
+                (LabelTemplates::Name, request.name.clone().into()),
+                (LabelTemplates::Zpl, request.zpl.into()),
+                (LabelTemplates::IsDefault, request.is_default.into()),
+            ])
+            .and_where(Expr::col(LabelTemplates::Id).eq(id))
+            .returning(Query::returning().columns(TEMPLATE_COLUMNS))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, LabelTemplate>(&sql)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| {
+                write_error(
+                    e,
+                    &request.name,
+                    "Failed to perform a sql to update a label template",
+                )
+            })?;
+
+        tx.commit()
+            .await
+            .context("Failed to commit the label template update")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn delete(&self, id: uuid::Uuid) -> anyhow::Result<bool> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::delete()
+            .from_table(LabelTemplates::Table)
+            .and_where(Expr::col(LabelTemplates::Id).eq(id))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query(&sql)
+            .execute(&mut *conn)
+            .await
+            .context("Failed to perform a sql to delete a label template")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res.rows_affected() > 0)
+    }
+}
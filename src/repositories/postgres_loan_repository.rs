@@ -0,0 +1,163 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use sea_query::{Cond, Expr, OnConflict, Order, PostgresQueryBuilder, Query};
+
+use crate::{
+    errors::AppError,
+    models::{loan::Loan, loan_table::DeviceLoans},
+    utils::PostgresSession,
+};
+
+use super::i_loan_repository::ILoanRepository;
+use super::postgres_device_repository::live_devices;
+
+pub struct PostgresLoanRepository {
+    session: PostgresSession,
+}
+
+impl PostgresLoanRepository {
+    pub fn new(session: PostgresSession) -> Self {
+        Self { session }
+    }
+}
+
+const LOAN_COLUMNS: [DeviceLoans; 9] = [
+    DeviceLoans::Id,
+    DeviceLoans::DeviceId,
+    DeviceLoans::BorrowerId,
+    DeviceLoans::LentBy,
+    DeviceLoans::CheckedOutAt,
+    DeviceLoans::ExpectedReturnAt,
+    DeviceLoans::ReturnedAt,
+    DeviceLoans::ReturnedBy,
+    DeviceLoans::Note,
+];
+
+#[async_trait::async_trait]
+impl ILoanRepository for PostgresLoanRepository {
+    async fn checkout(
+        &self,
+        device_id: uuid::Uuid,
+        borrower_id: uuid::Uuid,
+        lent_by: uuid::Uuid,
+        expected_return_at: DateTime<Utc>,
+        note: Option<String>,
+    ) -> anyhow::Result<Option<Loan>> {
+        let mut conn = self.session.get_session().await?;
+
+        // The partial unique index only allows one open loan per device
+        let sql = Query::insert()
+            .into_table(DeviceLoans::Table)
+            .columns([
+                DeviceLoans::Id,
+                DeviceLoans::DeviceId,
+                DeviceLoans::BorrowerId,
+                DeviceLoans::LentBy,
+                DeviceLoans::CheckedOutAt,
+                DeviceLoans::ExpectedReturnAt,
+                DeviceLoans::Note,
+            ])
+            .values_panic([
+                uuid::Uuid::new_v4().into(),
+                device_id.into(),
+                borrower_id.into(),
+                lent_by.into(),
+                Utc::now().into(),
+                expected_return_at.into(),
+                note.into(),
+            ])
+            .on_conflict(
+                OnConflict::column(DeviceLoans::DeviceId)
+                    .target_and_where(Expr::col(DeviceLoans::ReturnedAt).is_null())
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .returning(Query::returning().columns(LOAN_COLUMNS))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Loan>(&sql)
+            .fetch_optional(&mut *conn)
+            .await
+            .context("Failed to perform a sql to check out a device")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn checkin(
+        &self,
+        device_id: uuid::Uuid,
+        returned_by: uuid::Uuid,
+    ) -> anyhow::Result<Option<Loan>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::update()
+            .table(DeviceLoans::Table)
+            .values([
+                (DeviceLoans::ReturnedAt, Utc::now().into()),
+                (DeviceLoans::ReturnedBy, returned_by.into()),
+            ])
+            .and_where(Expr::col(DeviceLoans::DeviceId).eq(device_id))
+            .and_where(Expr::col(DeviceLoans::ReturnedAt).is_null())
+            .returning(Query::returning().columns(LOAN_COLUMNS))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Loan>(&sql)
+            .fetch_optional(&mut *conn)
+            .await
+            .context("Failed to perform a sql to check in a device")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn history(&self, device_id: uuid::Uuid) -> anyhow::Result<Vec<Loan>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(LOAN_COLUMNS)
+            .from(DeviceLoans::Table)
+            .and_where(Expr::col(DeviceLoans::DeviceId).eq(device_id))
+            .order_by(DeviceLoans::CheckedOutAt, Order::Desc)
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Loan>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to get the loan history")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn list_open(
+        &self,
+        borrower_id: Option<uuid::Uuid>,
+        overdue_at: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<Vec<Loan>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(LOAN_COLUMNS)
+            .from(DeviceLoans::Table)
+            .cond_where(
+                Cond::all()
+                    .add(Expr::col(DeviceLoans::ReturnedAt).is_null())
+                    .add(Expr::col(DeviceLoans::DeviceId).in_subquery(live_devices()))
+                    .add_option(borrower_id.map(|id| Expr::col(DeviceLoans::BorrowerId).eq(id)))
+                    .add_option(
+                        overdue_at.map(|at| Expr::col(DeviceLoans::ExpectedReturnAt).lt(at)),
+                    ),
+            )
+            .order_by(DeviceLoans::ExpectedReturnAt, Order::Asc)
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Loan>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to list the open loans")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+}
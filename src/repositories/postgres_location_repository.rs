@@ -0,0 +1,357 @@
+use anyhow::Context;
+use sea_query::{
+    Alias, CommonTableExpression, Expr, LockType, Order, PostgresQueryBuilder, Query,
+    QueryStatementWriter, UnionType, WithClause, WithQuery,
+};
+use sqlx::{Connection, PgConnection, Row};
+
+use crate::{
+    errors::AppError,
+    models::{
+        location::{Location, LocationDetail, LocationKind, LocationRequest},
+        location_table::Locations,
+    },
+    utils::PostgresSession,
+};
+
+use super::i_location_repository::ILocationRepository;
+use super::{FOREIGN_KEY_VIOLATION, UNIQUE_VIOLATION};
+
+pub struct PostgresLocationRepository {
+    session: PostgresSession,
+}
+
+impl PostgresLocationRepository {
+    pub fn new(session: PostgresSession) -> Self {
+        Self { session }
+    }
+}
+
+const LOCATION_COLUMNS: [Locations; 5] = [
+    Locations::Id,
+    Locations::ParentId,
+    Locations::Kind,
+    Locations::Name,
+    Locations::CreatedAt,
+];
+
+/// Select the ids of a location and of every location under it, recursively
+pub(crate) fn subtree(id: uuid::Uuid) -> WithQuery {
+    let subtree = Alias::new("subtree");
+
+    let cte = CommonTableExpression::new()
+        .query(
+            Query::select()
+                .column(Locations::Id)
+                .from(Locations::Table)
+                .and_where(Expr::col(Locations::Id).eq(id))
+                .union(
+                    UnionType::All,
+                    Query::select()
+                        .column((Locations::Table, Locations::Id))
+                        .from(Locations::Table)
+                        .inner_join(
+                            subtree.clone(),
+                            Expr::col((Locations::Table, Locations::ParentId))
+                                .equals((subtree.clone(), Locations::Id)),
+                        )
+                        .to_owned(),
+                )
+                .to_owned(),
+        )
+        .column(Locations::Id)
+        .table_name(subtree.clone())
+        .to_owned();
+
+    Query::select()
+        .column(Locations::Id)
+        .from(subtree)
+        .to_owned()
+        .with(WithClause::new().cte(cte).recursive(true).to_owned())
+}
+
+/// Select a location and every location above it, from the site down
+fn ancestors(id: uuid::Uuid) -> WithQuery {
+    let ancestors = Alias::new("ancestors");
+    let depth = Alias::new("depth");
+
+    let cte = CommonTableExpression::new()
+        .query(
+            Query::select()
+                .columns(LOCATION_COLUMNS)
+                .expr(Expr::val(0))
+                .from(Locations::Table)
+                .and_where(Expr::col(Locations::Id).eq(id))
+                .union(
+                    UnionType::All,
+                    Query::select()
+                        .columns(LOCATION_COLUMNS.map(|column| (Locations::Table, column)))
+                        .expr(Expr::col((ancestors.clone(), depth.clone())).add(1))
+                        .from(Locations::Table)
+                        .inner_join(
+                            ancestors.clone(),
+                            Expr::col((Locations::Table, Locations::Id))
+                                .equals((ancestors.clone(), Locations::ParentId)),
+                        )
+                        .to_owned(),
+                )
+                .to_owned(),
+        )
+        .columns(LOCATION_COLUMNS)
+        .column(depth.clone())
+        .table_name(ancestors.clone())
+        .to_owned();
+
+    Query::select()
+        .columns(LOCATION_COLUMNS)
+        .from(ancestors)
+        .order_by(depth, Order::Desc)
+        .to_owned()
+        .with(WithClause::new().cte(cte).recursive(true).to_owned())
+}
+
+/// Turn a duplicated sibling name into a conflict, any other error is unexpected
+fn write_error(e: sqlx::Error, request: &LocationRequest, context: &'static str) -> anyhow::Error {
+    match &e {
+        sqlx::Error::Database(db)
+            if db.code().as_deref() == Some(UNIQUE_VIOLATION)
+                && db.constraint() == Some("locations_sibling_name_idx") =>
+        {
+            AppError::Conflict(format!(
+                "a location named `{}` already exists there",
+                request.name
+            ))
+            .into()
+        }
+        _ => AppError::UnexpectedError(anyhow::Error::new(e).context(context)).into(),
+    }
+}
+
+/// Check the kind of a location against the kind of its parent, the parent is
+/// locked so its kind can't change before the location is written
+async fn check_parent(conn: &mut PgConnection, request: &LocationRequest) -> anyhow::Result<()> {
+    let parent_kind = match request.parent_id {
+        Some(parent_id) => {
+            let sql = Query::select()
+                .column(Locations::Kind)
+                .from(Locations::Table)
+                .and_where(Expr::col(Locations::Id).eq(parent_id))
+                .lock(LockType::Share)
+                .to_string(PostgresQueryBuilder);
+
+            let kind = sqlx::query(&sql)
+                .fetch_optional(conn)
+                .await
+                .context("Failed to perform a sql to get the parent of a location")
+                .map_err(AppError::UnexpectedError)?
+                .map(|row| row.get::<LocationKind, usize>(0))
+                .ok_or_else(|| {
+                    AppError::InvalidRequest(format!("unknown location `{parent_id}`"))
+                })?;
+            Some(kind)
+        }
+        None => None,
+    };
+
+    if !request.kind.can_be_under(parent_kind) {
+        return Err(AppError::InvalidRequest(match parent_kind {
+            Some(parent_kind) => format!("a {} can't be in a {parent_kind}", request.kind),
+            None => format!("a {} must be in another location", request.kind),
+        }))?;
+    }
+
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl ILocationRepository for PostgresLocationRepository {
+    async fn list(&self) -> anyhow::Result<Vec<Location>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(LOCATION_COLUMNS)
+            .from(Locations::Table)
+            .order_by(Locations::Name, Order::Asc)
+            .order_by(Locations::Id, Order::Asc)
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Location>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to list locations")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn get(&self, id: uuid::Uuid) -> anyhow::Result<Option<LocationDetail>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = ancestors(id).to_string(PostgresQueryBuilder);
+
+        let mut path = sqlx::query_as::<_, Location>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to get a location")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(path.pop().map(|location| LocationDetail { location, path }))
+    }
+
+    async fn create(&self, request: LocationRequest) -> anyhow::Result<Location> {
+        let mut conn = self.session.get_session().await?;
+
+        let mut tx = conn
+            .begin()
+            .await
+            .context("Failed to begin a transaction")
+            .map_err(AppError::UnexpectedError)?;
+
+        check_parent(&mut tx, &request).await?;
+
+        let sql = Query::insert()
+            .into_table(Locations::Table)
+            .columns([
+                Locations::Id,
+                Locations::ParentId,
+                Locations::Kind,
+                Locations::Name,
+            ])
+            .values_panic([
+                uuid::Uuid::new_v4().into(),
+                request.parent_id.into(),
+                request.kind.as_str().into(),
+                request.name.clone().into(),
+            ])
+            .returning(Query::returning().columns(LOCATION_COLUMNS))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Location>(&sql)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| {
+                write_error(e, &request, "Failed to perform a sql to create a location")
+            })?;
+
+        tx.commit()
+            .await
+            .context("Failed to commit the new location")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn update(
+        &self,
+        id: uuid::Uuid,
+        request: LocationRequest,
+    ) -> anyhow::Result<Option<Location>> {
+        let mut conn = self.session.get_session().await?;
+
+        let mut tx = conn
+            .begin()
+            .await
+            .context("Failed to begin a transaction")
+            .map_err(AppError::UnexpectedError)?;
+
+        // Lock the location so no sub-location is added before its kind
+        // changes. The levels strictly increase down the tree, so checking the
+        // parent and the sub-locations also keeps a location from being moved
+        // under itself.
+        let sql = Query::select()
+            .column(Locations::Id)
+            .from(Locations::Table)
+            .and_where(Expr::col(Locations::Id).eq(id))
+            .lock(LockType::Update)
+            .to_string(PostgresQueryBuilder);
+
+        let exists = sqlx::query(&sql)
+            .fetch_optional(&mut *tx)
+            .await
+            .context("Failed to perform a sql to lock a location")
+            .map_err(AppError::UnexpectedError)?
+            .is_some();
+        if !exists {
+            return Ok(None);
+        }
+
+        check_parent(&mut tx, &request).await?;
+
+        let sql = Query::select()
+            .columns(LOCATION_COLUMNS)
+            .from(Locations::Table)
+            .and_where(Expr::col(Locations::ParentId).eq(id))
+            .to_string(PostgresQueryBuilder);
+
+        let children = sqlx::query_as::<_, Location>(&sql)
+            .fetch_all(&mut *tx)
+            .await
+            .context("Failed to perform a sql to get the sub-locations of a location")
+            .map_err(AppError::UnexpectedError)?;
+        if let Some(child) = children
+            .iter()
+            .find(|child| !child.kind.can_be_under(Some(request.kind)))
+        {
+            return Err(AppError::InvalidRequest(format!(
+                "the {} `{}` can't be in a {}",
+                child.kind, child.name, request.kind
+            )))?;
+        }
+
+        let sql = Query::update()
+            .table(Locations::Table)
+            .values([
+                (Locations::ParentId, request.parent_id.into()),
+                (Locations::Kind, request.kind.as_str().into()),
+                (Locations::Name, request.name.clone().into()),
+            ])
+            .and_where(Expr::col(Locations::Id).eq(id))
+            .returning(Query::returning().columns(LOCATION_COLUMNS))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Location>(&sql)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| {
+                write_error(e, &request, "Failed to perform a sql to update a location")
+            })?;
+
+        tx.commit()
+            .await
+            .context("Failed to commit the location update")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(Some(res))
+    }
+
+    async fn delete(&self, id: uuid::Uuid) -> anyhow::Result<bool> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::delete()
+            .from_table(Locations::Table)
+            .and_where(Expr::col(Locations::Id).eq(id))
+            .to_string(PostgresQueryBuilder);
+
+        let res = match sqlx::query(&sql).execute(&mut *conn).await {
+            Ok(res) => res,
+            Err(sqlx::Error::Database(e))
+                if e.code().as_deref() == Some(FOREIGN_KEY_VIOLATION)
+                    && e.constraint() == Some("locations_parent_id_fkey") =>
+            {
+                return Err(AppError::Conflict(
+                    "the location still has sub-locations".to_owned(),
+                ))?
+            }
+            Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some(FOREIGN_KEY_VIOLATION) => {
+                return Err(AppError::Conflict(
+                    "the location still holds devices".to_owned(),
+                ))?
+            }
+            Err(e) => Err(e)
+                .context("Failed to perform a sql to delete a location")
+                .map_err(AppError::UnexpectedError)?,
+        };
+
+        Ok(res.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,194 @@
+use anyhow::Context;
+use sea_query::{Expr, Func, Order, PostgresQueryBuilder, Query};
+use sqlx::Row;
+
+use crate::{
+    errors::AppError,
+    models::{
+        device_table::Devices,
+        owner::{Owner, OwnerRequest},
+        owner_table::Owners,
+    },
+    utils::PostgresSession,
+};
+
+use super::i_owner_repository::IOwnerRepository;
+use super::{FOREIGN_KEY_VIOLATION, UNIQUE_VIOLATION};
+
+pub struct PostgresOwnerRepository {
+    session: PostgresSession,
+}
+
+impl PostgresOwnerRepository {
+    pub fn new(session: PostgresSession) -> Self {
+        Self { session }
+    }
+}
+
+const OWNER_COLUMNS: [Owners; 5] = [
+    Owners::Id,
+    Owners::Name,
+    Owners::Description,
+    Owners::UserId,
+    Owners::TeamId,
+];
+
+/// Turn a second owner of a user or a team into a conflict and an unknown user
+/// or team into an invalid request, any other error is unexpected
+fn write_error(e: sqlx::Error, request: &OwnerRequest, context: &'static str) -> anyhow::Error {
+    let id = |id: Option<uuid::Uuid>| id.unwrap_or_default();
+
+    if let sqlx::Error::Database(db) = &e {
+        match (db.code().as_deref(), db.constraint()) {
+            (Some(UNIQUE_VIOLATION), Some("owners_user_id_key")) => {
+                return AppError::Conflict(format!(
+                    "the user `{}` already has an owner",
+                    id(request.user_id)
+                ))
+                .into()
+            }
+            (Some(UNIQUE_VIOLATION), Some("owners_team_id_key")) => {
+                return AppError::Conflict(format!(
+                    "the team `{}` already has an owner",
+                    id(request.team_id)
+                ))
+                .into()
+            }
+            (Some(FOREIGN_KEY_VIOLATION), Some("owners_user_id_fkey")) => {
+                return AppError::InvalidRequest(format!("unknown user `{}`", id(request.user_id)))
+                    .into()
+            }
+            (Some(FOREIGN_KEY_VIOLATION), Some("owners_team_id_fkey")) => {
+                return AppError::InvalidRequest(format!("unknown team `{}`", id(request.team_id)))
+                    .into()
+            }
+            _ => {}
+        }
+    }
+
+    AppError::UnexpectedError(anyhow::Error::new(e).context(context)).into()
+}
+
+#[async_trait::async_trait]
+impl IOwnerRepository for PostgresOwnerRepository {
+    async fn list(&self) -> anyhow::Result<Vec<Owner>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(OWNER_COLUMNS)
+            .from(Owners::Table)
+            .order_by(Owners::Name, Order::Asc)
+            .order_by(Owners::Id, Order::Asc)
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Owner>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to list owners")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn get(&self, id: uuid::Uuid) -> anyhow::Result<Option<Owner>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(OWNER_COLUMNS)
+            .from(Owners::Table)
+            .and_where(Expr::col(Owners::Id).eq(id))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Owner>(&sql)
+            .fetch_optional(&mut *conn)
+            .await
+            .context("Failed to perform a sql to get an owner")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn create(&self, request: OwnerRequest) -> anyhow::Result<Owner> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::insert()
+            .into_table(Owners::Table)
+            .columns(OWNER_COLUMNS)
+            .values_panic([
+                uuid::Uuid::new_v4().into(),
+                request.name.clone().into(),
+                request.description.clone().into(),
+                request.user_id.into(),
+                request.team_id.into(),
+            ])
+            .returning(Query::returning().columns(OWNER_COLUMNS))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Owner>(&sql)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| write_error(e, &request, "Failed to perform a sql to create an owner"))?;
+
+        Ok(res)
+    }
+
+    async fn update(&self, id: uuid::Uuid, request: OwnerRequest) -> anyhow::Result<Option<Owner>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::update()
+            .table(Owners::Table)
+            .values([
+                (Owners::Name, request.name.clone().into()),
+                (Owners::Description, request.description.clone().into()),
+                (Owners::UserId, request.user_id.into()),
+                (Owners::TeamId, request.team_id.into()),
+            ])
+            .and_where(Expr::col(Owners::Id).eq(id))
+            .returning(Query::returning().columns(OWNER_COLUMNS))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Owner>(&sql)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| write_error(e, &request, "Failed to perform a sql to update an owner"))?;
+
+        Ok(res)
+    }
+
+    async fn delete(&self, id: uuid::Uuid) -> anyhow::Result<bool> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::delete()
+            .from_table(Owners::Table)
+            .and_where(Expr::col(Owners::Id).eq(id))
+            .to_string(PostgresQueryBuilder);
+
+        let res = match sqlx::query(&sql).execute(&mut *conn).await {
+            Ok(res) => res,
+            Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some(FOREIGN_KEY_VIOLATION) => {
+                let sql = Query::select()
+                    .expr(Func::count(Expr::col(Devices::Id)))
+                    .from(Devices::Table)
+                    .and_where(Expr::col(Devices::OwnerId).eq(id))
+                    .to_string(PostgresQueryBuilder);
+
+                let owned = sqlx::query(&sql)
+                    .fetch_one(&mut *conn)
+                    .await
+                    .context("Failed to perform a sql to count the devices of an owner")
+                    .map_err(AppError::UnexpectedError)?
+                    .get::<i64, usize>(0);
+
+                return Err(AppError::Conflict(format!(
+                    "the owner still owns {owned} device(s), \
+                     move them to another owner before deleting it"
+                )))?;
+            }
+            Err(e) => Err(e)
+                .context("Failed to perform a sql to delete an owner")
+                .map_err(AppError::UnexpectedError)?,
+        };
+
+        Ok(res.rows_affected() > 0)
+    }
+}
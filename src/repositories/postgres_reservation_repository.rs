@@ -0,0 +1,145 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use sea_query::{Cond, Expr, Order, PostgresQueryBuilder, Query};
+
+use crate::{
+    errors::AppError,
+    models::{reservation::Reservation, reservation_table::DeviceReservations},
+    utils::PostgresSession,
+};
+
+use super::i_reservation_repository::IReservationRepository;
+use super::EXCLUSION_VIOLATION;
+
+pub struct PostgresReservationRepository {
+    session: PostgresSession,
+}
+
+impl PostgresReservationRepository {
+    pub fn new(session: PostgresSession) -> Self {
+        Self { session }
+    }
+}
+
+const RESERVATION_COLUMNS: [DeviceReservations; 7] = [
+    DeviceReservations::Id,
+    DeviceReservations::DeviceId,
+    DeviceReservations::ReservedBy,
+    DeviceReservations::StartsAt,
+    DeviceReservations::EndsAt,
+    DeviceReservations::Purpose,
+    DeviceReservations::CreatedAt,
+];
+
+#[async_trait::async_trait]
+impl IReservationRepository for PostgresReservationRepository {
+    async fn create(
+        &self,
+        device_id: uuid::Uuid,
+        reserved_by: uuid::Uuid,
+        starts_at: DateTime<Utc>,
+        ends_at: DateTime<Utc>,
+        purpose: Option<String>,
+    ) -> anyhow::Result<Option<Reservation>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::insert()
+            .into_table(DeviceReservations::Table)
+            .columns(RESERVATION_COLUMNS)
+            .values_panic([
+                uuid::Uuid::new_v4().into(),
+                device_id.into(),
+                reserved_by.into(),
+                starts_at.into(),
+                ends_at.into(),
+                purpose.into(),
+                Utc::now().into(),
+            ])
+            .returning(Query::returning().columns(RESERVATION_COLUMNS))
+            .to_string(PostgresQueryBuilder);
+
+        // The exclusion constraint rejects overlapping windows of the same device
+        match sqlx::query_as::<_, Reservation>(&sql)
+            .fetch_one(&mut *conn)
+            .await
+        {
+            Ok(reservation) => Ok(Some(reservation)),
+            Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some(EXCLUSION_VIOLATION) => {
+                Ok(None)
+            }
+            Err(e) => Err(e)
+                .context("Failed to perform a sql to create a reservation")
+                .map_err(AppError::UnexpectedError)?,
+        }
+    }
+
+    async fn schedule(
+        &self,
+        device_id: uuid::Uuid,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<Vec<Reservation>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(RESERVATION_COLUMNS)
+            .from(DeviceReservations::Table)
+            .cond_where(
+                Cond::all()
+                    .add(Expr::col(DeviceReservations::DeviceId).eq(device_id))
+                    .add_option(from.map(|from| Expr::col(DeviceReservations::EndsAt).gt(from)))
+                    .add_option(to.map(|to| Expr::col(DeviceReservations::StartsAt).lt(to))),
+            )
+            .order_by(DeviceReservations::StartsAt, Order::Asc)
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Reservation>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to get the schedule of a device")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn get(
+        &self,
+        device_id: uuid::Uuid,
+        id: uuid::Uuid,
+    ) -> anyhow::Result<Option<Reservation>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(RESERVATION_COLUMNS)
+            .from(DeviceReservations::Table)
+            .and_where(Expr::col(DeviceReservations::Id).eq(id))
+            .and_where(Expr::col(DeviceReservations::DeviceId).eq(device_id))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Reservation>(&sql)
+            .fetch_optional(&mut *conn)
+            .await
+            .context("Failed to perform a sql to get a reservation")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn delete(&self, device_id: uuid::Uuid, id: uuid::Uuid) -> anyhow::Result<bool> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::delete()
+            .from_table(DeviceReservations::Table)
+            .and_where(Expr::col(DeviceReservations::Id).eq(id))
+            .and_where(Expr::col(DeviceReservations::DeviceId).eq(device_id))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query(&sql)
+            .execute(&mut *conn)
+            .await
+            .context("Failed to perform a sql to delete a reservation")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res.rows_affected() > 0)
+    }
+}
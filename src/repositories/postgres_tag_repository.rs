@@ -0,0 +1,244 @@
+use anyhow::Context;
+use sea_query::{Alias, Expr, Func, OnConflict, Order, PostgresQueryBuilder, Query};
+use sqlx::{Connection, Row};
+
+use crate::{
+    errors::AppError,
+    models::{
+        device_table::Devices,
+        tag::{Tag, TagUsage},
+        tag_table::{DeviceTags, Tags},
+    },
+    utils::PostgresSession,
+};
+
+use super::i_tag_repository::ITagRepository;
+
+pub struct PostgresTagRepository {
+    session: PostgresSession,
+}
+
+impl PostgresTagRepository {
+    pub fn new(session: PostgresSession) -> Self {
+        Self { session }
+    }
+}
+
+const TAG_COLUMNS: [Tags; 3] = [Tags::Id, Tags::Name, Tags::CreatedAt];
+
+#[async_trait::async_trait]
+impl ITagRepository for PostgresTagRepository {
+    async fn list(&self) -> anyhow::Result<Vec<TagUsage>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(TAG_COLUMNS.map(|column| (Tags::Table, column)))
+            .expr_as(
+                Func::count(Expr::col((DeviceTags::Table, DeviceTags::DeviceId))),
+                Alias::new("device_count"),
+            )
+            .from(Tags::Table)
+            .left_join(
+                DeviceTags::Table,
+                Expr::col((DeviceTags::Table, DeviceTags::TagId)).equals((Tags::Table, Tags::Id)),
+            )
+            .group_by_col((Tags::Table, Tags::Id))
+            .order_by((Tags::Table, Tags::Name), Order::Asc)
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, TagUsage>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to list tags")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn delete(&self, id: uuid::Uuid) -> anyhow::Result<bool> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::delete()
+            .from_table(Tags::Table)
+            .and_where(Expr::col(Tags::Id).eq(id))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query(&sql)
+            .execute(&mut *conn)
+            .await
+            .context("Failed to perform a sql to delete a tag")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res.rows_affected() > 0)
+    }
+
+    async fn device_tags(&self, device_id: uuid::Uuid) -> anyhow::Result<Vec<Tag>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(TAG_COLUMNS.map(|column| (Tags::Table, column)))
+            .from(Tags::Table)
+            .inner_join(
+                DeviceTags::Table,
+                Expr::col((DeviceTags::Table, DeviceTags::TagId)).equals((Tags::Table, Tags::Id)),
+            )
+            .and_where(Expr::col((DeviceTags::Table, DeviceTags::DeviceId)).eq(device_id))
+            .order_by((Tags::Table, Tags::Name), Order::Asc)
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Tag>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to list the tags of a device")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn retag(
+        &self,
+        device_ids: &[uuid::Uuid],
+        add: &[String],
+        remove: &[String],
+    ) -> anyhow::Result<()> {
+        let mut conn = self.session.get_session().await?;
+
+        let mut tx = conn
+            .begin()
+            .await
+            .context("Failed to begin a transaction")
+            .map_err(AppError::UnexpectedError)?;
+
+        // Lock the devices so none is deleted before it is tagged
+        let sql = Query::select()
+            .column(Devices::Id)
+            .from(Devices::Table)
+            .and_where(Expr::col(Devices::Id).is_in(device_ids.iter().copied()))
+            .and_where(Expr::col(Devices::DeletedAt).is_null())
+            .order_by(Devices::Id, Order::Asc)
+            .lock_shared()
+            .to_string(PostgresQueryBuilder);
+
+        let existing: Vec<uuid::Uuid> = sqlx::query(&sql)
+            .fetch_all(&mut *tx)
+            .await
+            .context("Failed to perform a sql to lock devices")
+            .map_err(AppError::UnexpectedError)?
+            .iter()
+            .map(|row| row.get(0))
+            .collect();
+        if let Some(unknown) = device_ids.iter().find(|id| !existing.contains(id)) {
+            return Err(AppError::InvalidRequest(format!(
+                "unknown device {unknown}"
+            )))?;
+        }
+
+        if !add.is_empty() {
+            let sql = add
+                .iter()
+                .fold(
+                    Query::insert()
+                        .into_table(Tags::Table)
+                        .columns([Tags::Id, Tags::Name])
+                        .on_conflict(OnConflict::column(Tags::Name).do_nothing().to_owned())
+                        .to_owned(),
+                    |mut insert, name| {
+                        insert.values_panic([uuid::Uuid::new_v4().into(), name.into()]);
+                        insert
+                    },
+                )
+                .to_string(PostgresQueryBuilder);
+
+            sqlx::query(&sql)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to perform a sql to create tags")
+                .map_err(AppError::UnexpectedError)?;
+
+            let sql = Query::insert()
+                .into_table(DeviceTags::Table)
+                .columns([DeviceTags::DeviceId, DeviceTags::TagId])
+                .select_from(
+                    Query::select()
+                        .column((Devices::Table, Devices::Id))
+                        .column((Tags::Table, Tags::Id))
+                        .from(Devices::Table)
+                        .from(Tags::Table)
+                        .and_where(
+                            Expr::col((Devices::Table, Devices::Id))
+                                .is_in(device_ids.iter().copied()),
+                        )
+                        .and_where(Expr::col((Tags::Table, Tags::Name)).is_in(add.iter().cloned()))
+                        .to_owned(),
+                )
+                .context("Failed to build a sql to tag devices")?
+                .on_conflict(
+                    OnConflict::columns([DeviceTags::DeviceId, DeviceTags::TagId])
+                        .do_nothing()
+                        .to_owned(),
+                )
+                .to_string(PostgresQueryBuilder);
+
+            sqlx::query(&sql)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to perform a sql to tag devices")
+                .map_err(AppError::UnexpectedError)?;
+        }
+
+        if !remove.is_empty() {
+            let sql = Query::delete()
+                .from_table(DeviceTags::Table)
+                .and_where(Expr::col(DeviceTags::DeviceId).is_in(device_ids.iter().copied()))
+                .and_where(
+                    Expr::col(DeviceTags::TagId).in_subquery(
+                        Query::select()
+                            .column(Tags::Id)
+                            .from(Tags::Table)
+                            .and_where(Expr::col(Tags::Name).is_in(remove.iter().cloned()))
+                            .to_owned(),
+                    ),
+                )
+                .to_string(PostgresQueryBuilder);
+
+            sqlx::query(&sql)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to perform a sql to untag devices")
+                .map_err(AppError::UnexpectedError)?;
+        }
+
+        tx.commit()
+            .await
+            .context("Failed to commit the tags")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(())
+    }
+
+    async fn untag(&self, device_id: uuid::Uuid, name: &str) -> anyhow::Result<bool> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::delete()
+            .from_table(DeviceTags::Table)
+            .and_where(Expr::col(DeviceTags::DeviceId).eq(device_id))
+            .and_where(
+                Expr::col(DeviceTags::TagId).in_subquery(
+                    Query::select()
+                        .column(Tags::Id)
+                        .from(Tags::Table)
+                        .and_where(Expr::col(Tags::Name).eq(name))
+                        .to_owned(),
+                ),
+            )
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query(&sql)
+            .execute(&mut *conn)
+            .await
+            .context("Failed to perform a sql to untag a device")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res.rows_affected() > 0)
+    }
+}
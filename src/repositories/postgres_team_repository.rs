@@ -0,0 +1,349 @@
+use anyhow::Context;
+use sea_query::{Alias, Expr, OnConflict, Order, PostgresQueryBuilder, Query, SimpleExpr};
+use sqlx::{Connection, Row};
+
+use crate::{
+    errors::AppError,
+    models::{
+        team::{Membership, Team, TeamMember, TeamMemberRequest, TeamRequest, TeamRole},
+        team_table::{TeamMembers, Teams},
+        user_table::Users,
+    },
+    utils::PostgresSession,
+};
+
+use super::i_team_repository::ITeamRepository;
+use super::{FOREIGN_KEY_VIOLATION, UNIQUE_VIOLATION};
+
+pub struct PostgresTeamRepository {
+    session: PostgresSession,
+}
+
+impl PostgresTeamRepository {
+    pub fn new(session: PostgresSession) -> Self {
+        Self { session }
+    }
+}
+
+const TEAM_COLUMNS: [Teams; 5] = [
+    Teams::Id,
+    Teams::Name,
+    Teams::Description,
+    Teams::Permissions,
+    Teams::CreatedAt,
+];
+
+/// An empty `ARRAY []` has no type, so the permissions are always cast
+fn permissions_value(permissions: &[String]) -> SimpleExpr {
+    Expr::val(permissions.to_vec()).cast_as(Alias::new("varchar(128)[]"))
+}
+
+/// Turn a duplicated name into a conflict, any other error is unexpected
+fn write_error(e: sqlx::Error, name: &str, context: &'static str) -> anyhow::Error {
+    match &e {
+        sqlx::Error::Database(db)
+            if db.code().as_deref() == Some(UNIQUE_VIOLATION)
+                && db.constraint() == Some("teams_name_key") =>
+        {
+            AppError::Conflict(format!("a team named `{name}` already exists")).into()
+        }
+        _ => AppError::UnexpectedError(anyhow::Error::new(e).context(context)).into(),
+    }
+}
+
+/// Select the members of a team joined with their usernames
+fn select_members(team_id: uuid::Uuid) -> sea_query::SelectStatement {
+    Query::select()
+        .columns([
+            (TeamMembers::Table, TeamMembers::UserId),
+            (TeamMembers::Table, TeamMembers::Role),
+            (TeamMembers::Table, TeamMembers::CreatedAt),
+        ])
+        .column((Users::Table, Users::Username))
+        .from(TeamMembers::Table)
+        .inner_join(
+            Users::Table,
+            Expr::col((Users::Table, Users::Id)).equals((TeamMembers::Table, TeamMembers::UserId)),
+        )
+        .and_where(Expr::col((TeamMembers::Table, TeamMembers::TeamId)).eq(team_id))
+        .to_owned()
+}
+
+#[async_trait::async_trait]
+impl ITeamRepository for PostgresTeamRepository {
+    async fn list(&self) -> anyhow::Result<Vec<Team>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(TEAM_COLUMNS)
+            .from(Teams::Table)
+            .order_by(Teams::Name, Order::Asc)
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Team>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to list teams")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn get(&self, id: uuid::Uuid) -> anyhow::Result<Option<Team>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns(TEAM_COLUMNS)
+            .from(Teams::Table)
+            .and_where(Expr::col(Teams::Id).eq(id))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Team>(&sql)
+            .fetch_optional(&mut *conn)
+            .await
+            .context("Failed to perform a sql to get a team")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+
+    async fn create(&self, request: TeamRequest) -> anyhow::Result<Team> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::insert()
+            .into_table(Teams::Table)
+            .columns([
+                Teams::Id,
+                Teams::Name,
+                Teams::Description,
+                Teams::Permissions,
+            ])
+            .values_panic([
+                uuid::Uuid::new_v4().into(),
+                request.name.clone().into(),
+                request.description.into(),
+                permissions_value(&request.permissions),
+            ])
+            .returning(Query::returning().columns(TEAM_COLUMNS))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Team>(&sql)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| {
+                write_error(e, &request.name, "Failed to perform a sql to create a team")
+            })?;
+
+        Ok(res)
+    }
+
+    async fn update(&self, id: uuid::Uuid, request: TeamRequest) -> anyhow::Result<Option<Team>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::update()
+            .table(Teams::Table)
+            .values([
+                (Teams::Name, request.name.clone().into()),
+                (Teams::Description, request.description.into()),
+                (Teams::Permissions, permissions_value(&request.permissions)),
+            ])
+            .and_where(Expr::col(Teams::Id).eq(id))
+            .returning(Query::returning().columns(TEAM_COLUMNS))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Team>(&sql)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| {
+                write_error(e, &request.name, "Failed to perform a sql to update a team")
+            })?;
+
+        Ok(res)
+    }
+
+    async fn delete(&self, id: uuid::Uuid) -> anyhow::Result<bool> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::delete()
+            .from_table(Teams::Table)
+            .and_where(Expr::col(Teams::Id).eq(id))
+            .to_string(PostgresQueryBuilder);
+
+        // The owner of the team goes with it, unless it still owns devices
+        let res = match sqlx::query(&sql).execute(&mut *conn).await {
+            Ok(res) => res,
+            Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some(FOREIGN_KEY_VIOLATION) => {
+                return Err(AppError::Conflict(
+                    "the team still owns devices, move them to another owner first".to_owned(),
+                ))?
+            }
+            Err(e) => Err(e)
+                .context("Failed to perform a sql to delete a team")
+                .map_err(AppError::UnexpectedError)?,
+        };
+
+        Ok(res.rows_affected() > 0)
+    }
+
+    async fn members(&self, team_id: uuid::Uuid) -> anyhow::Result<Option<Vec<TeamMember>>> {
+        if self.get(team_id).await?.is_none() {
+            return Ok(None);
+        }
+
+        let mut conn = self.session.get_session().await?;
+
+        let sql = select_members(team_id)
+            .order_by((Users::Table, Users::Username), Order::Asc)
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, TeamMember>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to list the members of a team")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(Some(res))
+    }
+
+    async fn role(
+        &self,
+        team_id: uuid::Uuid,
+        user_id: uuid::Uuid,
+    ) -> anyhow::Result<Option<TeamRole>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .column(TeamMembers::Role)
+            .from(TeamMembers::Table)
+            .and_where(Expr::col(TeamMembers::TeamId).eq(team_id))
+            .and_where(Expr::col(TeamMembers::UserId).eq(user_id))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query(&sql)
+            .fetch_optional(&mut *conn)
+            .await
+            .context("Failed to perform a sql to get the role of a team member")
+            .map_err(AppError::UnexpectedError)?
+            .map(|row| row.get::<TeamRole, usize>(0));
+
+        Ok(res)
+    }
+
+    async fn add_member(
+        &self,
+        team_id: uuid::Uuid,
+        request: TeamMemberRequest,
+    ) -> anyhow::Result<Option<TeamMember>> {
+        let mut conn = self.session.get_session().await?;
+
+        let mut tx = conn
+            .begin()
+            .await
+            .context("Failed to begin a transaction")
+            .map_err(AppError::UnexpectedError)?;
+
+        let sql = Query::insert()
+            .into_table(TeamMembers::Table)
+            .columns([TeamMembers::TeamId, TeamMembers::UserId, TeamMembers::Role])
+            .values_panic([
+                team_id.into(),
+                request.user_id.into(),
+                request.role.as_str().into(),
+            ])
+            .on_conflict(
+                OnConflict::columns([TeamMembers::TeamId, TeamMembers::UserId])
+                    .update_column(TeamMembers::Role)
+                    .to_owned(),
+            )
+            .to_string(PostgresQueryBuilder);
+
+        match sqlx::query(&sql).execute(&mut *tx).await {
+            Ok(_) => {}
+            Err(sqlx::Error::Database(e))
+                if e.code().as_deref() == Some(FOREIGN_KEY_VIOLATION)
+                    && e.constraint() == Some("team_members_team_id_fkey") =>
+            {
+                return Ok(None)
+            }
+            Err(sqlx::Error::Database(e))
+                if e.code().as_deref() == Some(FOREIGN_KEY_VIOLATION)
+                    && e.constraint() == Some("team_members_user_id_fkey") =>
+            {
+                return Err(AppError::InvalidRequest(format!(
+                    "unknown user `{}`",
+                    request.user_id
+                )))?
+            }
+            Err(e) => Err(e)
+                .context("Failed to perform a sql to add a team member")
+                .map_err(AppError::UnexpectedError)?,
+        }
+
+        let sql = select_members(team_id)
+            .and_where(Expr::col((TeamMembers::Table, TeamMembers::UserId)).eq(request.user_id))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, TeamMember>(&sql)
+            .fetch_one(&mut *tx)
+            .await
+            .context("Failed to perform a sql to get a team member")
+            .map_err(AppError::UnexpectedError)?;
+
+        tx.commit()
+            .await
+            .context("Failed to commit the team member")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(Some(res))
+    }
+
+    async fn remove_member(
+        &self,
+        team_id: uuid::Uuid,
+        user_id: uuid::Uuid,
+    ) -> anyhow::Result<bool> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::delete()
+            .from_table(TeamMembers::Table)
+            .and_where(Expr::col(TeamMembers::TeamId).eq(team_id))
+            .and_where(Expr::col(TeamMembers::UserId).eq(user_id))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query(&sql)
+            .execute(&mut *conn)
+            .await
+            .context("Failed to perform a sql to remove a team member")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res.rows_affected() > 0)
+    }
+
+    async fn memberships(&self, user_id: uuid::Uuid) -> anyhow::Result<Vec<Membership>> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .columns([
+                (TeamMembers::Table, TeamMembers::TeamId),
+                (TeamMembers::Table, TeamMembers::Role),
+            ])
+            .column((Teams::Table, Teams::Permissions))
+            .from(TeamMembers::Table)
+            .inner_join(
+                Teams::Table,
+                Expr::col((Teams::Table, Teams::Id))
+                    .equals((TeamMembers::Table, TeamMembers::TeamId)),
+            )
+            .and_where(Expr::col((TeamMembers::Table, TeamMembers::UserId)).eq(user_id))
+            .order_by((TeamMembers::Table, TeamMembers::TeamId), Order::Asc)
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query_as::<_, Membership>(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .context("Failed to perform a sql to get the teams of a user")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res)
+    }
+}
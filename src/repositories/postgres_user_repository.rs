@@ -23,7 +23,7 @@ impl IUserRespository for PostgresUserRepository {
         &self,
         username: &str,
     ) -> anyhow::Result<Option<(uuid::Uuid, Secret<String>)>> {
-        let mut conn = self.session.get_session().await;
+        let mut conn = self.session.get_session().await?;
 
         let sql = Query::select()
             .columns([Users::Id, Users::PasswordHash])
@@ -35,7 +35,7 @@ impl IUserRespository for PostgresUserRepository {
             //.fetch_optional(conn.deref_mut())
             // In 0.7, `Transaction` can no longer implement `Executor` directly,
             // so it must be dereferences to the internal connection type
-            .fetch_optional(&mut **conn)
+            .fetch_optional(&mut *conn)
             .await
             .context("Failed to perform a sql to retrieve stored credentials")
             .map_err(AppError::UnexpectedError)?
@@ -47,4 +47,22 @@ impl IUserRespository for PostgresUserRepository {
 
         Ok(res)
     }
+
+    async fn exists(&self, id: uuid::Uuid) -> anyhow::Result<bool> {
+        let mut conn = self.session.get_session().await?;
+
+        let sql = Query::select()
+            .column(Users::Id)
+            .from(Users::Table)
+            .and_where(Expr::col(Users::Id).eq(id))
+            .to_string(PostgresQueryBuilder);
+
+        let res = sqlx::query(&sql)
+            .fetch_optional(&mut *conn)
+            .await
+            .context("Failed to perform a sql to check the user exists")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(res.is_some())
+    }
 }
@@ -0,0 +1,326 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context};
+use axum::extract::{Multipart, Path, Query};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+
+use crate::configuration::AttachmentSettings;
+use crate::errors::AppError;
+use crate::models::attachment::{
+    Attachment, AttachmentListResponse, NewAttachment, ThumbnailQuery, ThumbnailSize,
+};
+use crate::models::login::AuthenticatedUser;
+use crate::repositories::i_attachment_repository::IAttachmentRepository;
+use crate::repositories::i_device_repository::IDeviceRepository;
+use crate::storage::IBlobStorage;
+use crate::telemetry::spawn_blocking_with_tracing;
+use crate::thumbnails;
+
+/// The limit of the `file_name` column of the `device_attachments` table
+const MAX_FILE_NAME_LENGTH: usize = 255;
+
+/// The thumbnails of an attachment never change, only the authenticated user
+/// may keep them
+const THUMBNAIL_CACHE_CONTROL: &str = "private, max-age=31536000, immutable";
+
+/// The media type of a file part without a `Content-Type`
+const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+
+/// Keep the last component of the name a browser sent, without the characters
+/// which can't be echoed back in a `Content-Disposition` header
+fn normalize_file_name(file_name: Option<&str>) -> String {
+    let name: String = file_name
+        .unwrap_or_default()
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"')
+        .take(MAX_FILE_NAME_LENGTH)
+        .collect();
+
+    match name.trim() {
+        "" => "attachment".to_owned(),
+        name => name.to_owned(),
+    }
+}
+
+/// An ASCII `filename` for the old clients and the exact name as `filename*`
+fn content_disposition(file_name: &str) -> String {
+    let fallback: String = file_name
+        .chars()
+        .map(|c| if c.is_ascii() && c != '\\' { c } else { '_' })
+        .collect();
+    let encoded: String = file_name
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect();
+
+    format!("attachment; filename=\"{fallback}\"; filename*=UTF-8''{encoded}")
+}
+
+/// Remove the stored content of deleted attachments. The records are already
+/// gone, so a failure only leaves an orphaned blob behind and is logged.
+pub(crate) async fn remove_contents(
+    storage: &Arc<dyn IBlobStorage + Send + Sync>,
+    attachments: &[Attachment],
+) {
+    let keys: Vec<_> = attachments
+        .iter()
+        .flat_map(Attachment::storage_keys)
+        .collect();
+    remove_blobs(storage, &keys).await;
+}
+
+async fn remove_blobs(storage: &Arc<dyn IBlobStorage + Send + Sync>, keys: &[String]) {
+    for key in keys {
+        if let Err(e) = storage.delete(key).await {
+            tracing::warn!(error = ?e, key, "Failed to remove an orphaned blob");
+        }
+    }
+}
+
+/// Render and store the thumbnails of a photo, a photo which can't be decoded
+/// is kept without thumbnails. Return the stored keys.
+async fn store_thumbnails(
+    storage: &Arc<dyn IBlobStorage + Send + Sync>,
+    storage_key: &str,
+    content: Vec<u8>,
+) -> Result<Vec<String>, AppError> {
+    let thumbnails =
+        spawn_blocking_with_tracing(move || thumbnails::render(&content, &ThumbnailSize::ALL))
+            .await
+            .context("Failed to render thumbnails")?;
+    let thumbnails = match thumbnails {
+        Ok(thumbnails) => thumbnails,
+        Err(e) => {
+            tracing::warn!(error = ?e, key = storage_key, "Failed to render thumbnails");
+            return Ok(vec![]);
+        }
+    };
+
+    let mut keys = vec![];
+    for (size, thumbnail) in thumbnails {
+        let key = size.storage_key(storage_key);
+        if let Err(e) = storage.put(&key, thumbnail, thumbnails::CONTENT_TYPE).await {
+            remove_blobs(storage, &keys).await;
+            return Err(e.into());
+        }
+        keys.push(key);
+    }
+
+    Ok(keys)
+}
+
+/// The API entrypoint for listing the attachments of a device
+pub async fn get_device_attachments(
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Extension(attachment_repository): Extension<Arc<dyn IAttachmentRepository + Send + Sync>>,
+    Path((_version, device_id)): Path<(String, uuid::Uuid)>,
+) -> Result<Response, AppError> {
+    device_repository
+        .get(device_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let items = attachment_repository.list(device_id).await?;
+
+    Ok(Json(AttachmentListResponse { items }).into_response())
+}
+
+/// The API entrypoint for attaching the `file` field of a multipart form to a
+/// device, the size and the media type are limited by `AttachmentSettings`
+pub async fn upload_device_attachment(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Extension(attachment_repository): Extension<Arc<dyn IAttachmentRepository + Send + Sync>>,
+    Extension(storage): Extension<Arc<dyn IBlobStorage + Send + Sync>>,
+    Extension(settings): Extension<Arc<AttachmentSettings>>,
+    Path((_version, device_id)): Path<(String, uuid::Uuid)>,
+    WithRejection(mut multipart, _): WithRejection<Multipart, AppError>,
+) -> Result<Response, AppError> {
+    let uploaded_by = authenticated_user.id()?;
+
+    device_repository
+        .get(device_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let mut upload = None;
+    while let Some(mut field) = multipart.next_field().await? {
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let file_name = normalize_file_name(field.file_name());
+        let content_type = field
+            .content_type()
+            .unwrap_or(DEFAULT_CONTENT_TYPE)
+            .to_lowercase();
+        if !settings.allows(&content_type) {
+            return Err(AppError::UnsupportedMediaType(format!(
+                "`{content_type}` files can't be attached, use one of {}",
+                settings.allowed_content_types.join(", ")
+            )));
+        }
+
+        // Stop reading as soon as the file is too large
+        let mut content = vec![];
+        while let Some(chunk) = field.chunk().await? {
+            if content.len() + chunk.len() > settings.max_size_bytes {
+                return Err(AppError::PayloadTooLarge(format!(
+                    "an attachment must be at most {} bytes",
+                    settings.max_size_bytes
+                )));
+            }
+            content.extend_from_slice(&chunk);
+        }
+
+        upload = Some((file_name, content_type, content));
+        break;
+    }
+    let (file_name, content_type, content) = upload
+        .ok_or_else(|| AppError::InvalidRequest("the `file` field is required".to_owned()))?;
+
+    let id = uuid::Uuid::new_v4();
+    let storage_key = format!("devices/{device_id}/{id}");
+    let size_bytes = content.len() as i64;
+    let photo = thumbnails::is_photo(&content_type).then(|| content.clone());
+    storage.put(&storage_key, content, &content_type).await?;
+
+    let mut stored_keys = vec![storage_key.clone()];
+    let has_thumbnails = match photo {
+        None => false,
+        Some(photo) => match store_thumbnails(&storage, &storage_key, photo).await {
+            Ok(keys) => {
+                stored_keys.extend(keys);
+                stored_keys.len() > 1
+            }
+            Err(e) => {
+                remove_blobs(&storage, &stored_keys).await;
+                return Err(e);
+            }
+        },
+    };
+
+    let attachment = attachment_repository
+        .create(NewAttachment {
+            id,
+            device_id,
+            file_name,
+            content_type,
+            size_bytes,
+            storage_key,
+            uploaded_by,
+            has_thumbnails,
+        })
+        .await;
+    let attachment = match attachment {
+        Ok(attachment) => attachment,
+        Err(e) => {
+            remove_blobs(&storage, &stored_keys).await;
+            return Err(e.into());
+        }
+    };
+
+    Ok((StatusCode::CREATED, Json(attachment)).into_response())
+}
+
+/// The API entrypoint for downloading the content of an attachment
+pub async fn download_device_attachment(
+    Extension(attachment_repository): Extension<Arc<dyn IAttachmentRepository + Send + Sync>>,
+    Extension(storage): Extension<Arc<dyn IBlobStorage + Send + Sync>>,
+    Path((_version, device_id, id)): Path<(String, uuid::Uuid, uuid::Uuid)>,
+) -> Result<Response, AppError> {
+    let attachment = attachment_repository
+        .get(device_id, id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let content = storage
+        .get(&attachment.storage_key)
+        .await?
+        .ok_or_else(|| anyhow!("The content of the attachment {id} is missing"))?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, attachment.content_type.clone()),
+            (
+                header::CONTENT_DISPOSITION,
+                content_disposition(&attachment.file_name),
+            ),
+        ],
+        content,
+    )
+        .into_response())
+}
+
+/// The API entrypoint for the thumbnail of a photo. An attachment never
+/// changes, so the thumbnail is cached by the browser for good.
+pub async fn get_device_attachment_thumbnail(
+    Extension(attachment_repository): Extension<Arc<dyn IAttachmentRepository + Send + Sync>>,
+    Extension(storage): Extension<Arc<dyn IBlobStorage + Send + Sync>>,
+    Path((_version, device_id, id)): Path<(String, uuid::Uuid, uuid::Uuid)>,
+    WithRejection(Query(query), _): WithRejection<Query<ThumbnailQuery>, AppError>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let attachment = attachment_repository
+        .get(device_id, id)
+        .await?
+        .filter(|attachment| attachment.has_thumbnails)
+        .ok_or(AppError::NotFound)?;
+
+    let etag = format!("\"{id}-{}\"", query.size.as_str());
+    let cache_headers = [
+        (header::CACHE_CONTROL, THUMBNAIL_CACHE_CONTROL.to_owned()),
+        (header::ETAG, etag.clone()),
+    ];
+    let cached = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value
+                .split(',')
+                .map(|tag| tag.trim().trim_start_matches("W/"))
+                .any(|tag| tag == etag || tag == "*")
+        });
+    if cached {
+        return Ok((StatusCode::NOT_MODIFIED, cache_headers).into_response());
+    }
+
+    let thumbnail = storage
+        .get(&query.size.storage_key(&attachment.storage_key))
+        .await?
+        .ok_or_else(|| anyhow!("The thumbnail of the attachment {id} is missing"))?;
+
+    Ok((
+        cache_headers,
+        [(header::CONTENT_TYPE, thumbnails::CONTENT_TYPE)],
+        thumbnail,
+    )
+        .into_response())
+}
+
+/// The API entrypoint for deleting an attachment and its content
+pub async fn delete_device_attachment(
+    Extension(attachment_repository): Extension<Arc<dyn IAttachmentRepository + Send + Sync>>,
+    Extension(storage): Extension<Arc<dyn IBlobStorage + Send + Sync>>,
+    Path((_version, device_id, id)): Path<(String, uuid::Uuid, uuid::Uuid)>,
+) -> Result<StatusCode, AppError> {
+    let attachment = attachment_repository
+        .delete(device_id, id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    remove_contents(&storage, &[attachment]).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
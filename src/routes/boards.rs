@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+
+use crate::errors::AppError;
+use crate::models::board::{BoardListResponse, BoardRequest};
+use crate::models::login::AuthenticatedUser;
+use crate::repositories::i_board_repository::IBoardRepository;
+
+/// The limits of the `name`, `revision` and `vendor` columns of the `boards`
+/// table
+const MAX_NAME_LENGTH: usize = 96;
+const MAX_REVISION_LENGTH: usize = 24;
+const MAX_VENDOR_LENGTH: usize = 256;
+
+/// Trim the name and the revision, the full name of a board must not depend
+/// on stray whitespace
+fn normalize(mut request: BoardRequest) -> Result<BoardRequest, AppError> {
+    request.name = request.name.trim().to_owned();
+    request.revision = request.revision.trim().to_owned();
+
+    if request.name.is_empty() || request.name.chars().count() > MAX_NAME_LENGTH {
+        return Err(AppError::InvalidRequest(format!(
+            "name must be between 1 and {MAX_NAME_LENGTH} characters"
+        )));
+    }
+    if request.revision.chars().count() > MAX_REVISION_LENGTH {
+        return Err(AppError::InvalidRequest(format!(
+            "revision must be at most {MAX_REVISION_LENGTH} characters"
+        )));
+    }
+    if request
+        .vendor
+        .as_ref()
+        .is_some_and(|vendor| vendor.chars().count() > MAX_VENDOR_LENGTH)
+    {
+        return Err(AppError::InvalidRequest(format!(
+            "vendor must be at most {MAX_VENDOR_LENGTH} characters"
+        )));
+    }
+
+    Ok(request)
+}
+
+/// The API entrypoint for listing the boards
+pub async fn get_boards(
+    Extension(board_repository): Extension<Arc<dyn IBoardRepository + Send + Sync>>,
+) -> Result<Response, AppError> {
+    let items = board_repository.list().await?;
+
+    Ok(Json(BoardListResponse { items }).into_response())
+}
+
+/// The API entrypoint for getting a board by its id
+pub async fn get_board(
+    Extension(board_repository): Extension<Arc<dyn IBoardRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+) -> Result<Response, AppError> {
+    let board = board_repository.get(id).await?.ok_or(AppError::NotFound)?;
+
+    Ok(Json(board).into_response())
+}
+
+/// The API entrypoint for adding a board to the catalog
+pub async fn create_board(
+    Extension(board_repository): Extension<Arc<dyn IBoardRepository + Send + Sync>>,
+    WithRejection(Json(payload), _): WithRejection<Json<BoardRequest>, AppError>,
+) -> Result<Response, AppError> {
+    let board = board_repository.create(normalize(payload)?).await?;
+
+    Ok((StatusCode::CREATED, Json(board)).into_response())
+}
+
+/// The API entrypoint for replacing a board
+pub async fn update_board(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(board_repository): Extension<Arc<dyn IBoardRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+    WithRejection(Json(payload), _): WithRejection<Json<BoardRequest>, AppError>,
+) -> Result<Response, AppError> {
+    let board = board_repository
+        .update(id, normalize(payload)?, authenticated_user.id()?)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(board).into_response())
+}
+
+/// The API entrypoint for deleting an unused board
+pub async fn delete_board(
+    Extension(board_repository): Extension<Arc<dyn IBoardRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+) -> Result<StatusCode, AppError> {
+    if !board_repository.delete(id).await? {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,134 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+
+use crate::errors::{AppError, AuthError};
+use crate::mentions;
+use crate::models::comment::{
+    CommentListResponse, CommentRequest, ListMentionsQuery, MentionListResponse,
+};
+use crate::models::login::AuthenticatedUser;
+use crate::repositories::i_comment_repository::ICommentRepository;
+use crate::repositories::i_device_repository::IDeviceRepository;
+
+/// The permission to delete the comments of the other users
+const MODERATE_PERMISSION: &str = "moderate:comments";
+
+const MAX_BODY_LENGTH: usize = 10000;
+
+fn normalize(request: CommentRequest) -> Result<String, AppError> {
+    let body = request.body.trim().to_owned();
+    if body.is_empty() || body.chars().count() > MAX_BODY_LENGTH {
+        return Err(AppError::InvalidRequest(format!(
+            "body must be between 1 and {MAX_BODY_LENGTH} characters"
+        )));
+    }
+
+    Ok(body)
+}
+
+/// The API entrypoint for listing the comments of a device
+pub async fn get_device_comments(
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Extension(comment_repository): Extension<Arc<dyn ICommentRepository + Send + Sync>>,
+    Path((_version, device_id)): Path<(String, uuid::Uuid)>,
+) -> Result<Response, AppError> {
+    device_repository
+        .get(device_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let items = comment_repository.list(device_id).await?;
+
+    Ok(Json(CommentListResponse { items }).into_response())
+}
+
+/// The API entrypoint for commenting on a device, the `@username` mentions
+/// of existing users are recorded
+pub async fn create_device_comment(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Extension(comment_repository): Extension<Arc<dyn ICommentRepository + Send + Sync>>,
+    Path((_version, device_id)): Path<(String, uuid::Uuid)>,
+    WithRejection(Json(payload), _): WithRejection<Json<CommentRequest>, AppError>,
+) -> Result<Response, AppError> {
+    let body = normalize(payload)?;
+    device_repository
+        .get(device_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    let mentions = mentions::parse(&body);
+
+    let comment = comment_repository
+        .create(device_id, authenticated_user.id()?, body, &mentions)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(comment)).into_response())
+}
+
+/// The API entrypoint for editing a comment, only its author can edit it
+pub async fn update_device_comment(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(comment_repository): Extension<Arc<dyn ICommentRepository + Send + Sync>>,
+    Path((_version, device_id, id)): Path<(String, uuid::Uuid, uuid::Uuid)>,
+    WithRejection(Json(payload), _): WithRejection<Json<CommentRequest>, AppError>,
+) -> Result<Response, AppError> {
+    let body = normalize(payload)?;
+
+    let comment = comment_repository
+        .get(device_id, id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    if comment.author_id != Some(authenticated_user.id()?) {
+        return Err(AuthError::Forbidden)?;
+    }
+
+    let mentions = mentions::parse(&body);
+    let comment = comment_repository
+        .update(device_id, id, body, &mentions)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(comment).into_response())
+}
+
+/// The API entrypoint for deleting a comment, by its author or a moderator
+pub async fn delete_device_comment(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(comment_repository): Extension<Arc<dyn ICommentRepository + Send + Sync>>,
+    Path((_version, device_id, id)): Path<(String, uuid::Uuid, uuid::Uuid)>,
+) -> Result<StatusCode, AppError> {
+    let comment = comment_repository
+        .get(device_id, id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    if comment.author_id != Some(authenticated_user.id()?)
+        && !authenticated_user.has_permission(MODERATE_PERMISSION)
+    {
+        return Err(AuthError::Forbidden)?;
+    }
+
+    if !comment_repository.delete(device_id, id).await? {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The API entrypoint for the mentions of the authenticated user, the latest
+/// first, so a client can notify the user
+pub async fn get_mentions(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(comment_repository): Extension<Arc<dyn ICommentRepository + Send + Sync>>,
+    WithRejection(Query(query), _): WithRejection<Query<ListMentionsQuery>, AppError>,
+) -> Result<Response, AppError> {
+    let items = comment_repository
+        .mentions(authenticated_user.id()?, query.since)
+        .await?;
+
+    Ok(Json(MentionListResponse { items }).into_response())
+}
@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+
+use crate::errors::AppError;
+use crate::models::device_status::{DeviceStatus, DeviceStatusChangeListResponse};
+use crate::models::login::AuthenticatedUser;
+use crate::repositories::i_device_repository::IDeviceRepository;
+
+/// Move a device to the status `to` if the transition table allows it
+async fn transition(
+    authenticated_user: AuthenticatedUser,
+    device_repository: Arc<dyn IDeviceRepository + Send + Sync>,
+    id: uuid::Uuid,
+    to: DeviceStatus,
+) -> Result<Response, AppError> {
+    let changed_by = authenticated_user.id()?;
+
+    let device = device_repository.get(id).await?.ok_or(AppError::NotFound)?;
+
+    if !device.status.can_transition_to(to) {
+        return Err(AppError::InvalidTransition {
+            from: device.status,
+            to,
+        });
+    }
+
+    let device = device_repository
+        .transition(id, device.status, to, changed_by)
+        .await?
+        .ok_or_else(|| AppError::Conflict("device status was changed concurrently".to_owned()))?;
+
+    Ok(Json(device).into_response())
+}
+
+/// The API entrypoint for putting a device into the inventory
+pub async fn stock_device(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+) -> Result<Response, AppError> {
+    transition(
+        authenticated_user,
+        device_repository,
+        id,
+        DeviceStatus::InInventory,
+    )
+    .await
+}
+
+/// The API entrypoint for putting a device into use
+pub async fn use_device(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+) -> Result<Response, AppError> {
+    transition(
+        authenticated_user,
+        device_repository,
+        id,
+        DeviceStatus::InUse,
+    )
+    .await
+}
+
+/// The API entrypoint for sending a device to repair
+pub async fn repair_device(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+) -> Result<Response, AppError> {
+    transition(
+        authenticated_user,
+        device_repository,
+        id,
+        DeviceStatus::InRepair,
+    )
+    .await
+}
+
+/// The API entrypoint for reporting a device as lost
+pub async fn report_lost_device(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+) -> Result<Response, AppError> {
+    transition(
+        authenticated_user,
+        device_repository,
+        id,
+        DeviceStatus::Lost,
+    )
+    .await
+}
+
+/// The API entrypoint for retiring a device
+pub async fn retire_device(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+) -> Result<Response, AppError> {
+    transition(
+        authenticated_user,
+        device_repository,
+        id,
+        DeviceStatus::Retired,
+    )
+    .await
+}
+
+/// The API entrypoint for scrapping a device, it is the end of the lifecycle
+pub async fn scrap_device(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+) -> Result<Response, AppError> {
+    transition(
+        authenticated_user,
+        device_repository,
+        id,
+        DeviceStatus::Scrapped,
+    )
+    .await
+}
+
+/// The API entrypoint for the status changes of a device
+pub async fn get_device_status_history(
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+) -> Result<Response, AppError> {
+    device_repository.get(id).await?.ok_or(AppError::NotFound)?;
+
+    let items = device_repository.status_history(id).await?;
+
+    Ok(Json(DeviceStatusChangeListResponse { items }).into_response())
+}
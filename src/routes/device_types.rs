@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+
+use crate::attributes;
+use crate::errors::AppError;
+use crate::models::device_type::{DeviceTypeListResponse, DeviceTypeRequest};
+use crate::repositories::i_device_type_repository::IDeviceTypeRepository;
+
+/// The limit of the `name` column of the `device_types` table
+const MAX_NAME_LENGTH: usize = 128;
+
+fn normalize(mut request: DeviceTypeRequest) -> Result<DeviceTypeRequest, AppError> {
+    request.name = request.name.trim().to_owned();
+
+    if request.name.is_empty() || request.name.chars().count() > MAX_NAME_LENGTH {
+        return Err(AppError::InvalidRequest(format!(
+            "name must be between 1 and {MAX_NAME_LENGTH} characters"
+        )));
+    }
+    attributes::compile(&request.attribute_schema).map_err(AppError::InvalidRequest)?;
+
+    Ok(request)
+}
+
+/// The API entrypoint for listing the device types
+pub async fn get_device_types(
+    Extension(device_type_repository): Extension<Arc<dyn IDeviceTypeRepository + Send + Sync>>,
+) -> Result<Response, AppError> {
+    let items = device_type_repository.list().await?;
+
+    Ok(Json(DeviceTypeListResponse { items }).into_response())
+}
+
+/// The API entrypoint for getting a device type by its id
+pub async fn get_device_type(
+    Extension(device_type_repository): Extension<Arc<dyn IDeviceTypeRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+) -> Result<Response, AppError> {
+    let device_type = device_type_repository
+        .get(id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(device_type).into_response())
+}
+
+/// The API entrypoint for creating a device type
+pub async fn create_device_type(
+    Extension(device_type_repository): Extension<Arc<dyn IDeviceTypeRepository + Send + Sync>>,
+    WithRejection(Json(payload), _): WithRejection<Json<DeviceTypeRequest>, AppError>,
+) -> Result<Response, AppError> {
+    let device_type = device_type_repository.create(normalize(payload)?).await?;
+
+    Ok((StatusCode::CREATED, Json(device_type)).into_response())
+}
+
+/// The API entrypoint for replacing a device type
+pub async fn update_device_type(
+    Extension(device_type_repository): Extension<Arc<dyn IDeviceTypeRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+    WithRejection(Json(payload), _): WithRejection<Json<DeviceTypeRequest>, AppError>,
+) -> Result<Response, AppError> {
+    let device_type = device_type_repository
+        .update(id, normalize(payload)?)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(device_type).into_response())
+}
+
+/// The API entrypoint for deleting an unused device type
+pub async fn delete_device_type(
+    Extension(device_type_repository): Extension<Arc<dyn IDeviceTypeRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+) -> Result<StatusCode, AppError> {
+    if !device_type_repository.delete(id).await? {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,11 +1,484 @@
-use axum::http::StatusCode;
-use axum::Extension;
+use std::sync::Arc;
 
+use axum::body::{Bytes, StreamBody};
+use axum::extract::{Multipart, Path, Query};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+use chrono::{Duration, SubsecRound, Utc};
+use futures_util::stream::{self, StreamExt};
+
+use crate::configuration::TrashSettings;
+use crate::csv_export;
+use crate::csv_import::parse_devices;
 use crate::errors::AppError;
+use crate::models::device::{
+    CreateDeviceRequest, Device, DeviceListResponse, DeviceSearchResponse, IfMatch,
+    ListDevicesQuery, PurgeDevicesResponse, SearchDevicesQuery, TrashedDeviceListResponse,
+    UpdateDeviceRequest, MAX_FIELD_LENGTH, MAX_NAME_LENGTH,
+};
+use crate::models::device_export::{ExportDevicesQuery, ExportFormat};
+use crate::models::device_history::DeviceHistoryListResponse;
+use crate::models::device_import::{ImportDevicesQuery, ImportDevicesResponse, ImportRowError};
 use crate::models::login::AuthenticatedUser;
+use crate::repositories::i_attachment_repository::IAttachmentRepository;
+use crate::repositories::i_device_repository::IDeviceRepository;
+use crate::storage::IBlobStorage;
+
+use super::attachments::remove_contents;
+
+const DEFAULT_PAGE_SIZE: u64 = 50;
+const MAX_PAGE_SIZE: u64 = 500;
+
+/// Check the `limit` of a paginated listing of devices
+pub(super) fn page_limit(limit: Option<u64>) -> Result<u64, AppError> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE);
+    if limit == 0 || limit > MAX_PAGE_SIZE {
+        return Err(AppError::InvalidQuery(format!(
+            "limit must be between 1 and {MAX_PAGE_SIZE}"
+        )));
+    }
+
+    Ok(limit)
+}
+
+/// Load the device a page continues after
+pub(super) async fn cursor_device(
+    device_repository: &Arc<dyn IDeviceRepository + Send + Sync>,
+    cursor: Option<uuid::Uuid>,
+) -> Result<Option<Device>, AppError> {
+    match cursor {
+        Some(id) => {
+            Ok(Some(device_repository.get(id).await?.ok_or_else(|| {
+                AppError::InvalidQuery("unknown cursor".to_owned())
+            })?))
+        }
+        None => Ok(None),
+    }
+}
+
+/// A full page may be followed by another one, which starts after its last
+/// device
+pub(super) fn next_cursor(items: &[Device], limit: u64) -> Option<uuid::Uuid> {
+    if items.len() as u64 == limit {
+        items.last().map(|device| device.id)
+    } else {
+        None
+    }
+}
+
+/// Check the fields shared by the create and update payloads against the
+/// limits of the `devices` table, the name is trimmed
+fn normalize_fields(
+    name: &mut String,
+    fields: [(&str, &Option<String>); 4],
+) -> Result<(), AppError> {
+    *name = name.trim().to_owned();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+        return Err(AppError::InvalidRequest(format!(
+            "name must be between 1 and {MAX_NAME_LENGTH} characters"
+        )));
+    }
+
+    for (field, value) in fields {
+        if value
+            .as_ref()
+            .is_some_and(|value| value.chars().count() > MAX_FIELD_LENGTH)
+        {
+            return Err(AppError::InvalidRequest(format!(
+                "{field} must be at most {MAX_FIELD_LENGTH} characters"
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// A blank serial number or barcode is missing, as in the migration which
+/// made them unique, so two devices without one don't collide
+fn identifier(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.trim().is_empty())
+}
+
+fn normalize_create(mut request: CreateDeviceRequest) -> Result<CreateDeviceRequest, AppError> {
+    request.sn = identifier(request.sn);
+    request.barcode = identifier(request.barcode);
+    normalize_fields(
+        &mut request.name,
+        [
+            ("board", &request.board),
+            ("sn", &request.sn),
+            ("barcode", &request.barcode),
+            ("hwPhase", &request.hw_phase),
+        ],
+    )?;
+
+    Ok(request)
+}
+
+fn normalize_update(mut request: UpdateDeviceRequest) -> Result<UpdateDeviceRequest, AppError> {
+    request.sn = identifier(request.sn);
+    request.barcode = identifier(request.barcode);
+    normalize_fields(
+        &mut request.name,
+        [
+            ("board", &request.board),
+            ("sn", &request.sn),
+            ("barcode", &request.barcode),
+            ("hwPhase", &request.hw_phase),
+        ],
+    )?;
+
+    Ok(request)
+}
+
+/// Respond with a device and its version as the `ETag`
+fn device_response(device: Device) -> Response {
+    ([(header::ETAG, device.etag())], Json(device)).into_response()
+}
+
+/// A change to a device has to name the version it is based on, so it can't
+/// silently overwrite a change made in the meantime
+fn if_match(headers: &HeaderMap) -> Result<IfMatch, AppError> {
+    headers
+        .get(header::IF_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .map(IfMatch::parse)
+        .ok_or(AppError::PreconditionRequired)
+}
+
+/// The API entrypoint for listing devices with filters, sorting and pagination
+pub async fn get_devices(
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    WithRejection(Query(query), _): WithRejection<Query<ListDevicesQuery>, AppError>,
+) -> Result<Response, AppError> {
+    let limit = page_limit(query.limit)?;
+    let cursor = cursor_device(&device_repository, query.cursor).await?;
+
+    let (items, total) = device_repository.list(&query, limit, cursor).await?;
+
+    let resp = DeviceListResponse {
+        next_cursor: next_cursor(&items, limit),
+        items,
+        total,
+    };
+
+    Ok(Json(resp).into_response())
+}
+
+/// The API entrypoint for downloading every device matching the filters of
+/// `GET /devices` as csv or JSON Lines, the devices are streamed instead of
+/// being loaded at once
+pub async fn export_devices(
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    headers: HeaderMap,
+    WithRejection(Query(query), _): WithRejection<Query<ListDevicesQuery>, AppError>,
+    WithRejection(Query(export), _): WithRejection<Query<ExportDevicesQuery>, AppError>,
+) -> Result<Response, AppError> {
+    let format = export
+        .format
+        .or_else(|| {
+            headers
+                .get(header::ACCEPT)
+                .and_then(|accept| accept.to_str().ok())
+                .and_then(ExportFormat::from_accept)
+        })
+        .unwrap_or(ExportFormat::Csv);
+
+    let devices = device_repository.export(&query).await?;
+
+    let lines = devices.map(move |device| {
+        let device = device?;
+        let line = match format {
+            ExportFormat::Csv => csv_export::device_record(&device)?,
+            ExportFormat::Ndjson => {
+                let mut line = serde_json::to_vec(&device)?;
+                line.push(b'\n');
+                line
+            }
+        };
+
+        anyhow::Ok(Bytes::from(line))
+    });
+    let body = match format {
+        ExportFormat::Csv => stream::once(async { csv_export::header().map(Bytes::from) })
+            .chain(lines)
+            .boxed(),
+        ExportFormat::Ndjson => lines.boxed(),
+    };
+
+    let headers = [
+        (header::CONTENT_TYPE, format.content_type().to_owned()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"devices.{}\"",
+                format.file_extension()
+            ),
+        ),
+    ];
+
+    Ok((headers, StreamBody::new(body)).into_response())
+}
+
+/// The API entrypoint for searching devices by words and serial numbers
+pub async fn search_devices(
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    WithRejection(Query(query), _): WithRejection<Query<SearchDevicesQuery>, AppError>,
+) -> Result<Response, AppError> {
+    let text = query.q.trim();
+    if text.is_empty() {
+        return Err(AppError::InvalidQuery("q must not be empty".to_owned()));
+    }
+
+    let limit = page_limit(query.limit)?;
+
+    let items = device_repository.search(text, limit).await?;
 
-pub async fn get(
-    Extension(_authenticated_user): Extension<AuthenticatedUser>,
+    Ok(Json(DeviceSearchResponse { items }).into_response())
+}
+
+/// The API entrypoint for getting a device by id
+pub async fn get_device(
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+) -> Result<Response, AppError> {
+    let device = device_repository.get(id).await?.ok_or(AppError::NotFound)?;
+
+    Ok(device_response(device))
+}
+
+/// The API entrypoint for getting a device by its barcode, e.g. from a scanner
+pub async fn get_device_by_barcode(
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Path((_version, code)): Path<(String, String)>,
+) -> Result<Response, AppError> {
+    let device = device_repository
+        .get_by_barcode(&code)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(device_response(device))
+}
+
+/// The API entrypoint for getting a device by its serial number
+pub async fn get_device_by_sn(
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Path((_version, sn)): Path<(String, String)>,
+) -> Result<Response, AppError> {
+    let device = device_repository
+        .get_by_sn(&sn)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(device_response(device))
+}
+
+/// The API entrypoint for creating a device
+pub async fn create_device(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    WithRejection(Json(payload), _): WithRejection<Json<CreateDeviceRequest>, AppError>,
+) -> Result<Response, AppError> {
+    let device = device_repository
+        .create(normalize_create(payload)?, authenticated_user.id()?)
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        [(header::ETAG, device.etag())],
+        Json(device),
+    )
+        .into_response())
+}
+
+/// The API entrypoint for importing devices from the csv file in the `file`
+/// field of a multipart form.
+///
+/// Every row is parsed and then created in one transaction, which also checks
+/// it against the database (duplicated identifiers, unknown owners, boards,
+/// hardware phases or device types). Nothing is written in a dry run or when
+/// any row is invalid, every invalid row is reported either way.
+pub async fn import_devices(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    WithRejection(Query(query), _): WithRejection<Query<ImportDevicesQuery>, AppError>,
+    WithRejection(mut multipart, _): WithRejection<Multipart, AppError>,
+) -> Result<Response, AppError> {
+    let mut content = None;
+    while let Some(field) = multipart.next_field().await? {
+        if field.name() == Some("file") {
+            content = Some(field.bytes().await?);
+        }
+    }
+    let content =
+        content.ok_or_else(|| AppError::InvalidRequest("the file field is missing".to_owned()))?;
+
+    let parsed = parse_devices(&content).map_err(AppError::InvalidRequest)?;
+
+    let mut resp = ImportDevicesResponse {
+        dry_run: query.dry_run,
+        total_rows: parsed.total_rows,
+        imported: 0,
+        errors: parsed.errors,
+    };
+
+    let (rows, requests): (Vec<_>, Vec<_>) = parsed
+        .devices
+        .into_iter()
+        .map(|device| (device.row, device.request))
+        .unzip();
+    let results = device_repository
+        .create_many(
+            requests,
+            query.dry_run || !resp.errors.is_empty(),
+            authenticated_user.id()?,
+        )
+        .await?;
+    let created = results.iter().filter(|result| result.is_ok()).count();
+    for (row, result) in rows.into_iter().zip(results) {
+        if let Err(e) = result {
+            let message = match e {
+                AppError::InvalidRequest(message) | AppError::Conflict(message) => message,
+                e => e.to_string(),
+            };
+            resp.errors.push(ImportRowError {
+                row,
+                column: None,
+                message,
+            });
+        }
+    }
+    resp.errors.sort_by_key(|error| error.row);
+
+    if !resp.errors.is_empty() {
+        let status = if query.dry_run {
+            StatusCode::OK
+        } else {
+            StatusCode::UNPROCESSABLE_ENTITY
+        };
+        return Ok((status, Json(resp)).into_response());
+    }
+
+    if query.dry_run {
+        return Ok(Json(resp).into_response());
+    }
+
+    resp.imported = created;
+
+    Ok((StatusCode::CREATED, Json(resp)).into_response())
+}
+
+/// The API entrypoint for replacing the fields of a device
+pub async fn update_device(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+    headers: HeaderMap,
+    WithRejection(Json(payload), _): WithRejection<Json<UpdateDeviceRequest>, AppError>,
+) -> Result<Response, AppError> {
+    let if_match = if_match(&headers)?;
+    let device = device_repository
+        .update(
+            id,
+            normalize_update(payload)?,
+            &if_match,
+            authenticated_user.id()?,
+        )
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(device_response(device))
+}
+
+/// The API entrypoint for deleting a device, it is kept in the trash with
+/// its attachments until it is purged
+pub async fn delete_device(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+    headers: HeaderMap,
 ) -> Result<StatusCode, AppError> {
-    Ok(StatusCode::OK)
+    if !device_repository
+        .delete(id, &if_match(&headers)?, authenticated_user.id()?)
+        .await?
+    {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The API entrypoint for listing the deleted devices
+pub async fn get_device_trash(
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+) -> Result<Response, AppError> {
+    let items = device_repository.trash().await?;
+
+    Ok(Json(TrashedDeviceListResponse { items }).into_response())
+}
+
+/// The API entrypoint for taking a deleted device out of the trash
+pub async fn restore_device(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+) -> Result<Response, AppError> {
+    let device = device_repository
+        .restore(id, authenticated_user.id()?)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(device_response(device))
+}
+
+/// The API entrypoint for removing the devices which stayed in the trash
+/// longer than the retention period for good, with their attachments
+pub async fn purge_devices(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Extension(attachment_repository): Extension<Arc<dyn IAttachmentRepository + Send + Sync>>,
+    Extension(storage): Extension<Arc<dyn IBlobStorage + Send + Sync>>,
+    Extension(settings): Extension<Arc<TrashSettings>>,
+) -> Result<Response, AppError> {
+    // The cutoff is sent to the database with a precision of seconds
+    let deleted_before =
+        (Utc::now() - Duration::days(settings.retention_days.into())).trunc_subsecs(0);
+
+    // The attachments are deleted together with the devices, the contents of
+    // the devices which were actually purged are removed afterwards
+    let expired: Vec<_> = device_repository
+        .trash()
+        .await?
+        .into_iter()
+        .filter(|trashed| trashed.deleted_at < deleted_before)
+        .map(|trashed| trashed.device.id)
+        .collect();
+    let attachments = attachment_repository.list_many(&expired).await?;
+
+    let purged = device_repository
+        .purge(deleted_before, authenticated_user.id()?)
+        .await?;
+    let attachments: Vec<_> = attachments
+        .into_iter()
+        .filter(|attachment| purged.contains(&attachment.device_id))
+        .collect();
+    remove_contents(&storage, &attachments).await;
+
+    Ok(Json(PurgeDevicesResponse { purged }).into_response())
+}
+
+/// The API entrypoint for the change history of a device, it is kept after
+/// the device is deleted
+pub async fn get_device_history(
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+) -> Result<Response, AppError> {
+    let items = device_repository.history(id).await?;
+    if items.is_empty() {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(Json(DeviceHistoryListResponse { items }).into_response())
 }
@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+
+use crate::errors::AppError;
+use crate::models::hw_phase::{
+    HwPhaseListResponse, HwPhaseRequest, PromoteDevicesRequest, PromoteDevicesResponse,
+};
+use crate::models::login::AuthenticatedUser;
+use crate::repositories::i_device_repository::IDeviceRepository;
+use crate::repositories::i_hw_phase_repository::IHwPhaseRepository;
+
+/// The permission which allows a promotion to skip phases
+const OVERRIDE_PERMISSION: &str = "override:hw-phase";
+
+const MAX_PROMOTED_DEVICES: usize = 500;
+
+/// The limit of the `hw_phase` column of the `devices` table
+const MAX_NAME_LENGTH: usize = 128;
+
+/// Trim the name, the names of the phases are unique regardless of stray
+/// whitespace and case
+fn normalize(mut request: HwPhaseRequest) -> Result<HwPhaseRequest, AppError> {
+    request.name = request.name.trim().to_owned();
+
+    if request.name.is_empty() || request.name.chars().count() > MAX_NAME_LENGTH {
+        return Err(AppError::InvalidRequest(format!(
+            "name must be between 1 and {MAX_NAME_LENGTH} characters"
+        )));
+    }
+
+    Ok(request)
+}
+
+/// The API entrypoint for listing the hardware phases in order
+pub async fn get_hw_phases(
+    Extension(hw_phase_repository): Extension<Arc<dyn IHwPhaseRepository + Send + Sync>>,
+) -> Result<Response, AppError> {
+    let items = hw_phase_repository.list().await?;
+
+    Ok(Json(HwPhaseListResponse { items }).into_response())
+}
+
+/// The API entrypoint for adding a hardware phase
+pub async fn create_hw_phase(
+    Extension(hw_phase_repository): Extension<Arc<dyn IHwPhaseRepository + Send + Sync>>,
+    WithRejection(Json(payload), _): WithRejection<Json<HwPhaseRequest>, AppError>,
+) -> Result<Response, AppError> {
+    let phase = hw_phase_repository.create(normalize(payload)?).await?;
+
+    Ok((StatusCode::CREATED, Json(phase)).into_response())
+}
+
+/// The API entrypoint for renaming or moving a hardware phase
+pub async fn update_hw_phase(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(hw_phase_repository): Extension<Arc<dyn IHwPhaseRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+    WithRejection(Json(payload), _): WithRejection<Json<HwPhaseRequest>, AppError>,
+) -> Result<Response, AppError> {
+    let phase = hw_phase_repository
+        .update(id, normalize(payload)?, authenticated_user.id()?)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(phase).into_response())
+}
+
+/// The API entrypoint for deleting an unused hardware phase
+pub async fn delete_hw_phase(
+    Extension(hw_phase_repository): Extension<Arc<dyn IHwPhaseRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+) -> Result<StatusCode, AppError> {
+    if !hw_phase_repository.delete(id).await? {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The API entrypoint for moving many devices to their next hardware phase,
+/// all the devices move or none of them
+pub async fn promote_devices(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    WithRejection(Json(payload), _): WithRejection<Json<PromoteDevicesRequest>, AppError>,
+) -> Result<Response, AppError> {
+    if payload.ids.is_empty() || payload.ids.len() > MAX_PROMOTED_DEVICES {
+        return Err(AppError::InvalidRequest(format!(
+            "between 1 and {MAX_PROMOTED_DEVICES} devices are required"
+        )));
+    }
+
+    let items = device_repository
+        .promote(
+            &payload.ids,
+            payload.to_phase.as_deref(),
+            authenticated_user.has_permission(OVERRIDE_PERMISSION),
+            authenticated_user.id()?,
+        )
+        .await?;
+
+    Ok(Json(PromoteDevicesResponse { items }).into_response())
+}
@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+
+use crate::errors::AppError;
+use crate::labels::zpl;
+use crate::models::label_template::{LabelTemplateListResponse, LabelTemplateRequest};
+use crate::repositories::i_label_template_repository::ILabelTemplateRepository;
+
+fn validate(request: &LabelTemplateRequest) -> Result<(), AppError> {
+    if request.name.trim().is_empty() {
+        return Err(AppError::InvalidRequest(
+            "name must not be empty".to_owned(),
+        ));
+    }
+
+    zpl::validate(&request.zpl).map_err(AppError::InvalidRequest)
+}
+
+/// The API entrypoint for listing the label templates
+pub async fn get_label_templates(
+    Extension(template_repository): Extension<Arc<dyn ILabelTemplateRepository + Send + Sync>>,
+) -> Result<Response, AppError> {
+    let items = template_repository.list().await?;
+
+    Ok(Json(LabelTemplateListResponse { items }).into_response())
+}
+
+/// The API entrypoint for creating a label template
+pub async fn create_label_template(
+    Extension(template_repository): Extension<Arc<dyn ILabelTemplateRepository + Send + Sync>>,
+    WithRejection(Json(payload), _): WithRejection<Json<LabelTemplateRequest>, AppError>,
+) -> Result<Response, AppError> {
+    validate(&payload)?;
+
+    let template = template_repository.create(payload).await?;
+
+    Ok((StatusCode::CREATED, Json(template)).into_response())
+}
+
+/// The API entrypoint for replacing a label template
+pub async fn update_label_template(
+    Extension(template_repository): Extension<Arc<dyn ILabelTemplateRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+    WithRejection(Json(payload), _): WithRejection<Json<LabelTemplateRequest>, AppError>,
+) -> Result<Response, AppError> {
+    validate(&payload)?;
+
+    let template = template_repository
+        .update(id, payload)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(template).into_response())
+}
+
+/// The API entrypoint for deleting a label template
+pub async fn delete_label_template(
+    Extension(template_repository): Extension<Arc<dyn ILabelTemplateRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+) -> Result<StatusCode, AppError> {
+    if !template_repository.delete(id).await? {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,191 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use axum::extract::{Path, Query};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+
+use crate::configuration::PrinterSettings;
+use crate::errors::AppError;
+use crate::labels::{self, printer, zpl, Label};
+use crate::models::device::Device;
+use crate::models::label::{
+    BatchLabelFormat, BatchLabelsRequest, LabelFormat, LabelQuery, PrintLabelsRequest,
+    PrintLabelsResponse,
+};
+use crate::models::label_template::LabelTemplate;
+use crate::repositories::i_device_repository::IDeviceRepository;
+use crate::repositories::i_label_template_repository::ILabelTemplateRepository;
+use crate::telemetry::spawn_blocking_with_tracing;
+
+/// 10 sheets of 24 labels
+const MAX_BATCH_LABELS: usize = 240;
+
+const ZPL_CONTENT_TYPE: &str = "text/plain; charset=utf-8";
+
+/// Get the picked template or the default one, the error is a message for
+/// the caller
+async fn resolve_template(
+    template_repository: &Arc<dyn ILabelTemplateRepository + Send + Sync>,
+    id: Option<uuid::Uuid>,
+) -> Result<Result<LabelTemplate, String>, AppError> {
+    let template = match id {
+        Some(id) => template_repository
+            .get(id)
+            .await?
+            .ok_or_else(|| format!("unknown label template {id}")),
+        None => template_repository
+            .get_default()
+            .await?
+            .ok_or_else(|| "there is no default label template".to_owned()),
+    };
+
+    Ok(template)
+}
+
+/// Get the devices of a batch in the order of `ids`
+async fn batch_devices(
+    device_repository: &Arc<dyn IDeviceRepository + Send + Sync>,
+    ids: &[uuid::Uuid],
+) -> Result<Vec<Device>, AppError> {
+    if ids.is_empty() || ids.len() > MAX_BATCH_LABELS {
+        return Err(AppError::InvalidRequest(format!(
+            "between 1 and {MAX_BATCH_LABELS} devices are required"
+        )));
+    }
+
+    let devices = device_repository.get_many(ids).await?;
+
+    // The same device may be asked for many times
+    ids.iter()
+        .map(|id| {
+            devices
+                .iter()
+                .find(|device| device.id == *id)
+                .cloned()
+                .ok_or_else(|| AppError::InvalidRequest(format!("unknown device {id}")))
+        })
+        .collect()
+}
+
+/// The API entrypoint for rendering the label of a device as SVG, PNG or ZPL
+pub async fn get_device_label(
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Extension(template_repository): Extension<Arc<dyn ILabelTemplateRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+    WithRejection(Query(query), _): WithRejection<Query<LabelQuery>, AppError>,
+) -> Result<Response, AppError> {
+    let device = device_repository.get(id).await?.ok_or(AppError::NotFound)?;
+
+    if query.format == LabelFormat::Zpl {
+        let template = resolve_template(&template_repository, query.template)
+            .await?
+            .map_err(AppError::InvalidQuery)?;
+        let zpl = zpl::render(&template.zpl, &device);
+
+        return Ok(([(header::CONTENT_TYPE, ZPL_CONTENT_TYPE)], zpl).into_response());
+    }
+
+    let label = Label::for_device(&device, query.kind)
+        .map_err(|e| AppError::InvalidQuery(e.to_string()))?;
+
+    let resp = match query.format {
+        LabelFormat::Png => {
+            let png = spawn_blocking_with_tracing(move || labels::png(&label))
+                .await
+                .context("Failed to render a label")??;
+            ([(header::CONTENT_TYPE, "image/png")], png).into_response()
+        }
+        _ => (
+            [(header::CONTENT_TYPE, "image/svg+xml")],
+            labels::svg(&label),
+        )
+            .into_response(),
+    };
+
+    Ok(resp)
+}
+
+/// The API entrypoint for the labels of many devices, either laid out on A4
+/// sheets as a PDF document or as one ZPL label per device
+pub async fn get_device_label_sheet(
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Extension(template_repository): Extension<Arc<dyn ILabelTemplateRepository + Send + Sync>>,
+    WithRejection(Json(payload), _): WithRejection<Json<BatchLabelsRequest>, AppError>,
+) -> Result<Response, AppError> {
+    let devices = batch_devices(&device_repository, &payload.ids).await?;
+
+    if payload.format == BatchLabelFormat::Zpl {
+        let template = resolve_template(&template_repository, payload.template_id)
+            .await?
+            .map_err(AppError::InvalidRequest)?;
+        let zpl: String = devices
+            .iter()
+            .map(|device| zpl::render(&template.zpl, device))
+            .collect();
+
+        return Ok((
+            [
+                (header::CONTENT_TYPE, ZPL_CONTENT_TYPE),
+                (
+                    header::CONTENT_DISPOSITION,
+                    "attachment; filename=\"labels.zpl\"",
+                ),
+            ],
+            zpl,
+        )
+            .into_response());
+    }
+
+    let labels = devices
+        .iter()
+        .map(|device| Label::for_device(device, payload.kind))
+        .collect::<anyhow::Result<Vec<_>>>()
+        .map_err(|e| AppError::InvalidRequest(e.to_string()))?;
+
+    let pdf = spawn_blocking_with_tracing(move || labels::pdf_sheet(&labels))
+        .await
+        .context("Failed to render the label sheet")??;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/pdf"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"labels.pdf\"",
+            ),
+        ],
+        pdf,
+    )
+        .into_response())
+}
+
+/// The API entrypoint for printing the ZPL labels of many devices on the
+/// configured thermal printer
+pub async fn print_device_labels(
+    Extension(printer_settings): Extension<Arc<PrinterSettings>>,
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Extension(template_repository): Extension<Arc<dyn ILabelTemplateRepository + Send + Sync>>,
+    WithRejection(Json(payload), _): WithRejection<Json<PrintLabelsRequest>, AppError>,
+) -> Result<Response, AppError> {
+    let devices = batch_devices(&device_repository, &payload.ids).await?;
+    let template = resolve_template(&template_repository, payload.template_id)
+        .await?
+        .map_err(AppError::InvalidRequest)?;
+
+    let zpl: String = devices
+        .iter()
+        .map(|device| zpl::render(&template.zpl, device))
+        .collect();
+
+    printer::send(&printer_settings, &zpl)
+        .await
+        .map_err(AppError::PrinterUnavailable)?;
+
+    Ok(Json(PrintLabelsResponse {
+        printed: devices.len(),
+    })
+    .into_response())
+}
@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+
+use crate::errors::AppError;
+use crate::models::loan::{CheckoutRequest, ListLoansQuery, LoanListResponse};
+use crate::models::login::AuthenticatedUser;
+use crate::repositories::i_device_repository::IDeviceRepository;
+use crate::repositories::i_loan_repository::ILoanRepository;
+use crate::repositories::i_user_repository::IUserRespository;
+
+/// The API entrypoint for lending a device to a user
+pub async fn checkout_device(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Extension(loan_repository): Extension<Arc<dyn ILoanRepository + Send + Sync>>,
+    Extension(user_repository): Extension<Arc<dyn IUserRespository + Send + Sync>>,
+    Path((_version, device_id)): Path<(String, uuid::Uuid)>,
+    WithRejection(Json(payload), _): WithRejection<Json<CheckoutRequest>, AppError>,
+) -> Result<Response, AppError> {
+    let lent_by = authenticated_user.id()?;
+    let borrower_id = payload.borrower_id.unwrap_or(lent_by);
+
+    if payload.expected_return_at <= chrono::Utc::now() {
+        return Err(AppError::InvalidRequest(
+            "expectedReturnAt must be in the future".to_owned(),
+        ));
+    }
+
+    device_repository
+        .get(device_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    if !user_repository.exists(borrower_id).await? {
+        return Err(AppError::InvalidRequest("unknown borrower".to_owned()));
+    }
+
+    let loan = loan_repository
+        .checkout(
+            device_id,
+            borrower_id,
+            lent_by,
+            payload.expected_return_at,
+            payload.note,
+        )
+        .await?
+        .ok_or_else(|| AppError::Conflict("device is already checked out".to_owned()))?;
+
+    Ok((StatusCode::CREATED, Json(loan)).into_response())
+}
+
+/// The API entrypoint for returning a lent device
+pub async fn checkin_device(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Extension(loan_repository): Extension<Arc<dyn ILoanRepository + Send + Sync>>,
+    Path((_version, device_id)): Path<(String, uuid::Uuid)>,
+) -> Result<Response, AppError> {
+    let returned_by = authenticated_user.id()?;
+
+    device_repository
+        .get(device_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let loan = loan_repository
+        .checkin(device_id, returned_by)
+        .await?
+        .ok_or_else(|| AppError::Conflict("device is not checked out".to_owned()))?;
+
+    Ok(Json(loan).into_response())
+}
+
+/// The API entrypoint for the loan history of a device
+pub async fn get_device_loans(
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Extension(loan_repository): Extension<Arc<dyn ILoanRepository + Send + Sync>>,
+    Path((_version, device_id)): Path<(String, uuid::Uuid)>,
+) -> Result<Response, AppError> {
+    device_repository
+        .get(device_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let items = loan_repository.history(device_id).await?;
+
+    Ok(Json(LoanListResponse { items }).into_response())
+}
+
+/// The API entrypoint for listing who has which device right now
+pub async fn get_loans(
+    Extension(loan_repository): Extension<Arc<dyn ILoanRepository + Send + Sync>>,
+    WithRejection(Query(query), _): WithRejection<Query<ListLoansQuery>, AppError>,
+) -> Result<Response, AppError> {
+    let overdue_at = query.overdue.then(chrono::Utc::now);
+
+    let items = loan_repository
+        .list_open(query.borrower_id, overdue_at)
+        .await?;
+
+    Ok(Json(LoanListResponse { items }).into_response())
+}
@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+
+use crate::errors::AppError;
+use crate::models::location::{LocationListResponse, LocationRequest, MoveDeviceRequest};
+use crate::models::login::AuthenticatedUser;
+use crate::repositories::i_device_repository::IDeviceRepository;
+use crate::repositories::i_location_repository::ILocationRepository;
+
+/// The limit of the `name` column of the `locations` table
+const MAX_NAME_LENGTH: usize = 96;
+
+fn normalize(mut request: LocationRequest) -> Result<LocationRequest, AppError> {
+    request.name = request.name.trim().to_owned();
+
+    if request.name.is_empty() || request.name.chars().count() > MAX_NAME_LENGTH {
+        return Err(AppError::InvalidRequest(format!(
+            "name must be between 1 and {MAX_NAME_LENGTH} characters"
+        )));
+    }
+
+    Ok(request)
+}
+
+/// The API entrypoint for listing every location
+pub async fn get_locations(
+    Extension(location_repository): Extension<Arc<dyn ILocationRepository + Send + Sync>>,
+) -> Result<Response, AppError> {
+    let items = location_repository.list().await?;
+
+    Ok(Json(LocationListResponse { items }).into_response())
+}
+
+/// The API entrypoint for getting a location with its path from the site
+pub async fn get_location(
+    Extension(location_repository): Extension<Arc<dyn ILocationRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+) -> Result<Response, AppError> {
+    let location = location_repository
+        .get(id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(location).into_response())
+}
+
+/// The API entrypoint for adding a location
+pub async fn create_location(
+    Extension(location_repository): Extension<Arc<dyn ILocationRepository + Send + Sync>>,
+    WithRejection(Json(payload), _): WithRejection<Json<LocationRequest>, AppError>,
+) -> Result<Response, AppError> {
+    let location = location_repository.create(normalize(payload)?).await?;
+
+    Ok((StatusCode::CREATED, Json(location)).into_response())
+}
+
+/// The API entrypoint for renaming a location or moving it with everything
+/// under it
+pub async fn update_location(
+    Extension(location_repository): Extension<Arc<dyn ILocationRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+    WithRejection(Json(payload), _): WithRejection<Json<LocationRequest>, AppError>,
+) -> Result<Response, AppError> {
+    let location = location_repository
+        .update(id, normalize(payload)?)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(location).into_response())
+}
+
+/// The API entrypoint for deleting an empty location
+pub async fn delete_location(
+    Extension(location_repository): Extension<Arc<dyn ILocationRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+) -> Result<StatusCode, AppError> {
+    if !location_repository.delete(id).await? {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The API entrypoint for moving a device to another location, the move is
+/// recorded in the device history
+pub async fn move_device(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+    WithRejection(Json(payload), _): WithRejection<Json<MoveDeviceRequest>, AppError>,
+) -> Result<Response, AppError> {
+    let device = device_repository
+        .relocate(id, payload.location_id, authenticated_user.id()?)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(device).into_response())
+}
@@ -13,27 +13,36 @@ pub mod v1 {
         errors::AppError,
         models::login::{Claims, LoginRequest, LoginResponse},
         password::validate_credentials,
-        repositories::i_user_repository::IUserRespository,
+        repositories::{i_team_repository::ITeamRepository, i_user_repository::IUserRespository},
         startup::AppState,
     };
 
     pub async fn login(
         State(app_state): State<AppState>,
         Extension(user_repository): Extension<Arc<dyn IUserRespository + Sync + Send>>,
+        Extension(team_repository): Extension<Arc<dyn ITeamRepository + Sync + Send>>,
         WithRejection(Json(payload), _): WithRejection<Json<LoginRequest>, AppError>,
     ) -> Result<Response, AppError> {
         let credentials = payload.into();
 
         let user_id = validate_credentials(credentials, user_repository).await?;
-        let user_id = user_id.to_string();
         let exp = chrono::Utc::now() + chrono::Duration::days(15);
 
+        // The user holds the permissions of every team it belongs to
+        let memberships = team_repository.memberships(user_id).await?;
+        let roles = memberships.iter().map(|m| m.claim_role()).collect();
+        let mut permissions: Vec<String> = memberships
+            .into_iter()
+            .flat_map(|m| m.permissions)
+            .collect();
+        permissions.sort();
+        permissions.dedup();
+
         let claims = Claims {
-            sub: user_id,
+            sub: user_id.to_string(),
             exp: exp.timestamp() as usize,
-            // todo: add the user permissions
-            permissions: vec![],
-            roles: vec![],
+            permissions,
+            roles,
         };
 
         let token = jsonwebtoken::encode(
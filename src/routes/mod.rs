@@ -1,7 +1,61 @@
+mod attachments;
+mod boards;
+mod comments;
+mod device_status;
+mod device_types;
+mod devices;
 mod health_check;
+mod hw_phases;
+mod label_templates;
+mod labels;
+mod loans;
+mod locations;
 mod login;
-mod devices;
+mod owners;
+mod reservations;
+mod tags;
+mod teams;
 
+pub use attachments::{
+    delete_device_attachment, download_device_attachment, get_device_attachment_thumbnail,
+    get_device_attachments, upload_device_attachment,
+};
+pub use boards::{create_board, delete_board, get_board, get_boards, update_board};
+pub use comments::{
+    create_device_comment, delete_device_comment, get_device_comments, get_mentions,
+    update_device_comment,
+};
+pub use device_status::{
+    get_device_status_history, repair_device, report_lost_device, retire_device, scrap_device,
+    stock_device, use_device,
+};
+pub use device_types::{
+    create_device_type, delete_device_type, get_device_type, get_device_types, update_device_type,
+};
+pub use devices::{
+    create_device, delete_device, export_devices, get_device, get_device_by_barcode,
+    get_device_by_sn, get_device_history, get_device_trash, get_devices, import_devices,
+    purge_devices, restore_device, search_devices, update_device,
+};
 pub use health_check::health_check;
+pub use hw_phases::{
+    create_hw_phase, delete_hw_phase, get_hw_phases, promote_devices, update_hw_phase,
+};
+pub use label_templates::{
+    create_label_template, delete_label_template, get_label_templates, update_label_template,
+};
+pub use labels::{get_device_label, get_device_label_sheet, print_device_labels};
+pub use loans::{checkin_device, checkout_device, get_device_loans, get_loans};
+pub use locations::{
+    create_location, delete_location, get_location, get_locations, move_device, update_location,
+};
 pub use login::v1::login;
-pub use devices::get;
+pub use owners::{create_owner, delete_owner, get_owner, get_owners, update_owner};
+pub use reservations::{
+    create_reservation, delete_reservation, get_available_devices, get_device_reservations,
+};
+pub use tags::{delete_tag, get_device_tags, get_tags, retag_devices, tag_device, untag_device};
+pub use teams::{
+    add_team_member, create_team, delete_team, get_team, get_team_members, get_teams,
+    remove_team_member, update_team,
+};
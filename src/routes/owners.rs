@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+
+use crate::errors::AppError;
+use crate::models::owner::{OwnerListResponse, OwnerRequest};
+use crate::repositories::i_owner_repository::IOwnerRepository;
+
+/// The limits of the `name` and `description` columns of the `owners` table
+const MAX_NAME_LENGTH: usize = 1024;
+const MAX_DESCRIPTION_LENGTH: usize = 4096;
+
+fn normalize(mut request: OwnerRequest) -> Result<OwnerRequest, AppError> {
+    request.name = request.name.trim().to_owned();
+    if request.name.is_empty() || request.name.chars().count() > MAX_NAME_LENGTH {
+        return Err(AppError::InvalidRequest(format!(
+            "name must be between 1 and {MAX_NAME_LENGTH} characters"
+        )));
+    }
+    if request
+        .description
+        .as_ref()
+        .is_some_and(|d| d.chars().count() > MAX_DESCRIPTION_LENGTH)
+    {
+        return Err(AppError::InvalidRequest(format!(
+            "description must be at most {MAX_DESCRIPTION_LENGTH} characters"
+        )));
+    }
+    if request.user_id.is_some() == request.team_id.is_some() {
+        return Err(AppError::InvalidRequest(
+            "an owner is either a user or a team, exactly one of userId and teamId is required"
+                .to_owned(),
+        ));
+    }
+
+    Ok(request)
+}
+
+/// The API entrypoint for listing the owners
+pub async fn get_owners(
+    Extension(owner_repository): Extension<Arc<dyn IOwnerRepository + Send + Sync>>,
+) -> Result<Response, AppError> {
+    let items = owner_repository.list().await?;
+
+    Ok(Json(OwnerListResponse { items }).into_response())
+}
+
+/// The API entrypoint for getting an owner by its id
+pub async fn get_owner(
+    Extension(owner_repository): Extension<Arc<dyn IOwnerRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+) -> Result<Response, AppError> {
+    let owner = owner_repository.get(id).await?.ok_or(AppError::NotFound)?;
+
+    Ok(Json(owner).into_response())
+}
+
+/// The API entrypoint for creating the owner of a user or a team
+pub async fn create_owner(
+    Extension(owner_repository): Extension<Arc<dyn IOwnerRepository + Send + Sync>>,
+    WithRejection(Json(payload), _): WithRejection<Json<OwnerRequest>, AppError>,
+) -> Result<Response, AppError> {
+    let owner = owner_repository.create(normalize(payload)?).await?;
+
+    Ok((StatusCode::CREATED, Json(owner)).into_response())
+}
+
+/// The API entrypoint for replacing an owner
+pub async fn update_owner(
+    Extension(owner_repository): Extension<Arc<dyn IOwnerRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+    WithRejection(Json(payload), _): WithRejection<Json<OwnerRequest>, AppError>,
+) -> Result<Response, AppError> {
+    let owner = owner_repository
+        .update(id, normalize(payload)?)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(owner).into_response())
+}
+
+/// The API entrypoint for deleting an owner without devices
+pub async fn delete_owner(
+    Extension(owner_repository): Extension<Arc<dyn IOwnerRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+) -> Result<StatusCode, AppError> {
+    if !owner_repository.delete(id).await? {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
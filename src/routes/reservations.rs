@@ -0,0 +1,132 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+use chrono::SubsecRound;
+
+use crate::errors::{AppError, AuthError};
+use crate::models::login::AuthenticatedUser;
+use crate::models::reservation::{
+    AvailabilityQuery, AvailableDevicesResponse, CreateReservationRequest, ReservationListResponse,
+    TimeWindowQuery,
+};
+use crate::repositories::i_device_repository::IDeviceRepository;
+use crate::repositories::i_reservation_repository::IReservationRepository;
+
+use super::devices::{cursor_device, next_cursor, page_limit};
+
+/// The permission to cancel the reservations of the other users
+const MANAGE_PERMISSION: &str = "manage:reservations";
+
+/// The API entrypoint for booking a device for a time window
+pub async fn create_reservation(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Extension(reservation_repository): Extension<Arc<dyn IReservationRepository + Send + Sync>>,
+    Path((_version, device_id)): Path<(String, uuid::Uuid)>,
+    WithRejection(Json(payload), _): WithRejection<Json<CreateReservationRequest>, AppError>,
+) -> Result<Response, AppError> {
+    let reserved_by = authenticated_user.id()?;
+
+    // The window is sent to the database with a precision of seconds
+    let starts_at = payload.starts_at.trunc_subsecs(0);
+    let ends_at = payload.ends_at.trunc_subsecs(0);
+    if starts_at >= ends_at {
+        return Err(AppError::InvalidRequest(
+            "startsAt must be before endsAt".to_owned(),
+        ));
+    }
+
+    device_repository
+        .get(device_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let reservation = reservation_repository
+        .create(device_id, reserved_by, starts_at, ends_at, payload.purpose)
+        .await?
+        .ok_or_else(|| {
+            AppError::Conflict("device is already reserved in this time window".to_owned())
+        })?;
+
+    Ok((StatusCode::CREATED, Json(reservation)).into_response())
+}
+
+/// The API entrypoint for the reservations of a device
+pub async fn get_device_reservations(
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Extension(reservation_repository): Extension<Arc<dyn IReservationRepository + Send + Sync>>,
+    Path((_version, device_id)): Path<(String, uuid::Uuid)>,
+    WithRejection(Query(query), _): WithRejection<Query<TimeWindowQuery>, AppError>,
+) -> Result<Response, AppError> {
+    device_repository
+        .get(device_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let items = reservation_repository
+        .schedule(device_id, query.from, query.to)
+        .await?;
+
+    Ok(Json(ReservationListResponse { items }).into_response())
+}
+
+/// The API entrypoint for cancelling a reservation, by the user who made it
+/// or a holder of the manage permission
+pub async fn delete_reservation(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Extension(reservation_repository): Extension<Arc<dyn IReservationRepository + Send + Sync>>,
+    Path((_version, device_id, reservation_id)): Path<(String, uuid::Uuid, uuid::Uuid)>,
+) -> Result<StatusCode, AppError> {
+    device_repository
+        .get(device_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    let reservation = reservation_repository
+        .get(device_id, reservation_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    if reservation.reserved_by != authenticated_user.id()?
+        && !authenticated_user.has_permission(MANAGE_PERMISSION)
+    {
+        return Err(AuthError::Forbidden)?;
+    }
+
+    if !reservation_repository
+        .delete(device_id, reservation_id)
+        .await?
+    {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The API entrypoint for finding the devices which are free in a time window,
+/// a page at a time
+pub async fn get_available_devices(
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    WithRejection(Query(query), _): WithRejection<Query<AvailabilityQuery>, AppError>,
+) -> Result<Response, AppError> {
+    if query.from >= query.to {
+        return Err(AppError::InvalidQuery("from must be before to".to_owned()));
+    }
+
+    let limit = page_limit(query.limit)?;
+    let cursor = cursor_device(&device_repository, query.cursor).await?;
+
+    let items = device_repository
+        .available(query.from, query.to, limit, cursor)
+        .await?;
+
+    let resp = AvailableDevicesResponse {
+        next_cursor: next_cursor(&items, limit),
+        items,
+    };
+
+    Ok(Json(resp).into_response())
+}
@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+
+use crate::errors::AppError;
+use crate::models::tag::{
+    RetagDevicesRequest, TagDeviceRequest, TagListResponse, TagUsageListResponse,
+};
+use crate::repositories::i_device_repository::IDeviceRepository;
+use crate::repositories::i_tag_repository::ITagRepository;
+
+/// The limit of the `name` column of the `tags` table
+const MAX_TAG_LENGTH: usize = 64;
+
+const MAX_TAGS_PER_REQUEST: usize = 100;
+
+const MAX_DEVICES_PER_REQUEST: usize = 500;
+
+/// Lowercase and deduplicate tag names, a name can't contain whitespace or
+/// commas so it can be used in the `tags` filter of `GET /devices`
+fn normalize_tags(names: Vec<String>) -> Result<Vec<String>, AppError> {
+    if names.len() > MAX_TAGS_PER_REQUEST {
+        return Err(AppError::InvalidRequest(format!(
+            "at most {MAX_TAGS_PER_REQUEST} tags can be changed at once"
+        )));
+    }
+
+    let mut tags: Vec<String> = vec![];
+    for name in names {
+        let name = name.trim().to_lowercase();
+        if name.is_empty()
+            || name.chars().count() > MAX_TAG_LENGTH
+            || name.contains(|c: char| c.is_whitespace() || c == ',')
+        {
+            return Err(AppError::InvalidRequest(format!(
+                "`{name}` is not a valid tag, a tag has 1 to {MAX_TAG_LENGTH} characters without whitespace or commas"
+            )));
+        }
+        if !tags.contains(&name) {
+            tags.push(name);
+        }
+    }
+
+    Ok(tags)
+}
+
+/// The API entrypoint for listing the tags with the number of their devices
+pub async fn get_tags(
+    Extension(tag_repository): Extension<Arc<dyn ITagRepository + Send + Sync>>,
+) -> Result<Response, AppError> {
+    let items = tag_repository.list().await?;
+
+    Ok(Json(TagUsageListResponse { items }).into_response())
+}
+
+/// The API entrypoint for deleting a tag from every device
+pub async fn delete_tag(
+    Extension(tag_repository): Extension<Arc<dyn ITagRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+) -> Result<StatusCode, AppError> {
+    if !tag_repository.delete(id).await? {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The API entrypoint for listing the tags of a device
+pub async fn get_device_tags(
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Extension(tag_repository): Extension<Arc<dyn ITagRepository + Send + Sync>>,
+    Path((_version, device_id)): Path<(String, uuid::Uuid)>,
+) -> Result<Response, AppError> {
+    device_repository
+        .get(device_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let items = tag_repository.device_tags(device_id).await?;
+
+    Ok(Json(TagListResponse { items }).into_response())
+}
+
+/// The API entrypoint for tagging a device, the missing tags are created.
+/// Respond with every tag of the device.
+pub async fn tag_device(
+    Extension(device_repository): Extension<Arc<dyn IDeviceRepository + Send + Sync>>,
+    Extension(tag_repository): Extension<Arc<dyn ITagRepository + Send + Sync>>,
+    Path((_version, device_id)): Path<(String, uuid::Uuid)>,
+    WithRejection(Json(payload), _): WithRejection<Json<TagDeviceRequest>, AppError>,
+) -> Result<Response, AppError> {
+    let tags = normalize_tags(payload.tags)?;
+
+    device_repository
+        .get(device_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    tag_repository.retag(&[device_id], &tags, &[]).await?;
+    let items = tag_repository.device_tags(device_id).await?;
+
+    Ok(Json(TagListResponse { items }).into_response())
+}
+
+/// The API entrypoint for removing a tag from a device
+pub async fn untag_device(
+    Extension(tag_repository): Extension<Arc<dyn ITagRepository + Send + Sync>>,
+    Path((_version, device_id, name)): Path<(String, uuid::Uuid, String)>,
+) -> Result<StatusCode, AppError> {
+    if !tag_repository
+        .untag(device_id, &name.trim().to_lowercase())
+        .await?
+    {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The API entrypoint for tagging and untagging many devices at once, either
+/// every device is changed or none is
+pub async fn retag_devices(
+    Extension(tag_repository): Extension<Arc<dyn ITagRepository + Send + Sync>>,
+    WithRejection(Json(payload), _): WithRejection<Json<RetagDevicesRequest>, AppError>,
+) -> Result<StatusCode, AppError> {
+    let mut device_ids = payload.device_ids;
+    device_ids.sort();
+    device_ids.dedup();
+    if device_ids.is_empty() || device_ids.len() > MAX_DEVICES_PER_REQUEST {
+        return Err(AppError::InvalidRequest(format!(
+            "deviceIds must have between 1 and {MAX_DEVICES_PER_REQUEST} devices"
+        )));
+    }
+
+    let add = normalize_tags(payload.add)?;
+    let remove = normalize_tags(payload.remove)?;
+    if let Some(name) = add.iter().find(|name| remove.contains(name)) {
+        return Err(AppError::InvalidRequest(format!(
+            "`{name}` can't be both added and removed"
+        )));
+    }
+
+    tag_repository.retag(&device_ids, &add, &remove).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+
+use crate::errors::{AppError, AuthError};
+use crate::models::login::AuthenticatedUser;
+use crate::models::team::{
+    TeamListResponse, TeamMemberListResponse, TeamMemberRequest, TeamRequest, TeamRole,
+};
+use crate::repositories::i_team_repository::ITeamRepository;
+
+/// The permission which allows managing every team
+const MANAGE_PERMISSION: &str = "manage:teams";
+
+/// The limits of the `name` column of the `teams` table and of a permission
+const MAX_NAME_LENGTH: usize = 1024;
+const MAX_PERMISSION_LENGTH: usize = 128;
+
+fn normalize(mut request: TeamRequest) -> Result<TeamRequest, AppError> {
+    request.name = request.name.trim().to_owned();
+    if request.name.is_empty() || request.name.chars().count() > MAX_NAME_LENGTH {
+        return Err(AppError::InvalidRequest(format!(
+            "name must be between 1 and {MAX_NAME_LENGTH} characters"
+        )));
+    }
+
+    if request
+        .permissions
+        .iter()
+        .any(|p| p.trim().is_empty() || p.chars().count() > MAX_PERMISSION_LENGTH)
+    {
+        return Err(AppError::InvalidRequest(format!(
+            "permissions must be between 1 and {MAX_PERMISSION_LENGTH} characters"
+        )));
+    }
+    request.permissions.sort();
+    request.permissions.dedup();
+
+    Ok(request)
+}
+
+/// Only the holders of the manage permission and the managers of the team can
+/// change its members
+async fn ensure_can_manage(
+    authenticated_user: &AuthenticatedUser,
+    team_repository: &Arc<dyn ITeamRepository + Send + Sync>,
+    team_id: uuid::Uuid,
+) -> Result<(), AppError> {
+    if authenticated_user.has_permission(MANAGE_PERMISSION) {
+        return Ok(());
+    }
+
+    match team_repository
+        .role(team_id, authenticated_user.id()?)
+        .await?
+    {
+        Some(TeamRole::Manager) => Ok(()),
+        _ => Err(AuthError::Forbidden)?,
+    }
+}
+
+/// The API entrypoint for listing the teams
+pub async fn get_teams(
+    Extension(team_repository): Extension<Arc<dyn ITeamRepository + Send + Sync>>,
+) -> Result<Response, AppError> {
+    let items = team_repository.list().await?;
+
+    Ok(Json(TeamListResponse { items }).into_response())
+}
+
+/// The API entrypoint for getting a team by its id
+pub async fn get_team(
+    Extension(team_repository): Extension<Arc<dyn ITeamRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+) -> Result<Response, AppError> {
+    let team = team_repository.get(id).await?.ok_or(AppError::NotFound)?;
+
+    Ok(Json(team).into_response())
+}
+
+/// The API entrypoint for creating a team
+pub async fn create_team(
+    Extension(team_repository): Extension<Arc<dyn ITeamRepository + Send + Sync>>,
+    WithRejection(Json(payload), _): WithRejection<Json<TeamRequest>, AppError>,
+) -> Result<Response, AppError> {
+    let team = team_repository.create(normalize(payload)?).await?;
+
+    Ok((StatusCode::CREATED, Json(team)).into_response())
+}
+
+/// The API entrypoint for replacing a team
+pub async fn update_team(
+    Extension(team_repository): Extension<Arc<dyn ITeamRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+    WithRejection(Json(payload), _): WithRejection<Json<TeamRequest>, AppError>,
+) -> Result<Response, AppError> {
+    let team = team_repository
+        .update(id, normalize(payload)?)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(team).into_response())
+}
+
+/// The API entrypoint for deleting a team
+pub async fn delete_team(
+    Extension(team_repository): Extension<Arc<dyn ITeamRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+) -> Result<StatusCode, AppError> {
+    if !team_repository.delete(id).await? {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The API entrypoint for listing the members of a team
+pub async fn get_team_members(
+    Extension(team_repository): Extension<Arc<dyn ITeamRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+) -> Result<Response, AppError> {
+    let items = team_repository
+        .members(id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(TeamMemberListResponse { items }).into_response())
+}
+
+/// The API entrypoint for adding a member to a team or changing its role
+pub async fn add_team_member(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(team_repository): Extension<Arc<dyn ITeamRepository + Send + Sync>>,
+    Path((_version, id)): Path<(String, uuid::Uuid)>,
+    WithRejection(Json(payload), _): WithRejection<Json<TeamMemberRequest>, AppError>,
+) -> Result<Response, AppError> {
+    ensure_can_manage(&authenticated_user, &team_repository, id).await?;
+
+    let member = team_repository
+        .add_member(id, payload)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(member).into_response())
+}
+
+/// The API entrypoint for removing a member from a team
+pub async fn remove_team_member(
+    Extension(authenticated_user): Extension<AuthenticatedUser>,
+    Extension(team_repository): Extension<Arc<dyn ITeamRepository + Send + Sync>>,
+    Path((_version, id, user_id)): Path<(String, uuid::Uuid, uuid::Uuid)>,
+) -> Result<StatusCode, AppError> {
+    ensure_can_manage(&authenticated_user, &team_repository, id).await?;
+
+    if !team_repository.remove_member(id, user_id).await? {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,7 +1,8 @@
 use std::net::TcpListener;
 use std::sync::Arc;
 
-use axum::routing::{get, post};
+use axum::extract::DefaultBodyLimit;
+use axum::routing::{delete, get, post, put};
 use axum::{Extension, Router};
 use jsonwebtoken::{DecodingKey, EncodingKey};
 use sqlx::{postgres::PgPoolOptions, PgPool};
@@ -18,9 +19,55 @@ use uuid::Uuid;
 use crate::configuration::{DatabaseSettings, Settings};
 use crate::middlewares::authentication_layer;
 use crate::models::permission::Permission;
+use crate::repositories::i_attachment_repository::IAttachmentRepository;
+use crate::repositories::i_board_repository::IBoardRepository;
+use crate::repositories::i_comment_repository::ICommentRepository;
+use crate::repositories::i_device_repository::IDeviceRepository;
+use crate::repositories::i_device_type_repository::IDeviceTypeRepository;
+use crate::repositories::i_hw_phase_repository::IHwPhaseRepository;
+use crate::repositories::i_label_template_repository::ILabelTemplateRepository;
+use crate::repositories::i_loan_repository::ILoanRepository;
+use crate::repositories::i_location_repository::ILocationRepository;
+use crate::repositories::i_owner_repository::IOwnerRepository;
+use crate::repositories::i_reservation_repository::IReservationRepository;
+use crate::repositories::i_tag_repository::ITagRepository;
+use crate::repositories::i_team_repository::ITeamRepository;
 use crate::repositories::i_user_repository::IUserRespository;
+use crate::repositories::postgres_attachment_repository::PostgresAttachmentRepository;
+use crate::repositories::postgres_board_repository::PostgresBoardRepository;
+use crate::repositories::postgres_comment_repository::PostgresCommentRepository;
+use crate::repositories::postgres_device_repository::PostgresDeviceRepository;
+use crate::repositories::postgres_device_type_repository::PostgresDeviceTypeRepository;
+use crate::repositories::postgres_hw_phase_repository::PostgresHwPhaseRepository;
+use crate::repositories::postgres_label_template_repository::PostgresLabelTemplateRepository;
+use crate::repositories::postgres_loan_repository::PostgresLoanRepository;
+use crate::repositories::postgres_location_repository::PostgresLocationRepository;
+use crate::repositories::postgres_owner_repository::PostgresOwnerRepository;
+use crate::repositories::postgres_reservation_repository::PostgresReservationRepository;
+use crate::repositories::postgres_tag_repository::PostgresTagRepository;
+use crate::repositories::postgres_team_repository::PostgresTeamRepository;
 use crate::repositories::postgres_user_repository::PostgresUserRepository;
-use crate::routes::{health_check, login};
+use crate::routes::{
+    add_team_member, checkin_device, checkout_device, create_board, create_device,
+    create_device_comment, create_device_type, create_hw_phase, create_label_template,
+    create_location, create_owner, create_reservation, create_team, delete_board, delete_device,
+    delete_device_attachment, delete_device_comment, delete_device_type, delete_hw_phase,
+    delete_label_template, delete_location, delete_owner, delete_reservation, delete_tag,
+    delete_team, download_device_attachment, export_devices, get_available_devices, get_board,
+    get_boards, get_device, get_device_attachment_thumbnail, get_device_attachments,
+    get_device_by_barcode, get_device_by_sn, get_device_comments, get_device_history,
+    get_device_label, get_device_label_sheet, get_device_loans, get_device_reservations,
+    get_device_status_history, get_device_tags, get_device_trash, get_device_type,
+    get_device_types, get_devices, get_hw_phases, get_label_templates, get_loans, get_location,
+    get_locations, get_mentions, get_owner, get_owners, get_tags, get_team, get_team_members,
+    get_teams, health_check, import_devices, login, move_device, print_device_labels,
+    promote_devices, purge_devices, remove_team_member, repair_device, report_lost_device,
+    restore_device, retag_devices, retire_device, scrap_device, search_devices, stock_device,
+    tag_device, untag_device, update_board, update_device, update_device_comment,
+    update_device_type, update_hw_phase, update_label_template, update_location, update_owner,
+    update_team, upload_device_attachment, use_device,
+};
+use crate::storage;
 use crate::utils::PostgresSession;
 
 /// A data structure for app state
@@ -69,13 +116,308 @@ pub async fn run(settings: Settings, listener: TcpListener) -> hyper::Result<()>
         .expect("Failed to creaet a user repository")
         as Arc<dyn IUserRespository + Send + Sync>;
 
+    let device_repository = PostgresSession::new(db_pool.clone())
+        .await
+        .map(PostgresDeviceRepository::new)
+        .map(Arc::new)
+        .expect("Failed to create a device repository")
+        as Arc<dyn IDeviceRepository + Send + Sync>;
+
+    let loan_repository = PostgresSession::new(db_pool.clone())
+        .await
+        .map(PostgresLoanRepository::new)
+        .map(Arc::new)
+        .expect("Failed to create a loan repository")
+        as Arc<dyn ILoanRepository + Send + Sync>;
+
+    let reservation_repository = PostgresSession::new(db_pool.clone())
+        .await
+        .map(PostgresReservationRepository::new)
+        .map(Arc::new)
+        .expect("Failed to create a reservation repository")
+        as Arc<dyn IReservationRepository + Send + Sync>;
+
+    let label_template_repository = PostgresSession::new(db_pool.clone())
+        .await
+        .map(PostgresLabelTemplateRepository::new)
+        .map(Arc::new)
+        .expect("Failed to create a label template repository")
+        as Arc<dyn ILabelTemplateRepository + Send + Sync>;
+
+    let hw_phase_repository = PostgresSession::new(db_pool.clone())
+        .await
+        .map(PostgresHwPhaseRepository::new)
+        .map(Arc::new)
+        .expect("Failed to create a hardware phase repository")
+        as Arc<dyn IHwPhaseRepository + Send + Sync>;
+
+    let board_repository = PostgresSession::new(db_pool.clone())
+        .await
+        .map(PostgresBoardRepository::new)
+        .map(Arc::new)
+        .expect("Failed to create a board repository")
+        as Arc<dyn IBoardRepository + Send + Sync>;
+
+    let device_type_repository = PostgresSession::new(db_pool.clone())
+        .await
+        .map(PostgresDeviceTypeRepository::new)
+        .map(Arc::new)
+        .expect("Failed to create a device type repository")
+        as Arc<dyn IDeviceTypeRepository + Send + Sync>;
+
+    let team_repository = PostgresSession::new(db_pool.clone())
+        .await
+        .map(PostgresTeamRepository::new)
+        .map(Arc::new)
+        .expect("Failed to create a team repository")
+        as Arc<dyn ITeamRepository + Send + Sync>;
+
+    let owner_repository = PostgresSession::new(db_pool.clone())
+        .await
+        .map(PostgresOwnerRepository::new)
+        .map(Arc::new)
+        .expect("Failed to create an owner repository")
+        as Arc<dyn IOwnerRepository + Send + Sync>;
+
+    let attachment_repository = PostgresSession::new(db_pool.clone())
+        .await
+        .map(PostgresAttachmentRepository::new)
+        .map(Arc::new)
+        .expect("Failed to create an attachment repository")
+        as Arc<dyn IAttachmentRepository + Send + Sync>;
+
+    let comment_repository = PostgresSession::new(db_pool.clone())
+        .await
+        .map(PostgresCommentRepository::new)
+        .map(Arc::new)
+        .expect("Failed to create a comment repository")
+        as Arc<dyn ICommentRepository + Send + Sync>;
+    let tag_repository = PostgresSession::new(db_pool.clone())
+        .await
+        .map(PostgresTagRepository::new)
+        .map(Arc::new)
+        .expect("Failed to create a tag repository")
+        as Arc<dyn ITagRepository + Send + Sync>;
+    let location_repository = PostgresSession::new(db_pool.clone())
+        .await
+        .map(PostgresLocationRepository::new)
+        .map(Arc::new)
+        .expect("Failed to create a location repository")
+        as Arc<dyn ILocationRepository + Send + Sync>;
+
+    let printer_settings = Arc::new(settings.printer);
+    let blob_storage = storage::from_settings(&settings.attachments.storage);
+    // Leave room for the multipart boundaries and headers around the file,
+    // the handler enforces the exact limit
+    let upload_limit = DefaultBodyLimit::max(settings.attachments.max_size_bytes + 64 * 1024);
+    let attachment_settings = Arc::new(settings.attachments);
+    let trash_settings = Arc::new(settings.trash);
+
     let devices_routes = Router::new()
-        .route("/devices", get(crate::routes::get))
-        .route_layer(axum::middleware::from_fn_with_state(
-            state.clone(),
-            |state, req, next| {
-                authentication_layer(state, req, next, Arc::new(Permission::Empty))
-            },
+        .merge(require_permission(
+            Router::new()
+                .route("/devices", get(get_devices))
+                .route("/devices/search", get(search_devices))
+                .route("/devices/export", get(export_devices))
+                .route("/devices/by-barcode/:code", get(get_device_by_barcode))
+                .route("/devices/by-sn/:sn", get(get_device_by_sn))
+                .route("/devices/available", get(get_available_devices))
+                .route("/devices/:id", get(get_device))
+                .route("/devices/labels", post(get_device_label_sheet))
+                .route("/devices/:id/history", get(get_device_history))
+                .route("/devices/:id/attachments", get(get_device_attachments))
+                .route("/devices/:id/comments", get(get_device_comments))
+                .route("/devices/:id/tags", get(get_device_tags))
+                .route(
+                    "/devices/:id/attachments/:attachment_id",
+                    get(download_device_attachment),
+                )
+                .route(
+                    "/devices/:id/attachments/:attachment_id/thumbnail",
+                    get(get_device_attachment_thumbnail),
+                )
+                .route("/devices/:id/label", get(get_device_label))
+                .route("/devices/:id/loans", get(get_device_loans))
+                .route("/devices/:id/reservations", get(get_device_reservations))
+                .route(
+                    "/devices/:id/status-history",
+                    get(get_device_status_history),
+                )
+                .route("/loans", get(get_loans))
+                .route("/label-templates", get(get_label_templates))
+                .route("/hw-phases", get(get_hw_phases))
+                .route("/boards", get(get_boards))
+                .route("/boards/:id", get(get_board))
+                .route("/device-types", get(get_device_types))
+                .route("/device-types/:id", get(get_device_type))
+                .route("/owners", get(get_owners))
+                .route("/owners/:id", get(get_owner))
+                .route("/tags", get(get_tags))
+                .route("/locations", get(get_locations))
+                .route("/locations/:id", get(get_location)),
+            &state,
+            "read:devices",
+        ))
+        .merge(require_permission(
+            Router::new().route("/devices/:id/checkout", post(checkout_device)),
+            &state,
+            "checkout:device",
+        ))
+        .merge(require_permission(
+            Router::new().route("/devices/:id/checkin", post(checkin_device)),
+            &state,
+            "checkin:device",
+        ))
+        .merge(require_permission(
+            Router::new()
+                .route("/devices/:id/reservations", post(create_reservation))
+                .route(
+                    "/devices/:id/reservations/:reservation_id",
+                    delete(delete_reservation),
+                ),
+            &state,
+            "reserve:device",
+        ))
+        .merge(require_permission(
+            Router::new()
+                .route("/devices", post(create_device))
+                .route("/devices/import", post(import_devices)),
+            &state,
+            "create:device",
+        ))
+        .merge(require_permission(
+            Router::new()
+                .route("/devices/:id", put(update_device))
+                .route("/devices/promote", post(promote_devices))
+                .route("/devices/:id/stock", post(stock_device))
+                .route("/devices/:id/use", post(use_device))
+                .route("/devices/:id/repair", post(repair_device))
+                .route("/devices/:id/report-lost", post(report_lost_device))
+                .route("/devices/:id/retire", post(retire_device))
+                .route("/devices/:id/scrap", post(scrap_device))
+                .route("/devices/:id/move", post(move_device))
+                .route(
+                    "/devices/:id/attachments",
+                    post(upload_device_attachment).layer(upload_limit),
+                )
+                .route(
+                    "/devices/:id/attachments/:attachment_id",
+                    delete(delete_device_attachment),
+                )
+                .route("/devices/tags", post(retag_devices))
+                .route("/devices/:id/tags", post(tag_device))
+                .route("/devices/:id/tags/:name", delete(untag_device)),
+            &state,
+            "update:device",
+        ))
+        .merge(require_permission(
+            // The handlers check the author of an existing comment
+            Router::new()
+                .route("/devices/:id/comments", post(create_device_comment))
+                .route(
+                    "/devices/:id/comments/:comment_id",
+                    put(update_device_comment).delete(delete_device_comment),
+                ),
+            &state,
+            "comment:device",
+        ))
+        .merge(require_permission(
+            Router::new()
+                .route("/devices/:id", delete(delete_device))
+                .route("/devices/trash", get(get_device_trash))
+                .route("/devices/:id/restore", post(restore_device)),
+            &state,
+            "delete:devices",
+        ))
+        .merge(require_permission(
+            Router::new().route("/devices/trash", delete(purge_devices)),
+            &state,
+            "purge:devices",
+        ))
+        .merge(require_permission(
+            Router::new().route("/devices/labels/print", post(print_device_labels)),
+            &state,
+            "print:labels",
+        ))
+        .merge(require_permission(
+            Router::new()
+                .route("/label-templates", post(create_label_template))
+                .route(
+                    "/label-templates/:id",
+                    put(update_label_template).delete(delete_label_template),
+                ),
+            &state,
+            "manage:label-templates",
+        ))
+        .merge(require_permission(
+            Router::new()
+                .route("/hw-phases", post(create_hw_phase))
+                .route(
+                    "/hw-phases/:id",
+                    put(update_hw_phase).delete(delete_hw_phase),
+                ),
+            &state,
+            "manage:hw-phases",
+        ))
+        .merge(require_permission(
+            Router::new()
+                .route("/boards", post(create_board))
+                .route("/boards/:id", put(update_board).delete(delete_board)),
+            &state,
+            "manage:boards",
+        ))
+        .merge(require_permission(
+            Router::new()
+                .route("/device-types", post(create_device_type))
+                .route(
+                    "/device-types/:id",
+                    put(update_device_type).delete(delete_device_type),
+                ),
+            &state,
+            "manage:device-types",
+        ))
+        .merge(require_authentication(
+            // Managers of a team change its members without `manage:teams`, and
+            // every user reads their own mentions
+            Router::new()
+                .route("/teams", get(get_teams))
+                .route("/teams/:id", get(get_team))
+                .route(
+                    "/teams/:id/members",
+                    get(get_team_members).post(add_team_member),
+                )
+                .route("/teams/:id/members/:user_id", delete(remove_team_member))
+                .route("/mentions", get(get_mentions)),
+            &state,
+        ))
+        .merge(require_permission(
+            Router::new()
+                .route("/teams", post(create_team))
+                .route("/teams/:id", put(update_team).delete(delete_team)),
+            &state,
+            "manage:teams",
+        ))
+        .merge(require_permission(
+            Router::new()
+                .route("/owners", post(create_owner))
+                .route("/owners/:id", put(update_owner).delete(delete_owner)),
+            &state,
+            "manage:owners",
+        ))
+        .merge(require_permission(
+            Router::new().route("/tags/:id", delete(delete_tag)),
+            &state,
+            "manage:tags",
+        ))
+        .merge(require_permission(
+            Router::new()
+                .route("/locations", post(create_location))
+                .route(
+                    "/locations/:id",
+                    put(update_location).delete(delete_location),
+                ),
+            &state,
+            "manage:locations",
         ));
 
     let app = Router::new()
@@ -96,6 +438,23 @@ pub async fn run(settings: Settings, listener: TcpListener) -> hyper::Result<()>
                 ),
         )
         .layer(Extension(user_repository))
+        .layer(Extension(device_repository))
+        .layer(Extension(loan_repository))
+        .layer(Extension(reservation_repository))
+        .layer(Extension(label_template_repository))
+        .layer(Extension(hw_phase_repository))
+        .layer(Extension(board_repository))
+        .layer(Extension(device_type_repository))
+        .layer(Extension(team_repository))
+        .layer(Extension(owner_repository))
+        .layer(Extension(attachment_repository))
+        .layer(Extension(comment_repository))
+        .layer(Extension(tag_repository))
+        .layer(Extension(location_repository))
+        .layer(Extension(blob_storage))
+        .layer(Extension(attachment_settings))
+        .layer(Extension(trash_settings))
+        .layer(Extension(printer_settings))
         .layer(CorsLayer::permissive())
         .with_state(state);
 
@@ -105,6 +464,35 @@ pub async fn run(settings: Settings, listener: TcpListener) -> hyper::Result<()>
         .await
 }
 
+/// Protect every route of the `router` with the `authentication_layer`,
+/// the caller must hold the individual `permission`
+fn require_permission(
+    router: Router<AppState>,
+    state: &AppState,
+    permission: &str,
+) -> Router<AppState> {
+    protect(
+        router,
+        state,
+        Permission::IndividualPermission(vec![permission.to_owned()]),
+    )
+}
+
+/// Protect every route of the `router` with the `authentication_layer`, any
+/// valid token is accepted and the handlers check the rest
+fn require_authentication(router: Router<AppState>, state: &AppState) -> Router<AppState> {
+    protect(router, state, Permission::Empty)
+}
+
+fn protect(router: Router<AppState>, state: &AppState, permission: Permission) -> Router<AppState> {
+    let permission = Arc::new(permission);
+
+    router.route_layer(axum::middleware::from_fn_with_state(
+        state.clone(),
+        move |state, req, next| authentication_layer(state, req, next, permission.clone()),
+    ))
+}
+
 /// Get a database connection by giving a `DatabaseSettings`
 pub async fn get_database_connection(config: &DatabaseSettings) -> PgPool {
     PgPoolOptions::new()
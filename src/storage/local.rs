@@ -0,0 +1,58 @@
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use anyhow::Context;
+
+use super::IBlobStorage;
+
+/// Stores the blobs as files under a directory, a key is the relative path
+pub struct LocalStorage {
+    directory: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(directory: PathBuf) -> Self {
+        Self { directory }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.directory.join(key)
+    }
+}
+
+#[async_trait::async_trait]
+impl IBlobStorage for LocalStorage {
+    async fn put(&self, key: &str, content: Vec<u8>, _content_type: &str) -> anyhow::Result<()> {
+        let path = self.path(key);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .context("Failed to create the directory of a blob")?;
+        }
+
+        // A reader never sees a partially written file
+        let mut partial = path.clone().into_os_string();
+        partial.push(".partial");
+        tokio::fs::write(&partial, content)
+            .await
+            .context("Failed to write a blob")?;
+        tokio::fs::rename(&partial, &path)
+            .await
+            .context("Failed to move a written blob into place")
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        match tokio::fs::read(self.path(key)).await {
+            Ok(content) => Ok(Some(content)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).context("Failed to read a blob"),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        match tokio::fs::remove_file(self.path(key)).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e).context("Failed to delete a blob"),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,44 @@
+//! Blob storage of the files attached to devices
+
+mod local;
+mod s3;
+
+use std::sync::Arc;
+
+pub use local::LocalStorage;
+pub use s3::S3Storage;
+
+use crate::configuration::StorageSettings;
+
+/// Stores blobs by key, the keys are paths made of `/` separated segments
+#[async_trait::async_trait]
+pub trait IBlobStorage {
+    /// Store a blob, an existing blob with the same key is replaced
+    async fn put(&self, key: &str, content: Vec<u8>, content_type: &str) -> anyhow::Result<()>;
+
+    /// Read a blob, return `None` if it doesn't exist
+    async fn get(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>>;
+
+    /// Delete a blob, deleting a missing blob succeeds
+    async fn delete(&self, key: &str) -> anyhow::Result<()>;
+}
+
+/// Create the storage selected by the settings
+pub fn from_settings(settings: &StorageSettings) -> Arc<dyn IBlobStorage + Send + Sync> {
+    match settings {
+        StorageSettings::Local { directory } => Arc::new(LocalStorage::new(directory.clone())),
+        StorageSettings::S3 {
+            endpoint,
+            bucket,
+            region,
+            access_key_id,
+            secret_access_key,
+        } => Arc::new(S3Storage::new(
+            endpoint,
+            bucket,
+            region,
+            access_key_id,
+            secret_access_key.clone(),
+        )),
+    }
+}
@@ -0,0 +1,131 @@
+//! Thumbnails of the photos attached to devices
+
+use std::io::Cursor;
+
+use anyhow::Context;
+use image::io::{Limits, Reader};
+use image::{DynamicImage, ImageOutputFormat, Rgb, RgbImage};
+
+use crate::models::attachment::ThumbnailSize;
+
+/// The media types of the photos which get thumbnails
+const PHOTO_CONTENT_TYPES: [&str; 3] = ["image/png", "image/jpeg", "image/webp"];
+
+/// Refuse to decode larger photos, a small file can expand to a huge bitmap
+const MAX_DIMENSION: u32 = 16384;
+
+const JPEG_QUALITY: u8 = 80;
+
+/// The media type of every thumbnail
+pub const CONTENT_TYPE: &str = "image/jpeg";
+
+/// Whether the attachments of a media type get thumbnails
+pub fn is_photo(content_type: &str) -> bool {
+    PHOTO_CONTENT_TYPES.contains(&content_type)
+}
+
+/// Render a JPEG thumbnail of every size, the photo is decoded once. A photo
+/// smaller than a size is kept as is instead of being scaled up, and the
+/// transparent areas are filled with white.
+pub fn render(
+    content: &[u8],
+    sizes: &[ThumbnailSize],
+) -> anyhow::Result<Vec<(ThumbnailSize, Vec<u8>)>> {
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DIMENSION);
+    limits.max_image_height = Some(MAX_DIMENSION);
+
+    let mut reader = Reader::new(Cursor::new(content))
+        .with_guessed_format()
+        .context("Failed to read a photo")?;
+    reader.limits(limits);
+    let photo = flatten(reader.decode().context("Failed to decode a photo")?);
+
+    sizes
+        .iter()
+        .map(|&size| {
+            let pixels = size.pixels();
+            let thumbnail = if photo.width() <= pixels && photo.height() <= pixels {
+                photo.clone()
+            } else {
+                photo.thumbnail(pixels, pixels)
+            };
+
+            let mut jpeg = vec![];
+            thumbnail
+                .write_to(
+                    &mut Cursor::new(&mut jpeg),
+                    ImageOutputFormat::Jpeg(JPEG_QUALITY),
+                )
+                .context("Failed to encode a thumbnail")?;
+
+            Ok((size, jpeg))
+        })
+        .collect()
+}
+
+/// JPEG has no alpha channel, blend the photo over a white background
+fn flatten(photo: DynamicImage) -> DynamicImage {
+    if !photo.color().has_alpha() {
+        return DynamicImage::ImageRgb8(photo.to_rgb8());
+    }
+
+    let rgba = photo.to_rgba8();
+    let rgb = RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let [r, g, b, a] = rgba.get_pixel(x, y).0;
+        let blend = |c: u8| ((c as u16 * a as u16 + 255 * (255 - a as u16)) / 255) as u8;
+        Rgb([blend(r), blend(g), blend(b)])
+    });
+
+    DynamicImage::ImageRgb8(rgb)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use image::{DynamicImage, ImageOutputFormat, Rgba, RgbaImage};
+
+    use super::render;
+    use crate::models::attachment::ThumbnailSize;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let photo = RgbaImage::from_pixel(width, height, Rgba([10, 20, 30, 0]));
+        let mut content = vec![];
+        DynamicImage::ImageRgba8(photo)
+            .write_to(&mut Cursor::new(&mut content), ImageOutputFormat::Png)
+            .unwrap();
+
+        content
+    }
+
+    fn dimensions(jpeg: &[u8]) -> (u32, u32) {
+        let thumbnail = image::load_from_memory(jpeg).unwrap();
+
+        (thumbnail.width(), thumbnail.height())
+    }
+
+    #[test]
+    fn thumbnails_keep_the_aspect_ratio() {
+        let thumbnails = render(&png(1024, 256), &ThumbnailSize::ALL).unwrap();
+
+        assert_eq!(thumbnails[0].0, ThumbnailSize::Small);
+        assert_eq!(dimensions(&thumbnails[0].1), (128, 32));
+        assert_eq!(dimensions(&thumbnails[1].1), (512, 128));
+    }
+
+    #[test]
+    fn small_photos_are_not_scaled_up() {
+        let thumbnails = render(&png(100, 40), &[ThumbnailSize::Medium]).unwrap();
+
+        assert_eq!(dimensions(&thumbnails[0].1), (100, 40));
+        // The transparent pixels turn white
+        let thumbnail = image::load_from_memory(&thumbnails[0].1).unwrap().to_rgb8();
+        assert!(thumbnail.get_pixel(50, 20).0.iter().all(|&c| c > 250));
+    }
+
+    #[test]
+    fn render_rejects_what_is_not_a_photo() {
+        assert!(render(b"not a photo", &ThumbnailSize::ALL).is_err());
+    }
+}
@@ -1,26 +1,31 @@
-use std::sync::Arc;
-
+use anyhow::Context;
 use sqlx::pool::PoolConnection;
 use sqlx::{PgPool, Postgres};
-use tokio::sync::{Mutex, MutexGuard};
+
+use crate::errors::AppError;
 
 #[derive(Debug, Clone)]
 pub struct PostgresSession {
     pool: PgPool,
-    session: Arc<Mutex<PoolConnection<Postgres>>>,
 }
 
 impl PostgresSession {
+    /// Fail early if the database is unreachable, the connections themselves
+    /// are taken from the pool by every call of a repository
     pub async fn new(pool: PgPool) -> Result<Self, sqlx::Error> {
-        let session = pool.acquire().await?;
-        Ok(Self {
-            pool,
-            session: Arc::new(Mutex::new(session)),
-        })
+        pool.acquire().await?;
+        Ok(Self { pool })
     }
 
-    pub async fn get_session(&self) -> MutexGuard<PoolConnection<Postgres>> {
-        self.session.lock().await
+    pub async fn get_session(&self) -> anyhow::Result<PoolConnection<Postgres>> {
+        let session = self
+            .pool
+            .acquire()
+            .await
+            .context("Failed to acquire a database connection")
+            .map_err(AppError::UnexpectedError)?;
+
+        Ok(session)
     }
 
     pub async fn get_pool(&self) -> PgPool {
@@ -0,0 +1,207 @@
+use crate::helpers::{device_body, spawn_app};
+
+const ATTACHMENT_PERMISSIONS: [&str; 2] = ["read:devices", "update:device"];
+
+#[tokio::test]
+async fn attachments_can_be_uploaded_downloaded_and_deleted() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&ATTACHMENT_PERMISSIONS);
+    let device = app.create_device(&device_body("phone")).await;
+    let uri = format!(
+        "/api/v1/devices/{}/attachments",
+        device["id"].as_str().unwrap()
+    );
+    let content = b"%PDF-1.4 not really a pdf";
+
+    // Act
+    let resp = app
+        .upload_file(
+            &uri,
+            "Rechnung März.pdf",
+            "application/pdf",
+            content,
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 201);
+    let attachment: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(attachment["fileName"], "Rechnung März.pdf");
+    assert_eq!(attachment["contentType"], "application/pdf");
+    assert_eq!(attachment["sizeBytes"], content.len());
+    assert_eq!(attachment["uploadedBy"], app.test_user.id.to_string());
+    let attachment_uri = format!("{uri}/{}", attachment["id"].as_str().unwrap());
+
+    let resp = app.get(&uri, Some(&token)).await;
+    let attachments: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(attachments["items"][0]["id"], attachment["id"]);
+
+    let resp = app.get(&attachment_uri, Some(&token)).await;
+    assert_eq!(resp.status().as_u16(), 200);
+    assert_eq!(resp.headers()["content-type"], "application/pdf");
+    assert_eq!(
+        resp.headers()["content-disposition"],
+        "attachment; filename=\"Rechnung M_rz.pdf\"; filename*=UTF-8''Rechnung%20M%C3%A4rz.pdf"
+    );
+    assert_eq!(resp.bytes().await.unwrap().as_ref(), content);
+
+    let resp = app.delete(&attachment_uri, Some(&token)).await;
+    assert_eq!(resp.status().as_u16(), 204);
+    let resp = app.get(&attachment_uri, Some(&token)).await;
+    assert_eq!(resp.status().as_u16(), 404);
+    let resp = app.get(&uri, Some(&token)).await;
+    let attachments: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(attachments["items"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn attachments_outside_the_limits_are_rejected() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&ATTACHMENT_PERMISSIONS);
+    let device = app.create_device(&device_body("phone")).await;
+    let uri = format!(
+        "/api/v1/devices/{}/attachments",
+        device["id"].as_str().unwrap()
+    );
+    let too_large = vec![0; 10 * 1024 * 1024 + 1];
+
+    // Act
+    let unsupported = app
+        .upload_file(
+            &uri,
+            "tool.exe",
+            "application/x-msdownload",
+            b"MZ",
+            Some(&token),
+        )
+        .await;
+    let oversized = app
+        .upload_file(&uri, "photo.png", "image/png", &too_large, Some(&token))
+        .await;
+
+    // Assert
+    assert_eq!(unsupported.status().as_u16(), 415);
+    assert_eq!(oversized.status().as_u16(), 413);
+
+    let resp = app.get(&uri, Some(&token)).await;
+    let attachments: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(attachments["items"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn attachments_require_an_existing_device_and_permission() {
+    // Arrange
+    let app = spawn_app().await;
+    let reader = app.generate_token(&["read:devices"]);
+    let token = app.generate_token(&ATTACHMENT_PERMISSIONS);
+    let device = app.create_device(&device_body("phone")).await;
+    let uri = format!(
+        "/api/v1/devices/{}/attachments",
+        device["id"].as_str().unwrap()
+    );
+
+    // Act
+    let forbidden = app
+        .upload_file(&uri, "photo.png", "image/png", b"png", Some(&reader))
+        .await;
+    let unknown = app
+        .upload_file(
+            &format!("/api/v1/devices/{}/attachments", uuid::Uuid::new_v4()),
+            "photo.png",
+            "image/png",
+            b"png",
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(forbidden.status().as_u16(), 403);
+    assert_eq!(unknown.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn photos_are_served_as_cached_thumbnails() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&ATTACHMENT_PERMISSIONS);
+    let device = app.create_device(&device_body("phone")).await;
+    let uri = format!(
+        "/api/v1/devices/{}/attachments",
+        device["id"].as_str().unwrap()
+    );
+    let mut photo = vec![];
+    image::DynamicImage::new_rgb8(1000, 500)
+        .write_to(
+            &mut std::io::Cursor::new(&mut photo),
+            image::ImageOutputFormat::Png,
+        )
+        .unwrap();
+    let resp = app
+        .upload_file(&uri, "damage.png", "image/png", &photo, Some(&token))
+        .await;
+    let attachment: serde_json::Value = resp.json().await.unwrap();
+    let resp = app
+        .upload_file(&uri, "notes.txt", "text/plain", b"notes", Some(&token))
+        .await;
+    let document: serde_json::Value = resp.json().await.unwrap();
+    let thumbnail_uri = |attachment: &serde_json::Value, size: &str| {
+        format!(
+            "{}{uri}/{}/thumbnail?size={size}",
+            app.address,
+            attachment["id"].as_str().unwrap()
+        )
+    };
+
+    // Act
+    let small = app
+        .client
+        .get(thumbnail_uri(&attachment, "small"))
+        .header(reqwest::header::AUTHORIZATION, &token)
+        .send()
+        .await
+        .unwrap();
+    let medium = app
+        .client
+        .get(thumbnail_uri(&attachment, "medium"))
+        .header(reqwest::header::AUTHORIZATION, &token)
+        .send()
+        .await
+        .unwrap();
+    let missing = app
+        .client
+        .get(thumbnail_uri(&document, "small"))
+        .header(reqwest::header::AUTHORIZATION, &token)
+        .send()
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(attachment["hasThumbnails"], true);
+    assert_eq!(document["hasThumbnails"], false);
+    assert_eq!(missing.status().as_u16(), 404);
+
+    assert_eq!(small.status().as_u16(), 200);
+    assert_eq!(small.headers()["content-type"], "image/jpeg");
+    assert_eq!(
+        small.headers()["cache-control"],
+        "private, max-age=31536000, immutable"
+    );
+    let etag = small.headers()["etag"].to_str().unwrap().to_owned();
+    let thumbnail = image::load_from_memory(&small.bytes().await.unwrap()).unwrap();
+    assert_eq!((thumbnail.width(), thumbnail.height()), (128, 64));
+    let thumbnail = image::load_from_memory(&medium.bytes().await.unwrap()).unwrap();
+    assert_eq!((thumbnail.width(), thumbnail.height()), (512, 256));
+
+    let revalidated = app
+        .client
+        .get(thumbnail_uri(&attachment, "small"))
+        .header(reqwest::header::AUTHORIZATION, &token)
+        .header(reqwest::header::IF_NONE_MATCH, &etag)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(revalidated.status().as_u16(), 304);
+}
@@ -0,0 +1,119 @@
+use crate::helpers::{device_body, spawn_app, DEVICE_BOARD};
+
+#[tokio::test]
+async fn board_is_created_and_listed() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["read:devices", "manage:boards"]);
+    let body = serde_json::json!({
+        "name": " main-board ",
+        "revision": "B",
+        "vendor": "ACME",
+        "description": "the second spin",
+    });
+
+    // Act
+    let created = app.post("/api/v1/boards", &body, Some(&token)).await;
+    let duplicated = app.post("/api/v1/boards", &body, Some(&token)).await;
+    let resp = app.get("/api/v1/boards", Some(&token)).await;
+
+    // Assert
+    assert_eq!(created.status().as_u16(), 201);
+    let board: serde_json::Value = created.json().await.unwrap();
+    assert_eq!(board["name"], "main-board");
+    assert_eq!(board["fullName"], "main-board rev B");
+    assert_eq!(duplicated.status().as_u16(), 409);
+
+    let body: serde_json::Value = resp.json().await.unwrap();
+    let names: Vec<_> = body["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|b| b["fullName"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, [DEVICE_BOARD, "main-board rev B"]);
+}
+
+#[tokio::test]
+async fn renaming_a_board_renames_its_devices() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["read:devices", "manage:boards"]);
+    let device = app.create_device(&device_body("phone")).await;
+    let boards: serde_json::Value = app
+        .get("/api/v1/boards", Some(&token))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let board_id = boards["items"][0]["id"].as_str().unwrap();
+
+    // Act
+    let updated = app
+        .put(
+            &format!("/api/v1/boards/{board_id}"),
+            &serde_json::json!({ "name": "main-board", "revision": "A" }),
+            Some(&token),
+        )
+        .await;
+    let deleted = app
+        .delete(&format!("/api/v1/boards/{board_id}"), Some(&token))
+        .await;
+
+    // Assert
+    assert_eq!(updated.status().as_u16(), 200);
+    let resp = app
+        .get(
+            &format!("/api/v1/devices/{}", device["id"].as_str().unwrap()),
+            Some(&token),
+        )
+        .await;
+    let device: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(device["board"], "main-board rev A");
+    assert_eq!(deleted.status().as_u16(), 409);
+    let history: serde_json::Value = app
+        .get(
+            &format!("/api/v1/devices/{}/history", device["id"].as_str().unwrap()),
+            Some(&token),
+        )
+        .await
+        .json()
+        .await
+        .unwrap();
+    let renamed = &history["items"][1];
+    assert_eq!(renamed["action"], "update");
+    assert_eq!(renamed["changedBy"], app.test_user.id.to_string());
+    assert_eq!(
+        renamed["changes"],
+        serde_json::json!({ "board": { "old": "main-board", "new": "main-board rev A" } })
+    );
+}
+
+#[tokio::test]
+async fn device_with_unknown_board_is_rejected() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["create:device"]);
+    let mut body = device_body("phone");
+    body["board"] = "Main-Board".into();
+
+    // Act
+    let resp = app.post("/api/v1/devices", &body, Some(&token)).await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn board_with_an_overlong_vendor_is_rejected() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["manage:boards"]);
+    let body = serde_json::json!({ "name": "io-board", "vendor": "v".repeat(257) });
+
+    // Act
+    let resp = app.post("/api/v1/boards", &body, Some(&token)).await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 400);
+}
@@ -0,0 +1,126 @@
+use crate::helpers::{device_body, spawn_app};
+
+const COMMENT_PERMISSIONS: [&str; 2] = ["read:devices", "comment:device"];
+
+#[tokio::test]
+async fn comments_record_the_mentions_of_existing_users() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&COMMENT_PERMISSIONS);
+    let device = app.create_device(&device_body("phone")).await;
+    let uri = format!(
+        "/api/v1/devices/{}/comments",
+        device["id"].as_str().unwrap()
+    );
+    let username = &app.test_user.username;
+    let body = format!("@{username} the screen is cracked, cc @nobody and vendor@example.com");
+
+    // Act
+    let resp = app
+        .post(&uri, &serde_json::json!({ "body": body }), Some(&token))
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 201);
+    let comment: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(comment["body"], body);
+    assert_eq!(comment["authorId"], app.test_user.id.to_string());
+    assert_eq!(comment["authorUsername"], username.as_str());
+    assert_eq!(comment["updatedAt"], serde_json::Value::Null);
+    assert_eq!(
+        comment["mentions"],
+        serde_json::json!([{ "userId": app.test_user.id, "username": username }])
+    );
+
+    let resp = app.get(&uri, Some(&token)).await;
+    let comments: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(comments["items"][0]["mentions"], comment["mentions"]);
+
+    let resp = app.get("/api/v1/mentions", Some(&token)).await;
+    let mentions: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(mentions["items"][0]["commentId"], comment["id"]);
+    assert_eq!(mentions["items"][0]["deviceId"], device["id"]);
+}
+
+#[tokio::test]
+async fn editing_a_comment_updates_its_mentions() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&COMMENT_PERMISSIONS);
+    let device = app.create_device(&device_body("phone")).await;
+    let uri = format!(
+        "/api/v1/devices/{}/comments",
+        device["id"].as_str().unwrap()
+    );
+    let body = format!("ask @{}", app.test_user.username);
+    let resp = app
+        .post(&uri, &serde_json::json!({ "body": body }), Some(&token))
+        .await;
+    let comment: serde_json::Value = resp.json().await.unwrap();
+    let comment_uri = format!("{uri}/{}", comment["id"].as_str().unwrap());
+
+    // Act
+    let resp = app
+        .put(
+            &comment_uri,
+            &serde_json::json!({ "body": "never mind" }),
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 200);
+    let edited: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(edited["body"], "never mind");
+    assert_eq!(edited["mentions"], serde_json::json!([]));
+    assert_ne!(edited["updatedAt"], serde_json::Value::Null);
+
+    let resp = app.get("/api/v1/mentions", Some(&token)).await;
+    let mentions: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(mentions["items"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn only_the_author_edits_and_moderators_delete_comments() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&COMMENT_PERMISSIONS);
+    let someone_else = app.generate_token_for(uuid::Uuid::new_v4(), &COMMENT_PERMISSIONS);
+    let moderator = app.generate_token_for(
+        uuid::Uuid::new_v4(),
+        &["comment:device", "moderate:comments"],
+    );
+    let device = app.create_device(&device_body("phone")).await;
+    let uri = format!(
+        "/api/v1/devices/{}/comments",
+        device["id"].as_str().unwrap()
+    );
+    let resp = app
+        .post(&uri, &serde_json::json!({ "body": "mine" }), Some(&token))
+        .await;
+    let comment: serde_json::Value = resp.json().await.unwrap();
+    let comment_uri = format!("{uri}/{}", comment["id"].as_str().unwrap());
+
+    // Act
+    let edited = app
+        .put(
+            &comment_uri,
+            &serde_json::json!({ "body": "theirs" }),
+            Some(&someone_else),
+        )
+        .await;
+    let deleted = app.delete(&comment_uri, Some(&someone_else)).await;
+    let moderated = app.delete(&comment_uri, Some(&moderator)).await;
+    let blank = app
+        .post(&uri, &serde_json::json!({ "body": "  " }), Some(&token))
+        .await;
+
+    // Assert
+    assert_eq!(edited.status().as_u16(), 403);
+    assert_eq!(deleted.status().as_u16(), 403);
+    assert_eq!(moderated.status().as_u16(), 204);
+    assert_eq!(blank.status().as_u16(), 400);
+    let resp = app.get(&uri, Some(&token)).await;
+    let comments: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(comments["items"], serde_json::json!([]));
+}
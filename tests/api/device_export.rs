@@ -0,0 +1,85 @@
+use crate::helpers::{device_body, spawn_app};
+
+#[tokio::test]
+async fn export_devices_as_csv_applies_filters() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["read:devices"]);
+    for (name, hw_phase) in [("phone", "EVT"), ("tablet", "DVT"), ("watch, v2", "EVT")] {
+        let mut body = device_body(name);
+        body["hwPhase"] = hw_phase.into();
+        app.create_device(&body).await;
+    }
+
+    // Act
+    let resp = app
+        .get(
+            "/api/v1/devices/export?format=csv&hwPhase=EVT&sortBy=name",
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 200);
+    assert_eq!(
+        resp.headers()[reqwest::header::CONTENT_TYPE],
+        "text/csv; charset=utf-8"
+    );
+    let content = resp.text().await.unwrap();
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    assert_eq!(&reader.headers().unwrap()[1], "name");
+    let names: Vec<_> = reader
+        .records()
+        .map(|record| record.unwrap()[1].to_owned())
+        .collect();
+    assert_eq!(names, vec!["phone", "watch, v2"]);
+}
+
+#[tokio::test]
+async fn export_devices_as_json_lines_by_accept_header() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["read:devices"]);
+    for i in 0..3 {
+        app.create_device(&device_body(&format!("device-{i}")))
+            .await;
+    }
+
+    // Act
+    let resp = app
+        .client
+        .get(format!("{}/api/v1/devices/export", app.address))
+        .header(reqwest::header::AUTHORIZATION, &token)
+        .header(reqwest::header::ACCEPT, "application/x-ndjson")
+        .send()
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 200);
+    assert_eq!(
+        resp.headers()[reqwest::header::CONTENT_TYPE],
+        "application/x-ndjson"
+    );
+    let content = resp.text().await.unwrap();
+    let names: Vec<_> = content
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["name"].clone())
+        .collect();
+    assert_eq!(names, vec!["device-0", "device-1", "device-2"]);
+}
+
+#[tokio::test]
+async fn export_devices_with_unknown_format_is_rejected() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["read:devices"]);
+
+    // Act
+    let resp = app
+        .get("/api/v1/devices/export?format=xml", Some(&token))
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 400);
+}
@@ -0,0 +1,180 @@
+use crate::helpers::{device_body, spawn_app, DEVICE_OWNER};
+
+const IMPORT_PERMISSIONS: [&str; 2] = ["read:devices", "create:device"];
+
+#[tokio::test]
+async fn import_devices_dry_run_reports_errors_without_writing() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&IMPORT_PERMISSIONS);
+    let owner = DEVICE_OWNER;
+    let csv = format!(
+        "name,board,sn,received_date,hw_phase,owner\n\
+         phone,main-board,SN-1,2023-08-01,EVT,{owner}\n\
+         ,main-board,SN-2,not-a-date,EVT,{owner}\n"
+    );
+
+    // Act
+    let resp = app
+        .upload("/api/v1/devices/import?dryRun=true", &csv, Some(&token))
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 200);
+    let report: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(report["dryRun"], true);
+    assert_eq!(report["totalRows"], 2);
+    assert_eq!(report["imported"], 0);
+    assert_eq!(
+        report["errors"],
+        serde_json::json!([
+            { "row": 3, "column": "name", "message": "is required" },
+            {
+                "row": 3,
+                "column": "received_date",
+                "message": "`not-a-date` is not a date, use YYYY-MM-DD",
+            },
+        ])
+    );
+
+    let resp = app.get("/api/v1/devices", Some(&token)).await;
+    let devices: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(devices["total"], 0);
+}
+
+#[tokio::test]
+async fn import_devices_with_an_invalid_row_writes_nothing() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&IMPORT_PERMISSIONS);
+    let unknown_owner = uuid::Uuid::new_v4();
+    let csv = format!(
+        "name,owner\n\
+         phone,{unknown_owner}\n\
+         tablet,someone\n"
+    );
+
+    // Act
+    let resp = app
+        .upload("/api/v1/devices/import", &csv, Some(&token))
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 422);
+    let report: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(
+        report["errors"],
+        serde_json::json!([
+            { "row": 2, "message": format!("unknown owner `{unknown_owner}`") },
+            { "row": 3, "column": "owner", "message": "is not a valid id" },
+        ])
+    );
+
+    let resp = app.get("/api/v1/devices", Some(&token)).await;
+    let devices: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(devices["total"], 0);
+}
+
+#[tokio::test]
+async fn import_devices_dry_run_checks_rows_against_the_database() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&IMPORT_PERMISSIONS);
+    let owner = DEVICE_OWNER;
+    let mut taken = device_body("taken");
+    taken["sn"] = "SN-TAKEN".into();
+    app.create_device(&taken).await;
+    let unknown_owner = uuid::Uuid::new_v4();
+    let csv = format!(
+        "name,board,sn,hw_phase,owner,note\n\
+         phone,main-board,SN-1,EVT,{owner},\"first line\nsecond line\"\n\
+         tablet,main-board,SN-1,EVT,{owner},\n\
+         watch,main-board,SN-TAKEN,EVT,{owner},\n\
+         laptop,unknown-board,SN-2,EVT,{owner},\n\
+         camera,main-board,SN-3,XYZ,{owner},\n\
+         probe,,SN-4,,{unknown_owner},\n"
+    );
+
+    // Act
+    let dry_run = app
+        .upload("/api/v1/devices/import?dryRun=true", &csv, Some(&token))
+        .await;
+    let commit = app
+        .upload("/api/v1/devices/import", &csv, Some(&token))
+        .await;
+
+    // Assert
+    let expected = serde_json::json!([
+        { "row": 4, "message": "a device with the serial number `SN-1` already exists" },
+        { "row": 5, "message": "a device with the serial number `SN-TAKEN` already exists" },
+        { "row": 6, "message": "unknown board `unknown-board`" },
+        { "row": 7, "message": "unknown hardware phase `XYZ`" },
+        { "row": 8, "message": format!("unknown owner `{unknown_owner}`") },
+    ]);
+    assert_eq!(dry_run.status().as_u16(), 200);
+    let report: serde_json::Value = dry_run.json().await.unwrap();
+    assert_eq!(report["totalRows"], 6);
+    assert_eq!(report["imported"], 0);
+    assert_eq!(report["errors"], expected);
+
+    assert_eq!(commit.status().as_u16(), 422);
+    let report: serde_json::Value = commit.json().await.unwrap();
+    assert_eq!(report["errors"], expected);
+
+    let resp = app.get("/api/v1/devices", Some(&token)).await;
+    let devices: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(devices["total"], 1);
+}
+
+#[tokio::test]
+async fn import_devices_creates_every_row() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&IMPORT_PERMISSIONS);
+    let owner = DEVICE_OWNER;
+    let csv = format!(
+        "Name,Board,SN,Barcode,Received_Date,HW_Phase,Note,Owner\n\
+         phone,main-board,SN-1,BC-1,2023-08-01,EVT,first batch,{owner}\n\
+         tablet,main-board,SN-2,BC-2,2023-08-02T08:00:00Z,DVT,,{owner}\n"
+    );
+
+    // Act
+    let resp = app
+        .upload("/api/v1/devices/import", &csv, Some(&token))
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 201);
+    let report: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(report["imported"], 2);
+
+    let resp = app
+        .get(&format!("/api/v1/devices?ownerId={owner}"), Some(&token))
+        .await;
+    let devices: serde_json::Value = resp.json().await.unwrap();
+    let items = devices["items"].as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0]["name"], "phone");
+    assert_eq!(items[0]["receivedDate"], "2023-08-01T00:00:00Z");
+    assert_eq!(items[1]["hwPhase"], "DVT");
+    assert_eq!(items[1]["note"], serde_json::Value::Null);
+}
+
+#[tokio::test]
+async fn import_devices_without_a_file_is_rejected() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&IMPORT_PERMISSIONS);
+
+    // Act
+    let resp = app
+        .post(
+            "/api/v1/devices/import",
+            &serde_json::json!({}),
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 400);
+}
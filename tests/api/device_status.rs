@@ -0,0 +1,84 @@
+use crate::helpers::{device_body, spawn_app};
+
+const STATUS_PERMISSIONS: [&str; 2] = ["read:devices", "update:device"];
+
+#[tokio::test]
+async fn device_moves_through_its_lifecycle() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&STATUS_PERMISSIONS);
+    let device = app.create_device(&device_body("phone")).await;
+    let id = device["id"].as_str().unwrap();
+    assert_eq!(device["status"], "received");
+
+    // Act
+    let mut changed_at = Vec::new();
+    for (action, expected) in [
+        ("stock", "in_inventory"),
+        ("use", "in_use"),
+        ("repair", "in_repair"),
+        ("scrap", "scrapped"),
+    ] {
+        let resp = app
+            .post(
+                &format!("/api/v1/devices/{id}/{action}"),
+                &serde_json::json!({}),
+                Some(&token),
+            )
+            .await;
+        assert_eq!(resp.status().as_u16(), 200, "{action}");
+        let device: serde_json::Value = resp.json().await.unwrap();
+        assert_eq!(device["status"], expected);
+        changed_at.push(device["statusChangedAt"].as_str().unwrap().to_owned());
+    }
+
+    // Assert
+    let resp = app
+        .get(
+            &format!("/api/v1/devices/{id}/status-history"),
+            Some(&token),
+        )
+        .await;
+    let history: serde_json::Value = resp.json().await.unwrap();
+    let history = history["items"].as_array().unwrap();
+    assert_eq!(history.len(), 4);
+    assert_eq!(history[0]["fromStatus"], "received");
+    assert_eq!(history[3]["toStatus"], "scrapped");
+    assert_eq!(history[3]["changedBy"], app.test_user.id.to_string());
+    // The changes keep the sub-seconds of the time the device was changed
+    let history_changed_at: Vec<_> = history
+        .iter()
+        .map(|change| change["changedAt"].as_str().unwrap())
+        .collect();
+    assert_eq!(history_changed_at, changed_at);
+    let changed_at: Vec<_> = changed_at
+        .iter()
+        .map(|at| at.parse::<chrono::DateTime<chrono::Utc>>().unwrap())
+        .collect();
+    assert!(changed_at.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[tokio::test]
+async fn invalid_transition_is_a_conflict() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&STATUS_PERMISSIONS);
+    let device = app.create_device(&device_body("phone")).await;
+
+    // Act
+    let resp = app
+        .post(
+            &format!("/api/v1/devices/{}/scrap", device["id"].as_str().unwrap()),
+            &serde_json::json!({}),
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 409);
+    let error: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(
+        error["errorMessage"],
+        "a device can't move from received to scrapped"
+    );
+}
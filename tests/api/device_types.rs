@@ -0,0 +1,224 @@
+use crate::helpers::{device_body, spawn_app, TestApp};
+
+#[tokio::test]
+async fn device_type_is_created_and_renamed() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["read:devices", "manage:device-types"]);
+
+    // Act
+    let created = app
+        .post(
+            "/api/v1/device-types",
+            &serde_json::json!({ "name": "phone" }),
+            Some(&token),
+        )
+        .await;
+    let duplicated = app
+        .post(
+            "/api/v1/device-types",
+            &serde_json::json!({ "name": "phone" }),
+            Some(&token),
+        )
+        .await;
+    let created: serde_json::Value = created.json().await.unwrap();
+    let id = created["id"].as_str().unwrap();
+    let renamed = app
+        .put(
+            &format!("/api/v1/device-types/{id}"),
+            &serde_json::json!({ "name": "handset" }),
+            Some(&token),
+        )
+        .await;
+    let resp = app.get("/api/v1/device-types", Some(&token)).await;
+
+    // Assert
+    assert_eq!(duplicated.status().as_u16(), 409);
+    assert_eq!(renamed.status().as_u16(), 200);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["items"].as_array().unwrap().len(), 1);
+    assert_eq!(body["items"][0]["name"], "handset");
+}
+
+#[tokio::test]
+async fn device_type_in_use_can_not_be_deleted() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["read:devices", "manage:device-types"]);
+    let device_type: serde_json::Value = app
+        .post(
+            "/api/v1/device-types",
+            &serde_json::json!({ "name": "phone" }),
+            Some(&token),
+        )
+        .await
+        .json()
+        .await
+        .unwrap();
+    let id = device_type["id"].as_str().unwrap();
+    let mut body = device_body("phone");
+    body["deviceTypeId"] = id.into();
+    app.create_device(&body).await;
+
+    // Act
+    let resp = app
+        .delete(&format!("/api/v1/device-types/{id}"), Some(&token))
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 409);
+    let error: serde_json::Value = resp.json().await.unwrap();
+    assert!(error["errorMessage"]
+        .as_str()
+        .unwrap()
+        .contains("still used by 1 device"));
+}
+
+#[tokio::test]
+async fn device_with_unknown_device_type_is_rejected() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["create:device"]);
+    let mut body = device_body("phone");
+    body["deviceTypeId"] = uuid::Uuid::new_v4().to_string().into();
+
+    // Act
+    let resp = app.post("/api/v1/devices", &body, Some(&token)).await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 400);
+}
+
+/// Create a phone type whose devices require an IMEI
+async fn create_phone_type(app: &TestApp, token: &str) -> String {
+    let device_type: serde_json::Value = app
+        .post(
+            "/api/v1/device-types",
+            &serde_json::json!({
+                "name": "phone",
+                "attributeSchema": {
+                    "type": "object",
+                    "properties": {
+                        "imei": { "type": "string", "pattern": "^[0-9]{15}$" },
+                        "ramGb": { "type": "integer", "minimum": 1 },
+                    },
+                    "required": ["imei"],
+                },
+            }),
+            Some(token),
+        )
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    device_type["id"].as_str().unwrap().to_owned()
+}
+
+#[tokio::test]
+async fn device_attributes_are_validated_against_the_type() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["create:device", "manage:device-types"]);
+    let type_id = create_phone_type(&app, &token).await;
+    let mut valid = device_body("phone");
+    valid["deviceTypeId"] = type_id.clone().into();
+    valid["attributes"] = serde_json::json!({ "imei": "490154203237518", "ramGb": 8 });
+    let mut invalid = device_body("tablet");
+    invalid["deviceTypeId"] = type_id.into();
+    invalid["attributes"] = serde_json::json!({ "ramGb": 0 });
+    let mut untyped = device_body("charger");
+    untyped["attributes"] = serde_json::json!({ "wattage": 65 });
+
+    // Act
+    let valid = app.post("/api/v1/devices", &valid, Some(&token)).await;
+    let invalid = app.post("/api/v1/devices", &invalid, Some(&token)).await;
+    let untyped = app.post("/api/v1/devices", &untyped, Some(&token)).await;
+
+    // Assert
+    assert_eq!(valid.status().as_u16(), 201);
+    let device: serde_json::Value = valid.json().await.unwrap();
+    assert_eq!(device["attributes"]["ramGb"], 8);
+
+    assert_eq!(invalid.status().as_u16(), 400);
+    let error: serde_json::Value = invalid.json().await.unwrap();
+    let message = error["errorMessage"].as_str().unwrap();
+    assert!(message.contains("imei"), "{message}");
+    assert!(message.contains("/ramGb"), "{message}");
+
+    assert_eq!(untyped.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn devices_are_filtered_by_attributes() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["read:devices", "create:device", "manage:device-types"]);
+    let type_id = create_phone_type(&app, &token).await;
+    for (name, imei, ram) in [
+        ("small", "490154203237518", 4),
+        ("large", "356938035643809", 8),
+    ] {
+        let mut body = device_body(name);
+        body["deviceTypeId"] = type_id.clone().into();
+        body["attributes"] = serde_json::json!({ "imei": imei, "ramGb": ram });
+        app.create_device(&body).await;
+    }
+
+    // Act
+    let resp = app
+        .get(
+            "/api/v1/devices?attributes=%7B%22ramGb%22%3A8%7D",
+            Some(&token),
+        )
+        .await;
+    let malformed = app
+        .get("/api/v1/devices?attributes=ramGb", Some(&token))
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 200);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    let items = body["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["name"], "large");
+    assert_eq!(malformed.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn schema_change_must_keep_existing_devices_valid() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["manage:device-types"]);
+    let type_id = create_phone_type(&app, &token).await;
+    let mut body = device_body("phone");
+    body["deviceTypeId"] = type_id.clone().into();
+    body["attributes"] = serde_json::json!({ "imei": "490154203237518" });
+    app.create_device(&body).await;
+    let schema = |required: &str| {
+        serde_json::json!({
+            "name": "phone",
+            "attributeSchema": { "type": "object", "required": [required] },
+        })
+    };
+
+    // Act
+    let breaking = app
+        .put(
+            &format!("/api/v1/device-types/{type_id}"),
+            &schema("ramGb"),
+            Some(&token),
+        )
+        .await;
+    let compatible = app
+        .put(
+            &format!("/api/v1/device-types/{type_id}"),
+            &schema("imei"),
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(breaking.status().as_u16(), 409);
+    assert_eq!(compatible.status().as_u16(), 200);
+}
@@ -0,0 +1,708 @@
+use crate::helpers::{device_body, spawn_app};
+
+const ALL_DEVICE_PERMISSIONS: [&str; 4] = [
+    "read:devices",
+    "create:device",
+    "update:device",
+    "delete:devices",
+];
+
+#[tokio::test]
+async fn list_devices_without_token_is_rejected() {
+    // Arrange
+    let app = spawn_app().await;
+
+    // Act
+    let resp = app.get("/api/v1/devices", None).await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn create_device_without_permission_is_forbidden() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["read:devices"]);
+
+    // Act
+    let resp = app
+        .post("/api/v1/devices", &device_body("phone"), Some(&token))
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 403);
+}
+
+#[tokio::test]
+async fn device_crud_works() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&ALL_DEVICE_PERMISSIONS);
+
+    // Act - create
+    let resp = app
+        .post("/api/v1/devices", &device_body("phone"), Some(&token))
+        .await;
+    assert_eq!(resp.status().as_u16(), 201);
+    let created: serde_json::Value = resp.json().await.unwrap();
+    let id = created["id"].as_str().unwrap().to_owned();
+    assert_eq!(created["name"], "phone");
+
+    // Act - get and list
+    let resp = app
+        .get(&format!("/api/v1/devices/{id}"), Some(&token))
+        .await;
+    assert_eq!(resp.status().as_u16(), 200);
+    let etag = resp.headers()["etag"].to_str().unwrap().to_owned();
+    assert_eq!(etag, "\"1\"");
+
+    let resp = app.get("/api/v1/devices", Some(&token)).await;
+    let devices: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(devices["items"].as_array().unwrap().len(), 1);
+    assert_eq!(devices["total"], 1);
+
+    // Act - update
+    let mut body = device_body("tablet");
+    body["note"] = "updated".into();
+    let resp = app
+        .put_if_match(&format!("/api/v1/devices/{id}"), &body, &etag, &token)
+        .await;
+    assert_eq!(resp.status().as_u16(), 200);
+    let etag = resp.headers()["etag"].to_str().unwrap().to_owned();
+    assert_eq!(etag, "\"2\"");
+    let updated: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(updated["name"], "tablet");
+    assert_eq!(updated["note"], "updated");
+
+    // Act - delete
+    let resp = app
+        .delete_if_match(&format!("/api/v1/devices/{id}"), &etag, &token)
+        .await;
+    assert_eq!(resp.status().as_u16(), 204);
+
+    // Assert
+    let resp = app
+        .get(&format!("/api/v1/devices/{id}"), Some(&token))
+        .await;
+    assert_eq!(resp.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn changes_based_on_a_stale_version_are_rejected() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&ALL_DEVICE_PERMISSIONS);
+    let device = app.create_device(&device_body("phone")).await;
+    let uri = format!("/api/v1/devices/{}", device["id"].as_str().unwrap());
+    let resp = app.get(&uri, Some(&token)).await;
+    let stale = resp.headers()["etag"].to_str().unwrap().to_owned();
+    let mut first = device.clone();
+    first["note"] = "first".into();
+    let mut second = device.clone();
+    second["note"] = "second".into();
+
+    // Act
+    let resp = app.put_if_match(&uri, &first, &stale, &token).await;
+    assert_eq!(resp.status().as_u16(), 200);
+    let current = resp.headers()["etag"].to_str().unwrap().to_owned();
+    let conflict = app.put_if_match(&uri, &second, &stale, &token).await;
+    let missing = app.put(&uri, &second, Some(&token)).await;
+
+    // Assert
+    assert_eq!(conflict.status().as_u16(), 412);
+    assert_eq!(missing.status().as_u16(), 428);
+    let resp = app.get(&uri, Some(&token)).await;
+    assert_eq!(resp.headers()["etag"].to_str().unwrap(), current);
+    let unchanged: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(unchanged["note"], "first");
+
+    let resp = app
+        .put_if_match(&uri, &second, &format!("\"9\", {current}"), &token)
+        .await;
+    assert_eq!(resp.status().as_u16(), 200);
+    let current = resp.headers()["etag"].to_str().unwrap().to_owned();
+
+    let resp = app.delete_if_match(&uri, &stale, &token).await;
+    assert_eq!(resp.status().as_u16(), 412);
+    let resp = app.delete(&uri, Some(&token)).await;
+    assert_eq!(resp.status().as_u16(), 428);
+    let resp = app
+        .delete_if_match(&uri, &format!("W/{current}"), &token)
+        .await;
+    assert_eq!(resp.status().as_u16(), 412);
+    let resp = app.delete_if_match(&uri, &current, &token).await;
+    assert_eq!(resp.status().as_u16(), 204);
+}
+
+#[tokio::test]
+async fn list_devices_filters_and_paginates_with_cursor() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&ALL_DEVICE_PERMISSIONS);
+    for i in 0..5 {
+        let mut body = device_body(&format!("device-{i}"));
+        body["hwPhase"] = if i % 2 == 0 { "DVT" } else { "EVT" }.into();
+        body["receivedDate"] = format!("2023-0{}-01T00:00:00Z", i + 1).into();
+        let resp = app.post("/api/v1/devices", &body, Some(&token)).await;
+        assert_eq!(resp.status().as_u16(), 201);
+    }
+
+    // Act - filter
+    let resp = app
+        .get(
+            "/api/v1/devices?hwPhase=DVT&receivedFrom=2023-02-01T00:00:00Z",
+            Some(&token),
+        )
+        .await;
+    let page: serde_json::Value = resp.json().await.unwrap();
+
+    // Assert
+    assert_eq!(page["total"], 2);
+    let names: Vec<_> = page["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| d["name"].as_str().unwrap().to_owned())
+        .collect();
+    assert_eq!(names, vec!["device-2", "device-4"]);
+
+    // Act - walk every page in descending order with the cursor
+    let mut names = vec![];
+    let mut uri = "/api/v1/devices?sortBy=receivedDate&order=desc&limit=2".to_owned();
+    loop {
+        let resp = app.get(&uri, Some(&token)).await;
+        assert_eq!(resp.status().as_u16(), 200);
+        let page: serde_json::Value = resp.json().await.unwrap();
+        assert_eq!(page["total"], 5);
+        for device in page["items"].as_array().unwrap() {
+            names.push(device["name"].as_str().unwrap().to_owned());
+        }
+        match page["nextCursor"].as_str() {
+            Some(cursor) => {
+                uri = format!(
+                    "/api/v1/devices?sortBy=receivedDate&order=desc&limit=2&cursor={cursor}"
+                )
+            }
+            None => break,
+        }
+    }
+
+    // Assert
+    assert_eq!(
+        names,
+        vec!["device-4", "device-3", "device-2", "device-1", "device-0"]
+    );
+}
+
+#[tokio::test]
+async fn list_devices_with_invalid_limit_is_rejected() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["read:devices"]);
+
+    // Act
+    let resp = app.get("/api/v1/devices?limit=0", Some(&token)).await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn device_fields_longer_than_their_columns_are_rejected() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&ALL_DEVICE_PERMISSIONS);
+    let device = app.create_device(&device_body("phone")).await;
+    let uri = format!("/api/v1/devices/{}", device["id"].as_str().unwrap());
+    let mut long_name = device_body(&"n".repeat(1025));
+    long_name["sn"] = "SN-1".into();
+    let mut long_sn = device_body("phone");
+    long_sn["sn"] = "s".repeat(129).into();
+    let mut blank_name = device_body("  ");
+    blank_name["sn"] = "SN-2".into();
+
+    // Act
+    let created = app.post("/api/v1/devices", &long_name, Some(&token)).await;
+    let blank = app.post("/api/v1/devices", &blank_name, Some(&token)).await;
+    let updated = app.put_if_match(&uri, &long_sn, "*", &token).await;
+
+    // Assert
+    assert_eq!(created.status().as_u16(), 400);
+    assert_eq!(blank.status().as_u16(), 400);
+    assert_eq!(updated.status().as_u16(), 400);
+    let error: serde_json::Value = updated.json().await.unwrap();
+    assert_eq!(
+        error["errorMessage"],
+        "invalid request: sn must be at most 128 characters"
+    );
+}
+
+#[tokio::test]
+async fn search_devices_ranks_and_highlights_matches() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&ALL_DEVICE_PERMISSIONS);
+    let devices = [
+        ("thermal chamber board", "SN-0001-ALPHA", "burnt connector"),
+        (
+            "customer demo phone",
+            "SN-0002-BRAVO",
+            "thermal paste replaced",
+        ),
+        ("spare charger", "SN-0003-CHARLIE", "nothing special"),
+    ];
+    for (name, sn, note) in devices {
+        let mut body = device_body(name);
+        body["sn"] = sn.into();
+        body["note"] = note.into();
+        let resp = app.post("/api/v1/devices", &body, Some(&token)).await;
+        assert_eq!(resp.status().as_u16(), 201);
+    }
+
+    // Act - partial word
+    let resp = app
+        .get("/api/v1/devices/search?q=therm", Some(&token))
+        .await;
+    assert_eq!(resp.status().as_u16(), 200);
+    let result: serde_json::Value = resp.json().await.unwrap();
+
+    // Assert - the name match outranks the note match
+    let items = result["items"].as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0]["device"]["name"], "thermal chamber board");
+    assert_eq!(
+        items[0]["highlights"]["name"],
+        "<mark>thermal</mark> chamber board"
+    );
+    assert_eq!(
+        items[1]["highlights"]["note"],
+        "<mark>thermal</mark> paste replaced"
+    );
+
+    // Act - serial number with a typo
+    let resp = app
+        .get("/api/v1/devices/search?q=SN-0003-CHARLIF", Some(&token))
+        .await;
+    let result: serde_json::Value = resp.json().await.unwrap();
+
+    // Assert
+    let items = result["items"].as_array().unwrap();
+    assert_eq!(items[0]["device"]["name"], "spare charger");
+    assert!(items[0]["highlights"]["sn"]
+        .as_str()
+        .unwrap()
+        .contains("<mark>"));
+}
+
+#[tokio::test]
+async fn device_history_records_every_mutation() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&ALL_DEVICE_PERMISSIONS);
+    let device = app.create_device(&device_body("phone")).await;
+    let id = device["id"].as_str().unwrap();
+
+    // Act
+    let mut body = device.clone();
+    body["hwPhase"] = "DVT".into();
+    body["note"] = "moved to DVT".into();
+    let resp = app
+        .put_if_match(&format!("/api/v1/devices/{id}"), &body, "*", &token)
+        .await;
+    assert_eq!(resp.status().as_u16(), 200);
+    let resp = app
+        .delete_if_match(&format!("/api/v1/devices/{id}"), "*", &token)
+        .await;
+    assert_eq!(resp.status().as_u16(), 204);
+
+    // Assert - the history outlives the device
+    let resp = app
+        .get(&format!("/api/v1/devices/{id}/history"), Some(&token))
+        .await;
+    assert_eq!(resp.status().as_u16(), 200);
+    let history: serde_json::Value = resp.json().await.unwrap();
+    let history = history["items"].as_array().unwrap();
+    let actions: Vec<_> = history
+        .iter()
+        .map(|entry| entry["action"].as_str().unwrap())
+        .collect();
+    assert_eq!(actions, vec!["create", "update", "delete"]);
+    // The entries keep the sub-seconds of their time, in the order they happened
+    let changed_at: Vec<_> = history
+        .iter()
+        .map(|entry| {
+            entry["changedAt"]
+                .as_str()
+                .unwrap()
+                .parse::<chrono::DateTime<chrono::Utc>>()
+                .unwrap()
+        })
+        .collect();
+    assert!(changed_at.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(history[1]["changedBy"], app.test_user.id.to_string());
+    assert_eq!(
+        history[1]["changes"],
+        serde_json::json!({
+            "hwPhase": { "old": "EVT", "new": "DVT" },
+            "note": { "old": null, "new": "moved to DVT" },
+        })
+    );
+    assert_eq!(history[2]["changes"]["name"]["old"], "phone");
+}
+
+#[tokio::test]
+async fn deleted_devices_can_be_restored_from_the_trash() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&ALL_DEVICE_PERMISSIONS);
+    let device = app.create_device(&device_body("phone")).await;
+    let id = device["id"].as_str().unwrap();
+
+    // Act
+    let resp = app
+        .delete_if_match(&format!("/api/v1/devices/{id}"), "*", &token)
+        .await;
+    assert_eq!(resp.status().as_u16(), 204);
+
+    // Assert - the device only shows up in the trash
+    let resp = app.get("/api/v1/devices", Some(&token)).await;
+    let devices: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(devices["total"], 0);
+    let resp = app
+        .get(
+            &format!("/api/v1/devices/by-sn/{}", device["sn"].as_str().unwrap()),
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 404);
+    let resp = app
+        .delete_if_match(&format!("/api/v1/devices/{id}"), "*", &token)
+        .await;
+    assert_eq!(resp.status().as_u16(), 404);
+
+    let resp = app.get("/api/v1/devices/trash", Some(&token)).await;
+    assert_eq!(resp.status().as_u16(), 200);
+    let trash: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(trash["items"][0]["id"], id);
+    assert_eq!(trash["items"][0]["name"], "phone");
+    assert_eq!(trash["items"][0]["deletedBy"], app.test_user.id.to_string());
+
+    // Assert - a restored device is back with its history
+    let resp = app
+        .post(
+            &format!("/api/v1/devices/{id}/restore"),
+            &serde_json::json!({}),
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 200);
+    let restored: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(restored, device);
+    let resp = app
+        .post(
+            &format!("/api/v1/devices/{id}/restore"),
+            &serde_json::json!({}),
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 404);
+
+    let resp = app
+        .get(&format!("/api/v1/devices/{id}/history"), Some(&token))
+        .await;
+    let history: serde_json::Value = resp.json().await.unwrap();
+    let actions: Vec<_> = history["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["action"].as_str().unwrap())
+        .collect();
+    assert_eq!(actions, vec!["create", "delete", "restore"]);
+}
+
+#[tokio::test]
+async fn deleted_devices_give_up_their_identifiers() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&ALL_DEVICE_PERMISSIONS);
+    let mut body = device_body("phone");
+    body["barcode"] = "4006381333931".into();
+    let device = app.create_device(&body).await;
+    let id = device["id"].as_str().unwrap();
+    let resp = app
+        .delete_if_match(&format!("/api/v1/devices/{id}"), "*", &token)
+        .await;
+    assert_eq!(resp.status().as_u16(), 204);
+
+    // Act
+    let recreated = app.post("/api/v1/devices", &body, Some(&token)).await;
+    let restored = app
+        .post(
+            &format!("/api/v1/devices/{id}/restore"),
+            &serde_json::json!({}),
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(recreated.status().as_u16(), 201);
+    let recreated: serde_json::Value = recreated.json().await.unwrap();
+    assert_eq!(recreated["sn"], device["sn"]);
+    assert_eq!(recreated["barcode"], device["barcode"]);
+    assert_eq!(restored.status().as_u16(), 409);
+    let resp = app.get("/api/v1/devices/trash", Some(&token)).await;
+    let trash: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(trash["items"][0]["id"], id);
+}
+
+#[tokio::test]
+async fn deleted_devices_leave_the_loans_and_the_comments() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&[
+        "read:devices",
+        "delete:devices",
+        "checkout:device",
+        "reserve:device",
+        "comment:device",
+    ]);
+    let device = app.create_device(&device_body("phone")).await;
+    let id = device["id"].as_str().unwrap();
+    let body = serde_json::json!({
+        "expectedReturnAt": chrono::Utc::now() + chrono::Duration::days(7),
+    });
+    let resp = app
+        .post(
+            &format!("/api/v1/devices/{id}/checkout"),
+            &body,
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 201);
+    let body = serde_json::json!({
+        "startsAt": "2030-01-01T09:00:00Z",
+        "endsAt": "2030-01-01T12:00:00Z",
+    });
+    let resp = app
+        .post(
+            &format!("/api/v1/devices/{id}/reservations"),
+            &body,
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 201);
+    let reservation: serde_json::Value = resp.json().await.unwrap();
+    let body =
+        serde_json::json!({ "body": format!("@{} please return it", app.test_user.username) });
+    let resp = app
+        .post(
+            &format!("/api/v1/devices/{id}/comments"),
+            &body,
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 201);
+
+    // Act
+    let resp = app
+        .delete_if_match(&format!("/api/v1/devices/{id}"), "*", &token)
+        .await;
+    assert_eq!(resp.status().as_u16(), 204);
+
+    // Assert
+    let loans: serde_json::Value = app
+        .get("/api/v1/loans", Some(&token))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(loans["items"], serde_json::json!([]));
+    let mentions: serde_json::Value = app
+        .get("/api/v1/mentions", Some(&token))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(mentions["items"], serde_json::json!([]));
+    let resp = app
+        .post(
+            &format!("/api/v1/devices/{id}/comments"),
+            &body,
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 404);
+    let resp = app
+        .delete(
+            &format!(
+                "/api/v1/devices/{id}/reservations/{}",
+                reservation["id"].as_str().unwrap()
+            ),
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn purging_the_trash_requires_its_own_permission() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&ALL_DEVICE_PERMISSIONS);
+    let admin = app.generate_token(&["read:devices", "update:device", "purge:devices"]);
+    let deleted = app.create_device(&device_body("phone")).await;
+    let kept = app.create_device(&device_body("tablet")).await;
+    let deleted_id = deleted["id"].as_str().unwrap();
+    let attachments_uri = format!("/api/v1/devices/{deleted_id}/attachments");
+    let resp = app
+        .upload_file(
+            &attachments_uri,
+            "logs.txt",
+            "text/plain",
+            b"logs",
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 201);
+    let attachment: serde_json::Value = resp.json().await.unwrap();
+    let resp = app
+        .delete_if_match(&format!("/api/v1/devices/{deleted_id}"), "*", &token)
+        .await;
+    assert_eq!(resp.status().as_u16(), 204);
+    // The retention is cut off at whole seconds
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+
+    // Act
+    let forbidden = app.delete("/api/v1/devices/trash", Some(&token)).await;
+    let resp = app.delete("/api/v1/devices/trash", Some(&admin)).await;
+
+    // Assert
+    assert_eq!(forbidden.status().as_u16(), 403);
+    assert_eq!(resp.status().as_u16(), 200);
+    let purged: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(purged["purged"], serde_json::json!([deleted_id]));
+
+    let resp = app.get("/api/v1/devices/trash", Some(&token)).await;
+    let trash: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(trash["items"], serde_json::json!([]));
+    let resp = app
+        .get(
+            &format!("{attachments_uri}/{}", attachment["id"].as_str().unwrap()),
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 404);
+    let resp = app
+        .get(
+            &format!("/api/v1/devices/{}", kept["id"].as_str().unwrap()),
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 200);
+
+    let resp = app
+        .get(
+            &format!("/api/v1/devices/{deleted_id}/history"),
+            Some(&token),
+        )
+        .await;
+    let history: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(history["items"][2]["action"], "purge");
+}
+
+#[tokio::test]
+async fn get_device_by_barcode_and_sn_works() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["read:devices"]);
+    let mut body = device_body("phone");
+    body["sn"] = "SN-LOOKUP-1".into();
+    body["barcode"] = "4710000000017".into();
+    let device = app.create_device(&body).await;
+
+    // Act
+    let by_barcode = app
+        .get("/api/v1/devices/by-barcode/4710000000017", Some(&token))
+        .await;
+    let by_sn = app
+        .get("/api/v1/devices/by-sn/SN-LOOKUP-1", Some(&token))
+        .await;
+    let unknown = app
+        .get("/api/v1/devices/by-barcode/0000000000000", Some(&token))
+        .await;
+
+    // Assert
+    assert_eq!(by_barcode.status().as_u16(), 200);
+    let found: serde_json::Value = by_barcode.json().await.unwrap();
+    assert_eq!(found["id"], device["id"]);
+    assert_eq!(by_sn.status().as_u16(), 200);
+    let found: serde_json::Value = by_sn.json().await.unwrap();
+    assert_eq!(found["id"], device["id"]);
+    assert_eq!(unknown.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn create_device_with_duplicate_identifier_is_a_conflict() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&ALL_DEVICE_PERMISSIONS);
+    let mut body = device_body("phone");
+    body["sn"] = "SN-DUPLICATE".into();
+    body["barcode"] = "BC-DUPLICATE".into();
+    app.create_device(&body).await;
+
+    // Act
+    let mut same_sn = device_body("tablet");
+    same_sn["sn"] = "SN-DUPLICATE".into();
+    let resp_sn = app.post("/api/v1/devices", &same_sn, Some(&token)).await;
+    let mut same_barcode = device_body("watch");
+    same_barcode["barcode"] = "BC-DUPLICATE".into();
+    let resp_barcode = app
+        .post("/api/v1/devices", &same_barcode, Some(&token))
+        .await;
+
+    // Assert
+    assert_eq!(resp_sn.status().as_u16(), 409);
+    let error: serde_json::Value = resp_sn.json().await.unwrap();
+    assert_eq!(
+        error["errorMessage"],
+        "a device with the serial number `SN-DUPLICATE` already exists"
+    );
+    assert_eq!(resp_barcode.status().as_u16(), 409);
+
+    let resp = app.get("/api/v1/devices", Some(&token)).await;
+    let devices: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(devices["total"], 1);
+}
+
+#[tokio::test]
+async fn blank_identifiers_are_stored_as_missing() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&ALL_DEVICE_PERMISSIONS);
+    let mut bodies = vec![];
+    for name in ["phone", "tablet"] {
+        let mut body = device_body(name);
+        body["sn"] = "".into();
+        body["barcode"] = "  ".into();
+        bodies.push(body);
+    }
+
+    // Act
+    let first = app.post("/api/v1/devices", &bodies[0], Some(&token)).await;
+    let second = app.post("/api/v1/devices", &bodies[1], Some(&token)).await;
+
+    // Assert
+    assert_eq!(first.status().as_u16(), 201);
+    assert_eq!(second.status().as_u16(), 201);
+    let device: serde_json::Value = second.json().await.unwrap();
+    assert_eq!(device["sn"], serde_json::Value::Null);
+    assert_eq!(device["barcode"], serde_json::Value::Null);
+
+    let uri = format!("/api/v1/devices/{}", device["id"].as_str().unwrap());
+    let resp = app.put_if_match(&uri, &bodies[0], "*", &token).await;
+    assert_eq!(resp.status().as_u16(), 200);
+    let updated: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(updated["sn"], serde_json::Value::Null);
+}
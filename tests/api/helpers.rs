@@ -2,30 +2,209 @@ use std::net::TcpListener;
 
 use argon2::{password_hash::SaltString, Argon2, Params, PasswordHasher, Version};
 use devices_backend::{
-    configuration::{get_configuration, DatabaseSettings},
+    configuration::{get_configuration, DatabaseSettings, StorageSettings},
+    models::login::Claims,
     startup::{get_database_connection, run},
 };
 use sqlx::{Connection, Executor, PgConnection, PgPool};
 
 pub struct TestApp {
     pub address: String,
-    pub port: u16,
     pub client: reqwest::Client,
     pub test_user: TestUser,
+    pub jwt_secret: String,
+    /// Stands in for the label printer, it receives the raw ZPL
+    pub printer: TcpListener,
 }
 
 impl TestApp {
-    pub async fn post(&self, uri: &str, body: &serde_json::Value) -> reqwest::Response {
+    pub async fn post(
+        &self,
+        uri: &str,
+        body: &serde_json::Value,
+        token: Option<&str>,
+    ) -> reqwest::Response {
         send_api_request(
             &self.client,
             RequestMethod::Post,
             &self.address,
             uri,
             Some(body),
+            token,
+        )
+        .await
+    }
+
+    pub async fn get(&self, uri: &str, token: Option<&str>) -> reqwest::Response {
+        send_api_request(
+            &self.client,
+            RequestMethod::Get,
+            &self.address,
+            uri,
             None,
+            token,
+        )
+        .await
+    }
+
+    pub async fn put(
+        &self,
+        uri: &str,
+        body: &serde_json::Value,
+        token: Option<&str>,
+    ) -> reqwest::Response {
+        send_api_request(
+            &self.client,
+            RequestMethod::Put,
+            &self.address,
+            uri,
+            Some(body),
+            token,
+        )
+        .await
+    }
+
+    pub async fn delete(&self, uri: &str, token: Option<&str>) -> reqwest::Response {
+        send_api_request(
+            &self.client,
+            RequestMethod::Delete,
+            &self.address,
+            uri,
+            None,
+            token,
+        )
+        .await
+    }
+
+    /// Replace a device based on the version in `if_match`
+    pub async fn put_if_match(
+        &self,
+        uri: &str,
+        body: &serde_json::Value,
+        if_match: &str,
+        token: &str,
+    ) -> reqwest::Response {
+        self.client
+            .put(format!("{}{uri}", self.address))
+            .header(reqwest::header::AUTHORIZATION, token)
+            .header(reqwest::header::IF_MATCH, if_match)
+            .json(body)
+            .send()
+            .await
+            .expect("failed to make a request")
+    }
+
+    /// Delete a device based on the version in `if_match`
+    pub async fn delete_if_match(
+        &self,
+        uri: &str,
+        if_match: &str,
+        token: &str,
+    ) -> reqwest::Response {
+        self.client
+            .delete(format!("{}{uri}", self.address))
+            .header(reqwest::header::AUTHORIZATION, token)
+            .header(reqwest::header::IF_MATCH, if_match)
+            .send()
+            .await
+            .expect("failed to make a request")
+    }
+
+    /// Upload a file as the `file` field of a multipart form
+    pub async fn upload(&self, uri: &str, content: &str, token: Option<&str>) -> reqwest::Response {
+        self.upload_file(
+            uri,
+            "upload",
+            "application/octet-stream",
+            content.as_bytes(),
+            token,
         )
         .await
     }
+
+    /// Upload a named file of a media type as the `file` field of a multipart
+    /// form
+    pub async fn upload_file(
+        &self,
+        uri: &str,
+        file_name: &str,
+        content_type: &str,
+        content: &[u8],
+        token: Option<&str>,
+    ) -> reqwest::Response {
+        const BOUNDARY: &str = "test-boundary";
+        let mut body = format!(
+            "--{BOUNDARY}\r\n\
+             Content-Disposition: form-data; name=\"file\"; filename=\"{file_name}\"\r\n\
+             Content-Type: {content_type}\r\n\r\n"
+        )
+        .into_bytes();
+        body.extend_from_slice(content);
+        body.extend_from_slice(format!("\r\n--{BOUNDARY}--\r\n").as_bytes());
+
+        let mut builder = self
+            .client
+            .post(format!("{}{uri}", self.address))
+            .header(
+                reqwest::header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={BOUNDARY}"),
+            )
+            .body(body);
+        if let Some(token) = token {
+            builder = builder.header(reqwest::header::AUTHORIZATION, token);
+        }
+
+        builder.send().await.expect("failed to make a request")
+    }
+
+    /// Create a device through the API and return the created device
+    pub async fn create_device(&self, body: &serde_json::Value) -> serde_json::Value {
+        let token = self.generate_token(&["create:device"]);
+        let resp = self.post("/api/v1/devices", body, Some(&token)).await;
+        assert_eq!(resp.status().as_u16(), 201, "failed to create a device");
+
+        resp.json().await.expect("failed to decode the device")
+    }
+
+    /// Sign a token for the test user which holds the given permissions
+    pub fn generate_token(&self, permissions: &[&str]) -> String {
+        self.generate_token_for(self.test_user.id, permissions)
+    }
+
+    /// Sign a token for another user which holds the given permissions
+    pub fn generate_token_for(&self, user_id: uuid::Uuid, permissions: &[&str]) -> String {
+        let claims = Claims {
+            sub: user_id.to_string(),
+            exp: (chrono::Utc::now() + chrono::Duration::days(1)).timestamp() as usize,
+            permissions: permissions.iter().map(|p| p.to_string()).collect(),
+            roles: vec![],
+        };
+
+        jsonwebtoken::encode(
+            &jsonwebtoken::Header::default(),
+            &claims,
+            &jsonwebtoken::EncodingKey::from_secret(self.jwt_secret.as_bytes()),
+        )
+        .expect("failed to sign a test token")
+    }
+}
+
+/// The catalog board of the devices made by `device_body`
+pub const DEVICE_BOARD: &str = "main-board";
+
+/// The owner of the devices made by `device_body`, it is the test user
+pub const DEVICE_OWNER: uuid::Uuid =
+    uuid::Uuid::from_u128(0x5eed_0000_0000_4000_8000_0000_0000_0001);
+
+/// A valid body for creating a device
+pub fn device_body(name: &str) -> serde_json::Value {
+    serde_json::json!({
+        "name": name,
+        "ownerId": DEVICE_OWNER,
+        "board": DEVICE_BOARD,
+        "sn": uuid::Uuid::new_v4().to_string(),
+        "hwPhase": "EVT",
+    })
 }
 
 enum RequestMethod {
@@ -83,10 +262,7 @@ async fn send_api_request(
 ) -> reqwest::Response {
     let mut header_map = reqwest::header::HeaderMap::new();
     if let Some(token) = token {
-        header_map.append(
-            reqwest::header::AUTHORIZATION,
-            format!("Bearer {token}").parse().unwrap(),
-        );
+        header_map.append(reqwest::header::AUTHORIZATION, token.parse().unwrap());
     }
     let url = format!("{address}{uri}");
     let builder = match method {
@@ -109,12 +285,22 @@ async fn send_api_request(
 }
 
 pub async fn spawn_app() -> TestApp {
+    let printer = TcpListener::bind("127.0.0.1:0").expect("Can't bind the printer listener");
     let configuration = {
         let mut c = get_configuration().expect("Failed to read a configuration");
         // Use a random port
         c.application.port = 0;
         // Use a different database for each test case
         c.database.database_name = uuid::Uuid::new_v4().to_string();
+        // Send the labels to the fake printer
+        c.printer.host = "127.0.0.1".to_owned();
+        c.printer.port = printer.local_addr().unwrap().port();
+        // Keep the attachments of each test case apart
+        c.attachments.storage = StorageSettings::Local {
+            directory: std::env::temp_dir().join(format!("attachments-{}", uuid::Uuid::new_v4())),
+        };
+        // Let the test cases purge what they just deleted
+        c.trash.retention_days = 0;
         c
     };
 
@@ -128,6 +314,7 @@ pub async fn spawn_app() -> TestApp {
     );
     let listener = TcpListener::bind(address).expect("Can't bind tcp listener");
     let application_port = listener.local_addr().unwrap().port();
+    let jwt_secret = configuration.jwt_secret.secret_key.clone();
 
     tokio::spawn(run(configuration, listener));
 
@@ -139,16 +326,37 @@ pub async fn spawn_app() -> TestApp {
 
     let app = TestApp {
         address: format!("http://127.0.0.1:{application_port}"),
-        port: application_port,
         client,
         test_user: TestUser::generate(),
+        jwt_secret,
+        printer,
     };
 
     app.test_user.store(&db_pool).await;
+    store_board(&db_pool, DEVICE_BOARD).await;
+    store_owner(&db_pool, DEVICE_OWNER, app.test_user.id).await;
 
     app
 }
 
+async fn store_board(pool: &PgPool, name: &str) {
+    sqlx::query("INSERT INTO boards (id, name) VALUES ($1, $2);")
+        .bind(uuid::Uuid::new_v4())
+        .bind(name)
+        .execute(pool)
+        .await
+        .expect("failed to create a test board");
+}
+
+async fn store_owner(pool: &PgPool, id: uuid::Uuid, user_id: uuid::Uuid) {
+    sqlx::query("INSERT INTO owners (id, name, user_id) VALUES ($1, 'test user', $2);")
+        .bind(id)
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .expect("failed to create a test owner");
+}
+
 async fn configure_database(config: &DatabaseSettings) -> PgPool {
     let mut connection = PgConnection::connect_with(&config.without_db())
         .await
@@ -0,0 +1,250 @@
+use crate::helpers::{device_body, spawn_app};
+
+#[tokio::test]
+async fn hw_phases_are_listed_in_order() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["read:devices", "manage:hw-phases"]);
+
+    // Act
+    let created = app
+        .post(
+            "/api/v1/hw-phases",
+            &serde_json::json!({ "name": " EVT2 ", "position": 3 }),
+            Some(&token),
+        )
+        .await;
+    let duplicated = app
+        .post(
+            "/api/v1/hw-phases",
+            &serde_json::json!({ "name": "evt2" }),
+            Some(&token),
+        )
+        .await;
+    let resp = app.get("/api/v1/hw-phases", Some(&token)).await;
+
+    // Assert
+    assert_eq!(created.status().as_u16(), 201);
+    assert_eq!(duplicated.status().as_u16(), 409);
+    assert_eq!(resp.status().as_u16(), 200);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    let names: Vec<_> = body["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["Proto", "EVT", "EVT2", "DVT", "PVT", "MP"]);
+}
+
+#[tokio::test]
+async fn concurrent_hw_phases_take_their_own_positions() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["read:devices", "manage:hw-phases"]);
+
+    // Act
+    let bodies: Vec<_> = (0..5)
+        .map(|i| serde_json::json!({ "name": format!("EVT{i}"), "position": 2 }))
+        .collect();
+    let responses = futures_util::future::join_all(
+        bodies
+            .iter()
+            .map(|body| app.post("/api/v1/hw-phases", body, Some(&token))),
+    )
+    .await;
+
+    // Assert
+    for resp in responses {
+        assert_eq!(resp.status().as_u16(), 201);
+    }
+    let body: serde_json::Value = app
+        .get("/api/v1/hw-phases", Some(&token))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let positions: Vec<_> = body["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["position"].as_i64().unwrap())
+        .collect();
+    assert_eq!(positions, (1..=10).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn hw_phase_in_use_can_not_be_deleted() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["read:devices", "manage:hw-phases"]);
+    app.create_device(&device_body("phone")).await;
+    let body: serde_json::Value = app
+        .get("/api/v1/hw-phases", Some(&token))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let phase_id = |name: &str| {
+        body["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|p| p["name"] == name)
+            .unwrap()["id"]
+            .as_str()
+            .unwrap()
+            .to_owned()
+    };
+
+    // Act
+    let in_use = app
+        .delete(
+            &format!("/api/v1/hw-phases/{}", phase_id("EVT")),
+            Some(&token),
+        )
+        .await;
+    let unused = app
+        .delete(
+            &format!("/api/v1/hw-phases/{}", phase_id("Proto")),
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(in_use.status().as_u16(), 409);
+    assert_eq!(unused.status().as_u16(), 204);
+}
+
+#[tokio::test]
+async fn renaming_a_hw_phase_is_recorded_in_the_device_history() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["read:devices", "manage:hw-phases"]);
+    let device = app.create_device(&device_body("phone")).await;
+    let id = device["id"].as_str().unwrap();
+    let phases: serde_json::Value = app
+        .get("/api/v1/hw-phases", Some(&token))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let evt = phases["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|p| p["name"] == "EVT")
+        .unwrap();
+
+    // Act
+    let resp = app
+        .put(
+            &format!("/api/v1/hw-phases/{}", evt["id"].as_str().unwrap()),
+            &serde_json::json!({ "name": "EVT1", "position": evt["position"] }),
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 200);
+    let device: serde_json::Value = app
+        .get(&format!("/api/v1/devices/{id}"), Some(&token))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(device["hwPhase"], "EVT1");
+    let history: serde_json::Value = app
+        .get(&format!("/api/v1/devices/{id}/history"), Some(&token))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let history = history["items"].as_array().unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[1]["action"], "update");
+    assert_eq!(history[1]["changedBy"], app.test_user.id.to_string());
+    assert_eq!(
+        history[1]["changes"],
+        serde_json::json!({ "hwPhase": { "old": "EVT", "new": "EVT1" } })
+    );
+}
+
+#[tokio::test]
+async fn device_with_unknown_hw_phase_is_rejected() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["create:device"]);
+    let mut body = device_body("phone");
+    body["hwPhase"] = "dvt2".into();
+
+    // Act
+    let resp = app.post("/api/v1/devices", &body, Some(&token)).await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn devices_are_promoted_to_their_next_phase() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["update:device"]);
+    let first = app.create_device(&device_body("phone")).await;
+    let mut body = device_body("tablet");
+    body["hwPhase"] = "DVT".into();
+    let second = app.create_device(&body).await;
+
+    // Act
+    let resp = app
+        .post(
+            "/api/v1/devices/promote",
+            &serde_json::json!({ "ids": [first["id"], second["id"]] }),
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 200);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    let phases: Vec<_> = body["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| (d["id"].clone(), d["hwPhase"].as_str().unwrap().to_owned()))
+        .collect();
+    assert!(phases.contains(&(first["id"].clone(), "DVT".to_owned())));
+    assert!(phases.contains(&(second["id"].clone(), "PVT".to_owned())));
+}
+
+#[tokio::test]
+async fn skipping_hw_phases_requires_the_override_permission() {
+    // Arrange
+    let app = spawn_app().await;
+    let device = app.create_device(&device_body("phone")).await;
+    let body = serde_json::json!({ "ids": [device["id"]], "toPhase": "MP" });
+    let token = app.generate_token(&["update:device"]);
+    let override_token = app.generate_token(&["update:device", "override:hw-phase"]);
+
+    // Act
+    let forbidden = app
+        .post("/api/v1/devices/promote", &body, Some(&token))
+        .await;
+    let allowed = app
+        .post("/api/v1/devices/promote", &body, Some(&override_token))
+        .await;
+    let backwards = app
+        .post(
+            "/api/v1/devices/promote",
+            &serde_json::json!({ "ids": [device["id"]], "toPhase": "EVT" }),
+            Some(&override_token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(forbidden.status().as_u16(), 403);
+    assert_eq!(allowed.status().as_u16(), 200);
+    let body: serde_json::Value = allowed.json().await.unwrap();
+    assert_eq!(body["items"][0]["hwPhase"], "MP");
+    assert_eq!(backwards.status().as_u16(), 409);
+}
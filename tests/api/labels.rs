@@ -0,0 +1,199 @@
+use crate::helpers::{device_body, spawn_app};
+
+#[tokio::test]
+async fn device_label_is_rendered_as_svg_and_png() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["read:devices"]);
+    let mut body = device_body("phone");
+    body["sn"] = "SN-LABEL-1".into();
+    body["barcode"] = "4710000000017".into();
+    let device = app.create_device(&body).await;
+    let id = device["id"].as_str().unwrap();
+
+    // Act
+    let svg = app
+        .get(&format!("/api/v1/devices/{id}/label"), Some(&token))
+        .await;
+    let png = app
+        .get(
+            &format!("/api/v1/devices/{id}/label?kind=code128&format=png"),
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(svg.status().as_u16(), 200);
+    assert_eq!(
+        svg.headers()[reqwest::header::CONTENT_TYPE],
+        "image/svg+xml"
+    );
+    let svg = svg.text().await.unwrap();
+    assert!(svg.contains(">phone</text>"));
+    assert!(svg.contains(">SN: SN-LABEL-1</text>"));
+
+    assert_eq!(png.status().as_u16(), 200);
+    assert_eq!(png.headers()[reqwest::header::CONTENT_TYPE], "image/png");
+    let png = png.bytes().await.unwrap();
+    assert_eq!(&png[1..4], b"PNG");
+}
+
+#[tokio::test]
+async fn device_label_sheet_is_rendered_as_pdf() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["read:devices"]);
+    let mut ids = vec![];
+    for i in 0..30 {
+        let device = app
+            .create_device(&device_body(&format!("device-{i}")))
+            .await;
+        ids.push(device["id"].clone());
+    }
+
+    // Act
+    let resp = app
+        .post(
+            "/api/v1/devices/labels",
+            &serde_json::json!({ "ids": ids, "kind": "qr" }),
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 200);
+    assert_eq!(
+        resp.headers()[reqwest::header::CONTENT_TYPE],
+        "application/pdf"
+    );
+    let pdf = resp.bytes().await.unwrap();
+    assert!(pdf.starts_with(b"%PDF"));
+}
+
+#[tokio::test]
+async fn device_label_sheet_with_unknown_device_is_rejected() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["read:devices"]);
+
+    // Act
+    let resp = app
+        .post(
+            "/api/v1/devices/labels",
+            &serde_json::json!({ "ids": [uuid::Uuid::new_v4()] }),
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn device_labels_are_rendered_as_zpl_with_templates() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["read:devices", "manage:label-templates"]);
+    let mut body = device_body("phone");
+    body["sn"] = "SN_1".into();
+    let device = app.create_device(&body).await;
+    let id = device["id"].as_str().unwrap();
+
+    // Act - the default template
+    let resp = app
+        .get(
+            &format!("/api/v1/devices/{id}/label?format=zpl"),
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 200);
+    let zpl = resp.text().await.unwrap();
+    assert!(zpl.starts_with("^XA"));
+    assert!(zpl.contains(&format!("^FDMA,{id}^FS")));
+    assert!(zpl.contains("^FDSN_5F1^FS"));
+
+    // Act - a custom template for a batch
+    let resp = app
+        .post(
+            "/api/v1/label-templates",
+            &serde_json::json!({
+                "name": "small",
+                "zpl": "^XA^FO10,10^BCN,50^FH^FD{{sn}}^FS^XZ\n",
+            }),
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 201);
+    let template: serde_json::Value = resp.json().await.unwrap();
+    let resp = app
+        .post(
+            "/api/v1/devices/labels",
+            &serde_json::json!({
+                "ids": [id, id],
+                "format": "zpl",
+                "templateId": template["id"],
+            }),
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 200);
+    assert_eq!(
+        resp.text().await.unwrap(),
+        "^XA^FO10,10^BCN,50^FH^FDSN_5F1^FS^XZ\n".repeat(2)
+    );
+}
+
+#[tokio::test]
+async fn label_template_with_unknown_field_is_rejected() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["manage:label-templates"]);
+
+    // Act
+    let resp = app
+        .post(
+            "/api/v1/label-templates",
+            &serde_json::json!({ "name": "broken", "zpl": "^XA^FD{{owner}}^FS^XZ" }),
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn print_device_labels_sends_zpl_to_the_printer() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["print:labels"]);
+    let device = app.create_device(&device_body("phone")).await;
+    let printer = app.printer.try_clone().unwrap();
+    let received = std::thread::spawn(move || {
+        let (mut stream, _) = printer.accept().unwrap();
+        let mut zpl = String::new();
+        std::io::Read::read_to_string(&mut stream, &mut zpl).unwrap();
+        zpl
+    });
+
+    // Act
+    let resp = app
+        .post(
+            "/api/v1/devices/labels/print",
+            &serde_json::json!({ "ids": [device["id"]] }),
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 200);
+    let result: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(result["printed"], 1);
+    let zpl = received.join().unwrap();
+    assert!(zpl.starts_with("^XA"));
+    assert!(zpl.contains("^FDphone^FS"));
+}
@@ -0,0 +1,104 @@
+use crate::helpers::{device_body, spawn_app};
+
+const LOAN_PERMISSIONS: [&str; 3] = ["read:devices", "checkout:device", "checkin:device"];
+
+#[tokio::test]
+async fn checkout_and_checkin_are_kept_in_history() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&LOAN_PERMISSIONS);
+    let device = app.create_device(&device_body("phone")).await;
+    let id = device["id"].as_str().unwrap();
+    let body = serde_json::json!({
+        "expectedReturnAt": chrono::Utc::now() + chrono::Duration::days(7),
+        "note": "thermal test",
+    });
+
+    // Act - check out
+    let resp = app
+        .post(
+            &format!("/api/v1/devices/{id}/checkout"),
+            &body,
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 201);
+    let loan: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(loan["borrowerId"], app.test_user.id.to_string());
+
+    // Act - who has what
+    let resp = app
+        .get(
+            &format!("/api/v1/loans?borrowerId={}", app.test_user.id),
+            Some(&token),
+        )
+        .await;
+    let loans: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(loans["items"].as_array().unwrap().len(), 1);
+
+    // Act - check in
+    let resp = app
+        .post(
+            &format!("/api/v1/devices/{id}/checkin"),
+            &serde_json::json!({}),
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 200);
+
+    // Assert
+    let resp = app
+        .get(&format!("/api/v1/devices/{id}/loans"), Some(&token))
+        .await;
+    let history: serde_json::Value = resp.json().await.unwrap();
+    let history = history["items"].as_array().unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0]["returnedBy"], app.test_user.id.to_string());
+
+    let resp = app.get("/api/v1/loans", Some(&token)).await;
+    let loans: serde_json::Value = resp.json().await.unwrap();
+    assert!(loans["items"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn checkout_a_lent_device_is_a_conflict() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&LOAN_PERMISSIONS);
+    let device = app.create_device(&device_body("phone")).await;
+    let uri = format!(
+        "/api/v1/devices/{}/checkout",
+        device["id"].as_str().unwrap()
+    );
+    let body = serde_json::json!({
+        "expectedReturnAt": chrono::Utc::now() + chrono::Duration::days(1),
+    });
+    let resp = app.post(&uri, &body, Some(&token)).await;
+    assert_eq!(resp.status().as_u16(), 201);
+
+    // Act
+    let resp = app.post(&uri, &body, Some(&token)).await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 409);
+}
+
+#[tokio::test]
+async fn checkin_a_device_which_is_not_lent_is_a_conflict() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&LOAN_PERMISSIONS);
+    let device = app.create_device(&device_body("phone")).await;
+
+    // Act
+    let resp = app
+        .post(
+            &format!("/api/v1/devices/{}/checkin", device["id"].as_str().unwrap()),
+            &serde_json::json!({}),
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 409);
+}
@@ -0,0 +1,199 @@
+use crate::helpers::{device_body, spawn_app, TestApp};
+
+const LOCATION_PERMISSIONS: [&str; 3] = ["read:devices", "update:device", "manage:locations"];
+
+async fn create_location(
+    app: &TestApp,
+    parent: Option<&serde_json::Value>,
+    kind: &str,
+    name: &str,
+    token: &str,
+) -> serde_json::Value {
+    let resp = app
+        .post(
+            "/api/v1/locations",
+            &serde_json::json!({
+                "parentId": parent.map(|parent| &parent["id"]),
+                "kind": kind,
+                "name": name,
+            }),
+            Some(token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 201);
+    resp.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn locations_form_a_tree_from_sites_to_shelves() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&LOCATION_PERMISSIONS);
+    let site = create_location(&app, None, "site", "Taipei", &token).await;
+    let room = create_location(&app, Some(&site), "room", "Lab 3", &token).await;
+    let cabinet = create_location(&app, Some(&room), "cabinet", "C1", &token).await;
+
+    // Act
+    let shelf = create_location(&app, Some(&cabinet), "shelf", "Top", &token).await;
+    let rootless_room = app
+        .post(
+            "/api/v1/locations",
+            &serde_json::json!({ "kind": "room", "name": "Lab 4" }),
+            Some(&token),
+        )
+        .await;
+    let duplicate = app
+        .post(
+            "/api/v1/locations",
+            &serde_json::json!({ "parentId": site["id"], "kind": "room", "name": "Lab 3" }),
+            Some(&token),
+        )
+        .await;
+    let room_uri = format!("/api/v1/locations/{}", room["id"].as_str().unwrap());
+    let under_itself = app
+        .put(
+            &room_uri,
+            &serde_json::json!({ "parentId": shelf["id"], "kind": "room", "name": "Lab 3" }),
+            Some(&token),
+        )
+        .await;
+    let over_its_cabinet = app
+        .put(
+            &room_uri,
+            &serde_json::json!({ "parentId": site["id"], "kind": "shelf", "name": "Lab 3" }),
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(rootless_room.status().as_u16(), 400);
+    assert_eq!(duplicate.status().as_u16(), 409);
+    assert_eq!(under_itself.status().as_u16(), 400);
+    assert_eq!(over_its_cabinet.status().as_u16(), 400);
+
+    let resp = app
+        .get(
+            &format!("/api/v1/locations/{}", shelf["id"].as_str().unwrap()),
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 200);
+    let detail: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(detail["name"], "Top");
+    let path: Vec<_> = detail["path"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|location| location["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(path, ["Taipei", "Lab 3", "C1"]);
+
+    let resp = app.delete(&room_uri, Some(&token)).await;
+    assert_eq!(resp.status().as_u16(), 409);
+}
+
+#[tokio::test]
+async fn devices_under_a_location_are_listed_recursively() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&LOCATION_PERMISSIONS);
+    let site = create_location(&app, None, "site", "Taipei", &token).await;
+    let lab = create_location(&app, Some(&site), "room", "Lab 3", &token).await;
+    let office = create_location(&app, Some(&site), "room", "Office", &token).await;
+    let cabinet = create_location(&app, Some(&lab), "cabinet", "C1", &token).await;
+    let shelf = create_location(&app, Some(&cabinet), "shelf", "Top", &token).await;
+    let placements = [("phone", &shelf), ("tablet", &lab), ("watch", &office)];
+
+    // Act
+    for (name, location) in placements {
+        let device = app.create_device(&device_body(name)).await;
+        let resp = app
+            .post(
+                &format!("/api/v1/devices/{}/move", device["id"].as_str().unwrap()),
+                &serde_json::json!({ "locationId": location["id"] }),
+                Some(&token),
+            )
+            .await;
+        assert_eq!(resp.status().as_u16(), 200);
+    }
+
+    // Assert
+    let resp = app
+        .get(
+            &format!("/api/v1/devices?locationId={}", lab["id"].as_str().unwrap()),
+            Some(&token),
+        )
+        .await;
+    let page: serde_json::Value = resp.json().await.unwrap();
+    let names: Vec<_> = page["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|device| device["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["phone", "tablet"]);
+    assert_eq!(page["items"][0]["locationId"], shelf["id"]);
+
+    let resp = app
+        .get(
+            &format!(
+                "/api/v1/devices?locationId={}",
+                site["id"].as_str().unwrap()
+            ),
+            Some(&token),
+        )
+        .await;
+    let page: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(page["total"], 3);
+}
+
+#[tokio::test]
+async fn moving_a_device_is_recorded_in_its_history() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&LOCATION_PERMISSIONS);
+    let site = create_location(&app, None, "site", "Taipei", &token).await;
+    let room = create_location(&app, Some(&site), "room", "Lab 3", &token).await;
+    let device = app.create_device(&device_body("phone")).await;
+    let device_uri = format!("/api/v1/devices/{}", device["id"].as_str().unwrap());
+
+    // Act
+    let resp = app
+        .post(
+            &format!("{device_uri}/move"),
+            &serde_json::json!({ "locationId": room["id"] }),
+            Some(&token),
+        )
+        .await;
+    let unknown = app
+        .post(
+            &format!("{device_uri}/move"),
+            &serde_json::json!({ "locationId": uuid::Uuid::new_v4() }),
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 200);
+    assert_eq!(unknown.status().as_u16(), 400);
+
+    let resp = app
+        .get(&format!("{device_uri}/history"), Some(&token))
+        .await;
+    let history: serde_json::Value = resp.json().await.unwrap();
+    let entries = history["items"].as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1]["action"], "move");
+    assert_eq!(
+        entries[1]["changes"],
+        serde_json::json!({ "locationId": { "old": null, "new": room["id"] } })
+    );
+
+    let resp = app
+        .delete(
+            &format!("/api/v1/locations/{}", room["id"].as_str().unwrap()),
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 409);
+}
@@ -11,7 +11,7 @@ async fn login_failed() {
     });
 
     // Act
-    let resp = app.post("/api/vi/login", &body).await;
+    let resp = app.post("/api/vi/login", &body, None).await;
 
     // Assert
     assert_eq!(resp.status().as_u16(), 401);
@@ -28,7 +28,7 @@ async fn login_sucess() {
     });
 
     // Act
-    let resp = app.post("/api/vi/login", &body).await;
+    let resp = app.post("/api/vi/login", &body, None).await;
 
     // Assert
     assert_eq!(resp.status().as_u16(), 200);
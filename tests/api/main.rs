@@ -1,3 +1,19 @@
+mod attachments;
+mod boards;
+mod comments;
+mod device_export;
+mod device_import;
+mod device_status;
+mod device_types;
+mod devices;
 mod health_check;
 mod helpers;
+mod hw_phases;
+mod labels;
+mod loans;
+mod locations;
 mod login;
+mod owners;
+mod reservations;
+mod tags;
+mod teams;
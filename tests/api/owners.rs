@@ -0,0 +1,90 @@
+use crate::helpers::{device_body, spawn_app, DEVICE_OWNER};
+
+#[tokio::test]
+async fn owner_is_either_a_user_or_a_team() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["read:devices", "manage:owners", "manage:teams"]);
+    let team: serde_json::Value = app
+        .post(
+            "/api/v1/teams",
+            &serde_json::json!({ "name": "lab" }),
+            Some(&token),
+        )
+        .await
+        .json()
+        .await
+        .unwrap();
+    let team_owner = serde_json::json!({ "name": "the lab", "teamId": team["id"] });
+
+    // Act
+    let created = app.post("/api/v1/owners", &team_owner, Some(&token)).await;
+    let second = app.post("/api/v1/owners", &team_owner, Some(&token)).await;
+    let both = app
+        .post(
+            "/api/v1/owners",
+            &serde_json::json!({
+                "name": "both",
+                "teamId": team["id"],
+                "userId": app.test_user.id,
+            }),
+            Some(&token),
+        )
+        .await;
+    let unknown = app
+        .post(
+            "/api/v1/owners",
+            &serde_json::json!({ "name": "nobody", "userId": uuid::Uuid::new_v4() }),
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(created.status().as_u16(), 201);
+    let owner: serde_json::Value = created.json().await.unwrap();
+    assert_eq!(owner["teamId"], team["id"]);
+    assert_eq!(owner["userId"], serde_json::Value::Null);
+    assert_eq!(second.status().as_u16(), 409);
+    assert_eq!(both.status().as_u16(), 400);
+    assert_eq!(unknown.status().as_u16(), 400);
+
+    let resp = app.get("/api/v1/owners", Some(&token)).await;
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["items"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn owner_of_devices_can_not_be_deleted() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["manage:owners"]);
+    app.create_device(&device_body("phone")).await;
+
+    // Act
+    let resp = app
+        .delete(&format!("/api/v1/owners/{DEVICE_OWNER}"), Some(&token))
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 409);
+    let error: serde_json::Value = resp.json().await.unwrap();
+    assert!(error["errorMessage"]
+        .as_str()
+        .unwrap()
+        .contains("still owns 1 device"));
+}
+
+#[tokio::test]
+async fn device_with_unknown_owner_is_rejected() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["create:device"]);
+    let mut body = device_body("phone");
+    body["ownerId"] = uuid::Uuid::new_v4().to_string().into();
+
+    // Act
+    let resp = app.post("/api/v1/devices", &body, Some(&token)).await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 400);
+}
@@ -0,0 +1,220 @@
+use crate::helpers::{device_body, spawn_app};
+
+const RESERVATION_PERMISSIONS: [&str; 2] = ["read:devices", "reserve:device"];
+
+fn reservation_body(starts_at: &str, ends_at: &str) -> serde_json::Value {
+    serde_json::json!({
+        "startsAt": starts_at,
+        "endsAt": ends_at,
+        "purpose": "thermal campaign",
+    })
+}
+
+#[tokio::test]
+async fn overlapping_reservations_are_rejected() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&RESERVATION_PERMISSIONS);
+    let device = app.create_device(&device_body("phone")).await;
+    let uri = format!(
+        "/api/v1/devices/{}/reservations",
+        device["id"].as_str().unwrap()
+    );
+    let resp = app
+        .post(
+            &uri,
+            &reservation_body("2030-01-01T09:00:00Z", "2030-01-01T12:00:00Z"),
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 201);
+
+    // Act
+    let overlapping = app
+        .post(
+            &uri,
+            &reservation_body("2030-01-01T11:00:00Z", "2030-01-01T13:00:00Z"),
+            Some(&token),
+        )
+        .await;
+    let adjacent = app
+        .post(
+            &uri,
+            &reservation_body("2030-01-01T12:00:00Z", "2030-01-01T13:00:00Z"),
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(overlapping.status().as_u16(), 409);
+    assert_eq!(adjacent.status().as_u16(), 201);
+
+    let resp = app.get(&uri, Some(&token)).await;
+    let schedule: serde_json::Value = resp.json().await.unwrap();
+    let schedule = schedule["items"].as_array().unwrap();
+    assert_eq!(schedule.len(), 2);
+    assert_eq!(schedule[0]["startsAt"], "2030-01-01T09:00:00Z");
+}
+
+#[tokio::test]
+async fn reservation_with_an_empty_window_is_rejected() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&RESERVATION_PERMISSIONS);
+    let device = app.create_device(&device_body("phone")).await;
+
+    let uri = format!(
+        "/api/v1/devices/{}/reservations",
+        device["id"].as_str().unwrap()
+    );
+
+    // Act
+    let reversed = app
+        .post(
+            &uri,
+            &reservation_body("2030-01-01T12:00:00Z", "2030-01-01T09:00:00Z"),
+            Some(&token),
+        )
+        .await;
+    let within_a_second = app
+        .post(
+            &uri,
+            &reservation_body("2030-01-01T09:00:00.200Z", "2030-01-01T09:00:00.700Z"),
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(reversed.status().as_u16(), 400);
+    assert_eq!(within_a_second.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn available_devices_exclude_reserved_ones() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&RESERVATION_PERMISSIONS);
+    let booked = app.create_device(&device_body("booked")).await;
+    app.create_device(&device_body("free")).await;
+    let uri = format!(
+        "/api/v1/devices/{}/reservations",
+        booked["id"].as_str().unwrap()
+    );
+    let resp = app
+        .post(
+            &uri,
+            &reservation_body("2030-01-01T09:00:00Z", "2030-01-01T12:00:00Z"),
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 201);
+
+    // Act
+    let resp = app
+        .get(
+            "/api/v1/devices/available?from=2030-01-01T10:00:00Z&to=2030-01-01T11:00:00Z",
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 200);
+    let available: serde_json::Value = resp.json().await.unwrap();
+
+    // Assert
+    let names: Vec<_> = available["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| d["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["free"]);
+
+    // Act - cancel the booking
+    let reservation_id = {
+        let resp = app.get(&uri, Some(&token)).await;
+        let schedule: serde_json::Value = resp.json().await.unwrap();
+        schedule["items"][0]["id"].as_str().unwrap().to_owned()
+    };
+    let resp = app
+        .delete(&format!("{uri}/{reservation_id}"), Some(&token))
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 204);
+}
+
+#[tokio::test]
+async fn available_devices_are_paginated() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&RESERVATION_PERMISSIONS);
+    for name in ["phone", "tablet", "watch"] {
+        app.create_device(&device_body(name)).await;
+    }
+    let uri = "/api/v1/devices/available?from=2030-01-01T10:00:00Z&to=2030-01-01T11:00:00Z";
+
+    // Act
+    let resp = app.get(&format!("{uri}&limit=2"), Some(&token)).await;
+    assert_eq!(resp.status().as_u16(), 200);
+    let first: serde_json::Value = resp.json().await.unwrap();
+    let cursor = first["nextCursor"].as_str().unwrap();
+    let resp = app
+        .get(&format!("{uri}&limit=2&cursor={cursor}"), Some(&token))
+        .await;
+    assert_eq!(resp.status().as_u16(), 200);
+    let second: serde_json::Value = resp.json().await.unwrap();
+    let too_large = app.get(&format!("{uri}&limit=501"), Some(&token)).await;
+
+    // Assert
+    let names = |page: &serde_json::Value| -> Vec<String> {
+        page["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|d| d["name"].as_str().unwrap().to_owned())
+            .collect()
+    };
+    assert_eq!(names(&first), ["phone", "tablet"]);
+    assert_eq!(names(&second), ["watch"]);
+    assert_eq!(second["nextCursor"], serde_json::Value::Null);
+    assert_eq!(too_large.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn only_the_booker_or_a_manager_cancels_a_reservation() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&RESERVATION_PERMISSIONS);
+    let someone_else = app.generate_token_for(uuid::Uuid::new_v4(), &RESERVATION_PERMISSIONS);
+    let manager = app.generate_token_for(
+        uuid::Uuid::new_v4(),
+        &["reserve:device", "manage:reservations"],
+    );
+    let device = app.create_device(&device_body("phone")).await;
+    let uri = format!(
+        "/api/v1/devices/{}/reservations",
+        device["id"].as_str().unwrap()
+    );
+    let mut reservations = vec![];
+    for (starts_at, ends_at) in [
+        ("2030-01-01T09:00:00Z", "2030-01-01T10:00:00Z"),
+        ("2030-01-01T10:00:00Z", "2030-01-01T11:00:00Z"),
+    ] {
+        let resp = app
+            .post(&uri, &reservation_body(starts_at, ends_at), Some(&token))
+            .await;
+        let reservation: serde_json::Value = resp.json().await.unwrap();
+        reservations.push(format!("{uri}/{}", reservation["id"].as_str().unwrap()));
+    }
+
+    // Act
+    let forbidden = app.delete(&reservations[0], Some(&someone_else)).await;
+    let by_booker = app.delete(&reservations[0], Some(&token)).await;
+    let by_manager = app.delete(&reservations[1], Some(&manager)).await;
+    let missing = app.delete(&reservations[1], Some(&manager)).await;
+
+    // Assert
+    assert_eq!(forbidden.status().as_u16(), 403);
+    assert_eq!(by_booker.status().as_u16(), 204);
+    assert_eq!(by_manager.status().as_u16(), 204);
+    assert_eq!(missing.status().as_u16(), 404);
+}
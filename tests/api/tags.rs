@@ -0,0 +1,184 @@
+use crate::helpers::{device_body, spawn_app, TestApp};
+
+const TAG_PERMISSIONS: [&str; 3] = ["read:devices", "update:device", "manage:tags"];
+
+async fn device_names(app: &TestApp, query: &str, token: &str) -> Vec<String> {
+    let resp = app
+        .get(&format!("/api/v1/devices?{query}"), Some(token))
+        .await;
+    assert_eq!(resp.status().as_u16(), 200);
+    let page: serde_json::Value = resp.json().await.unwrap();
+    let mut names: Vec<String> = page["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|device| device["name"].as_str().unwrap().to_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn tagging_a_device_creates_the_missing_tags() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&TAG_PERMISSIONS);
+    let device = app.create_device(&device_body("phone")).await;
+    let uri = format!("/api/v1/devices/{}/tags", device["id"].as_str().unwrap());
+
+    // Act
+    let resp = app
+        .post(
+            &uri,
+            &serde_json::json!({ "tags": [" Thermal-Test", "customer-demo", "thermal-test"] }),
+            Some(&token),
+        )
+        .await;
+    let invalid = app
+        .post(
+            &uri,
+            &serde_json::json!({ "tags": ["broken usb"] }),
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 200);
+    let tags: serde_json::Value = resp.json().await.unwrap();
+    let names: Vec<_> = tags["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tag| tag["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["customer-demo", "thermal-test"]);
+    assert_eq!(invalid.status().as_u16(), 400);
+
+    let resp = app
+        .delete(&format!("{uri}/thermal-test"), Some(&token))
+        .await;
+    assert_eq!(resp.status().as_u16(), 204);
+    let resp = app
+        .delete(&format!("{uri}/thermal-test"), Some(&token))
+        .await;
+    assert_eq!(resp.status().as_u16(), 404);
+
+    let resp = app.get("/api/v1/tags", Some(&token)).await;
+    let usage: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(usage["items"][0]["name"], "customer-demo");
+    assert_eq!(usage["items"][0]["deviceCount"], 1);
+    assert_eq!(usage["items"][1]["name"], "thermal-test");
+    assert_eq!(usage["items"][1]["deviceCount"], 0);
+}
+
+#[tokio::test]
+async fn devices_are_filtered_by_any_or_all_tags() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&TAG_PERMISSIONS);
+    let phone = app.create_device(&device_body("phone")).await;
+    let tablet = app.create_device(&device_body("tablet")).await;
+    app.create_device(&device_body("watch")).await;
+
+    // Act
+    let resp = app
+        .post(
+            "/api/v1/devices/tags",
+            &serde_json::json!({
+                "deviceIds": [phone["id"], tablet["id"]],
+                "add": ["thermal-test", "broken-usb"],
+            }),
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 204);
+    let resp = app
+        .post(
+            "/api/v1/devices/tags",
+            &serde_json::json!({
+                "deviceIds": [tablet["id"]],
+                "add": ["customer-demo"],
+                "remove": ["broken-usb"],
+            }),
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 204);
+
+    // Assert
+    assert_eq!(
+        device_names(&app, "tags=broken-usb,customer-demo", &token).await,
+        ["phone", "tablet"]
+    );
+    assert_eq!(
+        device_names(&app, "tags=thermal-test,broken-usb&tagMatch=all", &token).await,
+        ["phone"]
+    );
+    assert_eq!(
+        device_names(&app, "tags=Customer-Demo&tagMatch=all", &token).await,
+        ["tablet"]
+    );
+    assert_eq!(device_names(&app, "tags=", &token).await.len(), 3);
+
+    let resp = app.get("/api/v1/tags", Some(&token)).await;
+    let usage: serde_json::Value = resp.json().await.unwrap();
+    let counts: Vec<_> = usage["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tag| {
+            (
+                tag["name"].as_str().unwrap(),
+                tag["deviceCount"].as_i64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        counts,
+        [("broken-usb", 1), ("customer-demo", 1), ("thermal-test", 2)]
+    );
+}
+
+#[tokio::test]
+async fn bulk_tagging_an_unknown_device_changes_nothing() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&TAG_PERMISSIONS);
+    let phone = app.create_device(&device_body("phone")).await;
+
+    // Act
+    let resp = app
+        .post(
+            "/api/v1/devices/tags",
+            &serde_json::json!({
+                "deviceIds": [phone["id"], uuid::Uuid::new_v4()],
+                "add": ["thermal-test"],
+            }),
+            Some(&token),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 400);
+    let resp = app.get("/api/v1/tags", Some(&token)).await;
+    let usage: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(usage["items"], serde_json::json!([]));
+
+    // Deleting a tag removes it from every device
+    let resp = app
+        .post(
+            "/api/v1/devices/tags",
+            &serde_json::json!({ "deviceIds": [phone["id"]], "add": ["thermal-test"] }),
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 204);
+    let resp = app.get("/api/v1/tags", Some(&token)).await;
+    let usage: serde_json::Value = resp.json().await.unwrap();
+    let tag_uri = format!("/api/v1/tags/{}", usage["items"][0]["id"].as_str().unwrap());
+    let resp = app.delete(&tag_uri, Some(&token)).await;
+    assert_eq!(resp.status().as_u16(), 204);
+    assert!(device_names(&app, "tags=thermal-test", &token)
+        .await
+        .is_empty());
+}
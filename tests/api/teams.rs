@@ -0,0 +1,174 @@
+use devices_backend::models::login::Claims;
+
+use crate::helpers::{spawn_app, TestApp};
+
+/// The user created by the migrations
+const ADMIN_USER_ID: &str = "391afe4c-6c47-4719-bc1f-3aca3600b8db";
+
+/// Create a team through the API and return its id
+async fn create_team(app: &TestApp, permissions: &[&str]) -> String {
+    let token = app.generate_token(&["manage:teams"]);
+    let resp = app
+        .post(
+            "/api/v1/teams",
+            &serde_json::json!({ "name": "lab", "permissions": permissions }),
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 201, "failed to create a team");
+
+    let team: serde_json::Value = resp.json().await.unwrap();
+    team["id"].as_str().unwrap().to_owned()
+}
+
+#[tokio::test]
+async fn team_is_created_and_listed() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&["manage:teams"]);
+    let body = serde_json::json!({
+        "name": "lab",
+        "description": "the hardware lab",
+        "permissions": ["read:devices", "checkout:device", "read:devices"],
+    });
+
+    // Act
+    let created = app.post("/api/v1/teams", &body, Some(&token)).await;
+    let duplicated = app.post("/api/v1/teams", &body, Some(&token)).await;
+    let resp = app.get("/api/v1/teams", Some(&token)).await;
+
+    // Assert
+    assert_eq!(created.status().as_u16(), 201);
+    let team: serde_json::Value = created.json().await.unwrap();
+    assert_eq!(
+        team["permissions"],
+        serde_json::json!(["checkout:device", "read:devices"])
+    );
+    assert_eq!(duplicated.status().as_u16(), 409);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    let names: Vec<_> = body["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|team| team["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names.len(), 2);
+    assert!(names.contains(&"lab"));
+}
+
+#[tokio::test]
+async fn the_admin_user_manages_the_seeded_admin_team() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.generate_token(&[]);
+
+    // Act
+    let resp = app.get("/api/v1/teams", Some(&token)).await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 200);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    let team = body["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|team| team["name"] == "Administrators")
+        .unwrap();
+    assert!(team["permissions"]
+        .as_array()
+        .unwrap()
+        .contains(&"manage:teams".into()));
+    let resp = app
+        .get(
+            &format!("/api/v1/teams/{}/members", team["id"].as_str().unwrap()),
+            Some(&token),
+        )
+        .await;
+    let members: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(members["items"][0]["userId"], ADMIN_USER_ID);
+    assert_eq!(members["items"][0]["role"], "manager");
+}
+
+#[tokio::test]
+async fn team_membership_flows_into_the_login_token() {
+    // Arrange
+    let app = spawn_app().await;
+    let team_id = create_team(&app, &["read:devices"]).await;
+    let token = app.generate_token(&["manage:teams"]);
+    let resp = app
+        .post(
+            &format!("/api/v1/teams/{team_id}/members"),
+            &serde_json::json!({ "userId": app.test_user.id, "role": "manager" }),
+            Some(&token),
+        )
+        .await;
+    assert_eq!(resp.status().as_u16(), 200);
+
+    // Act
+    let resp = app
+        .post(
+            "/api/v1/login",
+            &serde_json::json!({
+                "username": &app.test_user.username,
+                "password": &app.test_user.password,
+            }),
+            None,
+        )
+        .await;
+
+    // Assert
+    assert_eq!(resp.status().as_u16(), 200);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    let token = body["token"].as_str().unwrap();
+    let claims = jsonwebtoken::decode::<Claims>(
+        token,
+        &jsonwebtoken::DecodingKey::from_secret(app.jwt_secret.as_bytes()),
+        &jsonwebtoken::Validation::default(),
+    )
+    .unwrap()
+    .claims;
+    assert_eq!(claims.permissions, ["read:devices"]);
+    assert_eq!(claims.roles, [format!("team:{team_id}:manager")]);
+
+    let resp = app.get("/api/v1/devices", Some(token)).await;
+    assert_eq!(resp.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn only_managers_change_the_members() {
+    // Arrange
+    let app = spawn_app().await;
+    let team_id = create_team(&app, &[]).await;
+    let admin_token = app.generate_token(&["manage:teams"]);
+    let token = app.generate_token(&[]);
+    let members = format!("/api/v1/teams/{team_id}/members");
+    let add_test_user =
+        |role: &str| serde_json::json!({ "userId": app.test_user.id, "role": role });
+    app.post(&members, &add_test_user("member"), Some(&admin_token))
+        .await;
+
+    // Act
+    let as_member = app
+        .delete(&format!("{members}/{}", app.test_user.id), Some(&token))
+        .await;
+    app.post(&members, &add_test_user("manager"), Some(&admin_token))
+        .await;
+    let unknown_user = app
+        .post(
+            &members,
+            &serde_json::json!({ "userId": uuid::Uuid::new_v4() }),
+            Some(&token),
+        )
+        .await;
+    let as_manager = app
+        .delete(&format!("{members}/{}", app.test_user.id), Some(&token))
+        .await;
+
+    // Assert
+    assert_eq!(as_member.status().as_u16(), 403);
+    assert_eq!(unknown_user.status().as_u16(), 400);
+    assert_eq!(as_manager.status().as_u16(), 204);
+    let resp = app.get(&members, Some(&token)).await;
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert!(body["items"].as_array().unwrap().is_empty());
+}